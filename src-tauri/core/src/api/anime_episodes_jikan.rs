@@ -0,0 +1,282 @@
+use reqwest::Client;
+use std::collections::HashMap;
+use std::fmt;
+
+use crate::api::models::{
+    JikanAnimeDetailsResponse, JikanAnimeDetailsWrapper, JikanEpisodeResponse, JikanSearchResponse,
+    ProviderEpisode, SeasonedEpisodes, ShowDetailsWithEpisodes,
+};
+
+// Custom error type for better error handling
+#[derive(Debug)]
+pub enum FetchError {
+    RequestError(reqwest::Error),
+    ParsingError(serde_json::Error),
+}
+
+impl fmt::Display for FetchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FetchError::RequestError(err) => write!(f, "Request Error: {}", err),
+            FetchError::ParsingError(err) => write!(f, "Parsing Error: {}", err),
+        }
+    }
+}
+
+impl From<reqwest::Error> for FetchError {
+    fn from(error: reqwest::Error) -> Self {
+        FetchError::RequestError(error)
+    }
+}
+
+impl From<serde_json::Error> for FetchError {
+    fn from(error: serde_json::Error) -> Self {
+        FetchError::ParsingError(error)
+    }
+}
+
+pub async fn fetch_jikan_show_details(
+    anime_id: Option<i32>,
+    anime_name: Option<String>,
+    year: Option<i32>,
+) -> Result<ShowDetailsWithEpisodes, String> {
+    let client = Client::new();
+    let url: String;
+    let anime_details: JikanAnimeDetailsResponse;
+
+    if let Some(id) = anime_id {
+        // Fetch anime details by ID
+        let anime_url = format!("https://api.jikan.moe/v4/anime/{}", id);
+        let anime_response = client
+            .get(&anime_url)
+            .send()
+            .await
+            .map_err(|e| format!("Failed to fetch anime by ID: {}", e))?;
+
+        let response_text = anime_response
+            .text()
+            .await
+            .map_err(|e| format!("Failed to read response body: {}", e))?;
+
+        let anime_json: JikanAnimeDetailsWrapper = serde_json::from_str(&response_text)
+            .map_err(|e| format!("Failed to parse anime details response: {}", e))?;
+        anime_details = anime_json.data.clone();
+
+        // Proceed to fetch episodes
+        url = format!(
+            "https://api.jikan.moe/v4/anime/{}/episodes",
+            anime_details.mal_id
+        );
+    } else if let Some(name) = anime_name {
+        // Search anime by name
+        let search_url = format!("https://api.jikan.moe/v4/anime?q={}", name);
+        let search_response = client
+            .get(&search_url)
+            .send()
+            .await
+            .map_err(|e| format!("Failed to search anime by name: {}", e))?;
+
+        let search_json: JikanSearchResponse = search_response
+            .json()
+            .await
+            .map_err(|e| format!("Failed to parse search response: {}", e))?;
+
+        // Ensure we have results
+        if search_json.data.is_empty() {
+            return Err("No matching anime found.".to_string());
+        }
+
+        // Filter by year if provided
+        let filtered_anime = if let Some(search_year) = year {
+            search_json.data.iter().find(|anime| {
+                anime
+                    .aired
+                    .from
+                    .as_ref()
+                    .map_or(false, |from| from.starts_with(&search_year.to_string()))
+            })
+        } else {
+            // No year provided, fallback to the first result
+            search_json.data.first()
+        };
+
+        if let Some(anime_details_json) = filtered_anime {
+            let anime_id_from_search = anime_details_json.mal_id;
+
+            let anime_url = format!("https://api.jikan.moe/v4/anime/{}", anime_id_from_search);
+            let anime_response =
+                client.get(&anime_url).send().await.map_err(|e| {
+                    format!("Failed to fetch anime by ID from search result: {}", e)
+                })?;
+
+            let response_text = anime_response
+                .text()
+                .await
+                .map_err(|e| format!("Failed to read response body: {}", e))?;
+
+            let anime_json: JikanAnimeDetailsWrapper = serde_json::from_str(&response_text)
+                .map_err(|e| {
+                    format!(
+                        "Failed to parse anime details response from search result: {}",
+                        e
+                    )
+                })?;
+
+            anime_details = anime_json.data.clone();
+
+            url = format!(
+                "https://api.jikan.moe/v4/anime/{}/episodes",
+                anime_details.mal_id
+            );
+        } else {
+            return Err("No matching anime found with the given year.".to_string());
+        }
+    } else {
+        return Err("You must provide either an anime ID or an anime name.".into());
+    }
+
+    // Fetch episodes from Jikan
+    let response = client
+        .get(&url)
+        .send()
+        .await
+        .map_err(|e| format!("Failed to fetch episodes: {}", e))?;
+
+    let raw_response = response
+        .text()
+        .await
+        .map_err(|e| format!("Failed to read response body: {}", e))?;
+
+    let response_json: JikanEpisodeResponse = serde_json::from_str(&raw_response)
+        .map_err(|e| format!("Failed to parse episodes response: {}", e))?;
+
+    // Group episodes by release year (extracted from aired field)
+    let mut seasons_map: HashMap<i32, SeasonedEpisodes> = HashMap::new();
+    for (i, episode) in response_json.data.iter().enumerate() {
+        let aired_date = episode
+            .aired
+            .clone()
+            .unwrap_or_else(|| "Unknown".to_string());
+
+        let year: i32 = aired_date
+            .split('-')
+            .next()
+            .unwrap_or("0")
+            .parse()
+            .unwrap_or(0);
+
+        let season = seasons_map.entry(year).or_insert_with(|| SeasonedEpisodes {
+            season: year,
+            start_episode: i as i32 + 1,
+            end_episode: 0,
+            titles: vec![],
+        });
+
+        season.titles.push(
+            episode
+                .title
+                .clone()
+                .unwrap_or_else(|| "Unknown Title".to_string()),
+        );
+        season.end_episode = i as i32 + 1;
+    }
+
+    // Convert the HashMap to a Vec of SeasonedEpisodes
+    let mut seasons: Vec<SeasonedEpisodes> = seasons_map.into_values().collect();
+    seasons.sort_by_key(|s| s.season);
+
+    // Extract the premiered year from the anime details
+    let premiered_year = anime_details.aired.as_ref().and_then(|aired| {
+        aired
+            .from
+            .as_ref()
+            .map(|from| from.split('-').next().unwrap_or("").to_string())
+    });
+
+    // Return show details with grouped episodes
+    Ok(ShowDetailsWithEpisodes {
+        id: anime_details.mal_id,
+        name: anime_details.title,
+        premiered_year,
+        episodes_by_season: seasons,
+    })
+}
+
+// Fetches every episode of an anime with its air date. Jikan has no seasons,
+// so the episode number is also the absolute number.
+pub async fn fetch_jikan_episodes(anime_id: i32) -> Result<Vec<ProviderEpisode>, String> {
+    let client = Client::new();
+    let mut episodes = Vec::new();
+    let mut page = 1;
+
+    loop {
+        let url = format!(
+            "https://api.jikan.moe/v4/anime/{}/episodes?page={}",
+            anime_id, page
+        );
+        let response_json: JikanEpisodeResponse = client
+            .get(&url)
+            .send()
+            .await
+            .map_err(|e| format!("Failed to fetch episodes: {}", e))?
+            .json()
+            .await
+            .map_err(|e| format!("Failed to parse episodes response: {}", e))?;
+
+        for episode in response_json.data {
+            let number = episode
+                .url
+                .as_deref()
+                .and_then(episode_number_from_url)
+                .unwrap_or(episodes.len() as i32 + 1);
+            episodes.push(ProviderEpisode {
+                id: episode.mal_id,
+                season: None,
+                number,
+                absolute: number,
+                title: episode.title,
+                // "2002-10-03T00:00:00+00:00" -> "2002-10-03"
+                air_date: episode.aired.map(|aired| aired.chars().take(10).collect()),
+            });
+        }
+
+        let has_next_page = response_json
+            .pagination
+            .map_or(false, |pagination| pagination.has_next_page);
+        if !has_next_page {
+            break;
+        }
+        page += 1;
+
+        // Jikan allows about three requests per second
+        tokio::time::sleep(std::time::Duration::from_millis(400)).await;
+    }
+
+    Ok(episodes)
+}
+
+// "https://myanimelist.net/anime/21/One_Piece/episode/12" -> 12
+fn episode_number_from_url(url: &str) -> Option<i32> {
+    let (path, number) = url.trim_end_matches('/').rsplit_once('/')?;
+    if !path.ends_with("/episode") {
+        return None;
+    }
+    number.parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_the_episode_number_from_the_url() {
+        assert_eq!(
+            episode_number_from_url("https://myanimelist.net/anime/21/One_Piece/episode/12"),
+            Some(12)
+        );
+        assert_eq!(
+            episode_number_from_url("https://myanimelist.net/anime/21/One_Piece"),
+            None
+        );
+    }
+}
@@ -0,0 +1,245 @@
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+use crate::api::models::ProviderEpisode;
+
+// Struct to hold episode information
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TVDBEpisode {
+    id: Option<i32>,
+    aired_season: Option<i32>,         // Season number
+    aired_episode_number: Option<i32>, // Episode number
+    absolute_number: Option<i32>,
+    episode_name: Option<String>, // Episode title
+    first_aired: Option<String>,
+}
+
+// Struct to hold the response for episodes
+#[derive(Debug, Serialize, Deserialize)]
+struct TVDBEpisodeResponse {
+    data: Vec<TVDBEpisode>,
+    links: TVDBLinks, // Pagination links
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct TVDBLinks {
+    next: Option<i32>,
+}
+
+// Struct to hold search result for finding TV shows by name
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TVDBSearchResult {
+    id: i32,
+    series_name: String,
+}
+
+// Struct for handling the search response
+#[derive(Debug, Serialize, Deserialize)]
+struct TVDBSearchResponse {
+    data: Vec<TVDBSearchResult>,
+}
+
+// Struct for holding episodes grouped by season
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SeasonedEpisodes {
+    season: i32,
+    start_episode: i32,
+    end_episode: i32,
+    titles: Vec<String>,
+}
+
+// Custom error type for error handling
+#[derive(Debug)]
+pub enum FetchError {
+    RequestError(reqwest::Error),
+    ParsingError(serde_json::Error),
+    //NotFoundError(String),
+}
+
+impl fmt::Display for FetchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FetchError::RequestError(err) => write!(f, "Request Error: {}", err),
+            FetchError::ParsingError(err) => write!(f, "Parsing Error: {}", err),
+            //FetchError::NotFoundError(message) => write!(f, "Not Found Error: {}", message),
+        }
+    }
+}
+
+impl From<reqwest::Error> for FetchError {
+    fn from(error: reqwest::Error) -> Self {
+        FetchError::RequestError(error)
+    }
+}
+
+impl From<serde_json::Error> for FetchError {
+    fn from(error: serde_json::Error) -> Self {
+        FetchError::ParsingError(error)
+    }
+}
+
+// Fetches the episode titles from TheTVDB, grouped by season
+pub async fn fetch_tvdb_episode_titles_grouped_by_season(
+    tvdb_api_key: String,
+    anime_id: Option<i32>,
+    anime_name: Option<String>,
+    year: Option<i32>,
+) -> Result<Vec<SeasonedEpisodes>, String> {
+    let client = Client::new();
+    let url: String;
+
+    // Search by show ID or name
+    if let Some(id) = anime_id {
+        url = format!("https://api.thetvdb.com/series/{}/episodes", id);
+    } else if let Some(name) = anime_name {
+        let search_url = if let Some(year) = year {
+            format!(
+                "https://api.thetvdb.com/search/series?name={}&year={}",
+                name, year
+            )
+        } else {
+            format!("https://api.thetvdb.com/search/series?name={}", name)
+        };
+
+        let search_response = client
+            .get(&search_url)
+            .header("Authorization", format!("Bearer {}", tvdb_api_key))
+            .send()
+            .await
+            .map_err(|e| format!("Failed to search show by name: {}", e))?;
+
+        let search_json: TVDBSearchResponse = search_response
+            .json()
+            .await
+            .map_err(|e| format!("Failed to parse search response: {}", e))?;
+
+        let series_id = search_json
+            .data
+            .first()
+            .ok_or("No matching show found.")?
+            .id;
+
+        url = format!("https://api.thetvdb.com/series/{}/episodes", series_id);
+    } else {
+        return Err("You must provide either a show ID or a show name.".into());
+    }
+
+    // Fetch episodes from TheTVDB
+    let response = client
+        .get(&url)
+        .header("Authorization", format!("Bearer {}", tvdb_api_key))
+        .send()
+        .await
+        .map_err(|e| format!("Failed to fetch episodes: {}", e))?;
+
+    let raw_response = response
+        .text()
+        .await
+        .map_err(|e| format!("Failed to read response body: {}", e))?;
+
+    // Parse the response
+    let response_json: TVDBEpisodeResponse = serde_json::from_str(&raw_response)
+        .map_err(|e| format!("Failed to parse episodes response: {}", e))?;
+
+    // Extract episode data
+    let episode_data = &response_json.data;
+
+    if episode_data.is_empty() {
+        return Ok(vec![]); // Return empty result if no data
+    }
+
+    // Group episodes by season
+    let mut seasons_map = std::collections::HashMap::new();
+
+    for episode in episode_data.iter() {
+        if let (Some(season), Some(episode_name)) = (episode.aired_season, &episode.episode_name) {
+            // Add episode title to the corresponding season in the map
+            let season_entry = seasons_map
+                .entry(season)
+                .or_insert_with(|| SeasonedEpisodes {
+                    season,
+                    start_episode: 0, // Not relevant for your current structure
+                    end_episode: 0,   // Not relevant for your current structure
+                    titles: vec![],
+                });
+            season_entry.titles.push(episode_name.clone());
+        }
+    }
+
+    // Convert HashMap to Vec<SeasonedEpisodes>
+    let mut seasons: Vec<SeasonedEpisodes> = seasons_map.into_values().collect();
+    seasons.sort_by_key(|s| s.season); // Sort by season number
+
+    if seasons.is_empty() {
+        return Err("No episodes found.".to_string());
+    }
+
+    Ok(seasons)
+}
+
+// Fetches every episode of a series, page by page. Episodes without an absolute
+// number are counted in season and episode order.
+pub async fn fetch_tvdb_episodes(
+    tvdb_api_key: &str,
+    series_id: i32,
+) -> Result<Vec<ProviderEpisode>, String> {
+    let client = Client::new();
+    let mut tvdb_episodes = Vec::new();
+    let mut page = 1;
+
+    loop {
+        let url = format!(
+            "https://api.thetvdb.com/series/{}/episodes?page={}",
+            series_id, page
+        );
+        let response_json: TVDBEpisodeResponse = client
+            .get(&url)
+            .header("Authorization", format!("Bearer {}", tvdb_api_key))
+            .send()
+            .await
+            .map_err(|e| format!("Failed to fetch episodes: {}", e))?
+            .json()
+            .await
+            .map_err(|e| format!("Failed to parse episodes response: {}", e))?;
+
+        tvdb_episodes.extend(response_json.data);
+        match response_json.links.next {
+            Some(next) if next > page => page = next,
+            _ => break,
+        }
+    }
+
+    let mut tvdb_episodes: Vec<(i32, i32, TVDBEpisode)> = tvdb_episodes
+        .into_iter()
+        .filter_map(|episode| {
+            Some((
+                episode.aired_season?,
+                episode.aired_episode_number?,
+                episode,
+            ))
+        })
+        .collect();
+    tvdb_episodes.sort_by_key(|(season, number, _)| (*season, *number));
+
+    let mut absolute = 0;
+    Ok(tvdb_episodes
+        .into_iter()
+        .map(|(season, number, episode)| {
+            // Specials in season 0 have no place in the absolute order
+            if season > 0 {
+                absolute += 1;
+            }
+            ProviderEpisode {
+                id: episode.id,
+                season: Some(season),
+                number,
+                absolute: episode.absolute_number.unwrap_or(absolute),
+                title: episode.episode_name,
+                air_date: episode.first_aired.filter(|date| !date.is_empty()),
+            }
+        })
+        .collect())
+}
@@ -0,0 +1,43 @@
+use serde::Deserialize;
+
+use crate::api::anime_episodes_jikan::fetch_jikan_episodes;
+use crate::api::anime_episodes_thetvdb::fetch_tvdb_episodes;
+use crate::api::anime_episodes_tvmaze::fetch_tvmaze_episodes;
+use crate::api::models::ProviderEpisode;
+
+// Providers that can be asked for the episodes of a show by its ID
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum MetadataProvider {
+    Tvmaze,
+    Jikan,
+    Tvdb, // Needs an API key
+}
+
+impl MetadataProvider {
+    pub fn name(&self) -> &'static str {
+        match self {
+            MetadataProvider::Tvmaze => "TVMaze",
+            MetadataProvider::Jikan => "Jikan",
+            MetadataProvider::Tvdb => "TheTVDB",
+        }
+    }
+
+    pub async fn fetch_episodes(
+        &self,
+        show_id: i32,
+        tvdb_api_key: Option<&str>,
+    ) -> Result<Vec<ProviderEpisode>, String> {
+        match self {
+            MetadataProvider::Tvmaze => fetch_tvmaze_episodes(show_id).await,
+            MetadataProvider::Jikan => fetch_jikan_episodes(show_id).await,
+            MetadataProvider::Tvdb => {
+                let api_key = tvdb_api_key
+                    .map(str::trim)
+                    .filter(|key| !key.is_empty())
+                    .ok_or("TheTVDB needs an API key.")?;
+                fetch_tvdb_episodes(api_key, show_id).await
+            }
+        }
+    }
+}
@@ -0,0 +1,6 @@
+pub mod anime_episodes_jikan;
+pub mod anime_episodes_thetvdb;
+pub mod anime_episodes_tvmaze;
+pub mod metadata_provider;
+pub mod models;
+pub mod movies_tmdb;
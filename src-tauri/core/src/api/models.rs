@@ -26,6 +26,8 @@ pub struct JikanAired {
 // Struct to hold episode information
 #[derive(Debug, Serialize, Deserialize)]
 pub struct JikanEpisode {
+    pub mal_id: Option<i32>,
+    pub url: Option<String>, // Ends with the episode number, ".../episode/12"
     pub title: Option<String>,
     pub aired: Option<String>,
 }
@@ -34,6 +36,13 @@ pub struct JikanEpisode {
 #[derive(Debug, Serialize, Deserialize)]
 pub struct JikanEpisodeResponse {
     pub data: Vec<JikanEpisode>,
+    pub pagination: Option<JikanPagination>,
+}
+
+// Struct for the paging of long episode lists
+#[derive(Debug, Serialize, Deserialize)]
+pub struct JikanPagination {
+    pub has_next_page: bool,
 }
 
 // Struct to hold anime search result
@@ -74,10 +83,22 @@ pub struct ShowDetailsWithEpisodes {
     pub episodes_by_season: Vec<SeasonedEpisodes>,
 }
 
+// Struct to hold a single episode from any provider, used to match local files.
+// `season` is None for providers without seasons, `absolute` counts from 1 over all episodes.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ProviderEpisode {
+    pub id: Option<i32>,
+    pub season: Option<i32>,
+    pub number: i32,
+    pub absolute: i32,
+    pub title: Option<String>,
+    pub air_date: Option<String>,
+}
 
 // Struct to hold episode information from TVMaze API
 #[derive(Debug, Serialize, Deserialize)]
 pub struct TVMazeEpisode {
+    pub id: Option<i32>,
     pub season: i32,
     pub number: i32,
     pub name: Option<String>,
@@ -97,3 +118,17 @@ pub struct TVMazeShow {
 pub struct TVMazeSearchResponse {
     pub show: TVMazeShow,
 }
+
+// Struct to hold a movie from the TMDB search
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TMDBMovie {
+    pub id: i32,
+    pub title: String,
+    pub release_date: Option<String>, // "1982-06-25", empty for unreleased movies
+}
+
+// Struct for the movie search response from TMDB
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TMDBMovieSearchResponse {
+    pub results: Vec<TMDBMovie>,
+}
@@ -0,0 +1,47 @@
+use reqwest::Client;
+
+use crate::api::models::{TMDBMovie, TMDBMovieSearchResponse};
+
+impl TMDBMovie {
+    // "1982" from the release date
+    pub fn year(&self) -> Option<String> {
+        self.release_date
+            .as_deref()
+            .and_then(|date| date.get(..4))
+            .map(str::to_string)
+    }
+}
+
+// Searches TMDB for a movie by title, the year narrows the search if it is known.
+// Returns the best match, or None if TMDB knows no such movie.
+pub async fn search_tmdb_movie(
+    client: &Client,
+    tmdb_api_key: &str,
+    title: &str,
+    year: Option<&str>,
+) -> Result<Option<TMDBMovie>, String> {
+    let mut query = vec![("api_key", tmdb_api_key), ("query", title)];
+    if let Some(year) = year {
+        query.push(("year", year));
+    }
+
+    let response = client
+        .get("https://api.themoviedb.org/3/search/movie")
+        .query(&query)
+        .send()
+        .await
+        .map_err(|e| format!("Failed to search movie: {}", e))?;
+    if !response.status().is_success() {
+        return Err(format!(
+            "TMDB refused the movie search: {}",
+            response.status()
+        ));
+    }
+
+    let search_json: TMDBMovieSearchResponse = response
+        .json()
+        .await
+        .map_err(|e| format!("Failed to parse movie search response: {}", e))?;
+
+    Ok(search_json.results.into_iter().next())
+}
@@ -0,0 +1,846 @@
+// Runs the operations of the app from a shell, e.g. from cron or over SSH on a NAS.
+// Renaming commands apply their plan right away and are recorded for undo, like an
+// applied preview in the app. With --dry-run they only print the plan.
+
+use script_kiddie_core::api::anime_episodes_jikan::fetch_jikan_show_details;
+use script_kiddie_core::api::anime_episodes_thetvdb::fetch_tvdb_episode_titles_grouped_by_season;
+use script_kiddie_core::api::anime_episodes_tvmaze::fetch_tvmaze_show_details;
+use script_kiddie_core::api::metadata_provider::MetadataProvider;
+use script_kiddie_core::api::models::{SeasonedEpisodes, ShowDetailsWithEpisodes};
+use script_kiddie_core::explorer::absolute_numbering::{NumberingConversion, SeasonBoundaries};
+use script_kiddie_core::explorer::duplicates::find_duplicates;
+use script_kiddie_core::explorer::filename_policy::load_sanitize_profile;
+use script_kiddie_core::explorer::filename_template::FilenameTemplate;
+use script_kiddie_core::explorer::flatten::{flatten_plan, FlattenOptions};
+use script_kiddie_core::explorer::journal::{
+    preview_redo, preview_undo, redo_operation, undo_last_operation,
+};
+use script_kiddie_core::explorer::media_files::{
+    format_size, list_file_sizes, list_media_files, FileInfo,
+};
+use script_kiddie_core::explorer::name_transform::NameTransform;
+use script_kiddie_core::explorer::operations::{
+    add_titles_to_episodes_plan, adjust_episode_numbers_plan, adjust_season_numbers_plan,
+    apply_plan, check_episode_adjustment, check_episode_range_remap, check_season_adjustment,
+    clean_release_names_plan, confirm_movies, convert_absolute_numbering_plan,
+    current_episode_names, flatten_single_file_directories_plan, folders_to_process, movie_files,
+    number_files_plan, organize_into_show_folders_plan, organize_movies_plan, organize_videos_plan,
+    plan_for_folders, provider_titles_plan, remap_episode_range_plan, rename_with_template_plan,
+    search_and_replace_plan, transform_file_names_plan, EpisodeRangeRemap, DEFAULT_TITLE_SEPARATOR,
+};
+use script_kiddie_core::explorer::release_name::{
+    load_cleanup_rules, CleanupRules, ReleaseNameParser,
+};
+use script_kiddie_core::explorer::rename_plan::{
+    PreviewEntry, PreviewStatus, RenamePlan, RenameReport,
+};
+use script_kiddie_core::explorer::search_replace::{FileNameSearch, SearchOptions};
+use script_kiddie_core::explorer::selection::{FileSelection, SelectionOptions};
+use script_kiddie_core::explorer::sequential_numbering::SequentialNumbering;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+use std::process;
+use std::str::FromStr;
+use tokio_util::sync::CancellationToken;
+
+const USAGE: &str = "Usage: script-kiddie <command> [options] [directory]
+
+The directory defaults to the current directory.
+
+Renaming commands:
+  titles            Add episode titles from a provider (--provider tvmaze|jikan|tvdb,
+                    --show-id, --tvdb-key, --separator)
+  add-titles        Add episode titles from a JSON file of seasons (--titles, --separator)
+  template          Rename with a template (--template, show options, --separator)
+  search-replace    Replace text in file names (--search, --replace, --regex,
+                    --case-insensitive, --first-match-only, --stem-only, --all-files)
+  transform         Change the case or characters of names (--transform, repeatable:
+                    title_case, lower_case, upper_case, nfc, nfkc, ascii, separators_to_spaces)
+  clean             Clean release names (--keep, --remove-word, both repeatable,
+                    the saved rules without them)
+  adjust-episodes   Shift episode numbers (--by)
+  adjust-seasons    Shift season numbers (--by)
+  remap             Move episodes to another season (--season, --first, --last,
+                    --to-season, --to-first)
+  absolute          Convert numbering (--to seasonal|absolute, show options)
+  number            Number files without an episode tag (--season, --start, --step,
+                    --padding, --order natural|modified|size, --show-name)
+  organize-videos   Move every video into a folder of its own
+  flatten-single    Move videos out of folders that hold nothing else
+  organize-shows    File episodes into show and season folders (show options)
+  organize-movies   File movies as \"Title (Year)\" (--tmdb-key)
+  flatten           Move files up out of subfolders (--depth, --sidecars, --samples,
+                    --junk: bring|delete|leave, --conflict skip|suffix|overwrite)
+  undo              Undo the last operation, --dry-run only shows it
+  redo              Redo the last undone operation, --dry-run only shows it
+
+Other commands:
+  episode-titles    List the titles in the file names (--separator)
+  list-media        List every video file below the directory
+  sizes             List the entries of the directory by size
+  duplicates        List files with the same content
+  fetch             Show the details of a show (--provider tvmaze|jikan|tvdb,
+                    --show-id or --show-name, --year, --tvdb-key)
+
+Show options: --provider tvmaze|jikan with --show-id or --show-name and --year
+
+Options:
+  --depth N         Also process subfolders N levels deep (levels to flatten for flatten)
+  --dry-run         Only print what would be renamed
+  --json            Print JSON instead of text
+  --select PATH     Only touch this file, or the files in this folder (repeatable)
+  --include GLOB    Only touch files whose name matches, e.g. \"*S01E0[1-5]*\" (repeatable)
+  --exclude GLOB    Leave files whose name matches untouched (repeatable)";
+
+// Flags without a value
+const SWITCHES: [&str; 8] = [
+    "dry-run",
+    "json",
+    "regex",
+    "case-insensitive",
+    "first-match-only",
+    "stem-only",
+    "all-files",
+    "help",
+];
+
+// Flags that take a value, repeatable ones collect every value
+const VALUE_FLAGS: [&str; 33] = [
+    "depth",
+    "provider",
+    "show-id",
+    "show-name",
+    "year",
+    "tvdb-key",
+    "tmdb-key",
+    "titles",
+    "separator",
+    "template",
+    "search",
+    "replace",
+    "transform",
+    "keep",
+    "remove-word",
+    "by",
+    "season",
+    "first",
+    "last",
+    "to-season",
+    "to-first",
+    "to",
+    "start",
+    "step",
+    "padding",
+    "order",
+    "sidecars",
+    "samples",
+    "junk",
+    "conflict",
+    "select",
+    "include",
+    "exclude",
+];
+
+// STRUCTS
+
+struct Args {
+    command: String,
+    directory: PathBuf,
+    switches: Vec<String>,
+    values: HashMap<String, Vec<String>>,
+}
+
+#[derive(Serialize)]
+struct SizeEntry {
+    #[serde(flatten)]
+    file: FileInfo,
+    size: u64,
+}
+
+// START ARGUMENTS
+
+impl Args {
+    fn parse(raw: Vec<String>) -> Result<Self, String> {
+        let mut command = None;
+        let mut directory = None;
+        let mut switches = Vec::new();
+        let mut values: HashMap<String, Vec<String>> = HashMap::new();
+
+        let mut raw = raw.into_iter();
+        while let Some(arg) = raw.next() {
+            if let Some(flag) = arg.strip_prefix("--") {
+                // "--flag=value" and "--flag value" are the same
+                let (flag, inline_value) = match flag.find('=') {
+                    Some(index) => (&flag[..index], Some(flag[index + 1..].to_string())),
+                    None => (flag, None),
+                };
+                if SWITCHES.contains(&flag) {
+                    switches.push(flag.to_string());
+                } else if VALUE_FLAGS.contains(&flag) {
+                    let value = match inline_value {
+                        Some(value) => value,
+                        None => raw
+                            .next()
+                            .ok_or_else(|| format!("--{} needs a value", flag))?,
+                    };
+                    values.entry(flag.to_string()).or_default().push(value);
+                } else {
+                    return Err(format!("Unknown option --{}", flag));
+                }
+            } else if command.is_none() {
+                command = Some(arg);
+            } else if directory.is_none() {
+                directory = Some(PathBuf::from(arg));
+            } else {
+                return Err(format!("Unexpected argument {}", arg));
+            }
+        }
+
+        // The journal needs absolute paths without "." or "..", undo may run from another
+        // directory or from the app
+        let current_dir = env::current_dir().map_err(|e| e.to_string())?;
+        let directory = match directory {
+            Some(directory) => current_dir.join(directory),
+            None => current_dir,
+        };
+        let directory = fs::canonicalize(&directory)
+            .map_err(|e| format!("Cannot open {}: {}", directory.display(), e))?;
+        if !directory.is_dir() {
+            return Err(format!("{} is not a directory", directory.display()));
+        }
+
+        Ok(Self {
+            command: command.unwrap_or_else(|| "help".to_string()),
+            directory,
+            switches,
+            values,
+        })
+    }
+
+    fn switch(&self, flag: &str) -> bool {
+        self.switches.iter().any(|switch| switch == flag)
+    }
+
+    fn value(&self, flag: &str) -> Option<&str> {
+        self.values
+            .get(flag)
+            .and_then(|values| values.last())
+            .map(String::as_str)
+    }
+
+    fn all_values(&self, flag: &str) -> Vec<&str> {
+        self.values
+            .get(flag)
+            .map(|values| values.iter().map(String::as_str).collect())
+            .unwrap_or_default()
+    }
+
+    fn required(&self, flag: &str) -> Result<&str, String> {
+        self.value(flag)
+            .ok_or_else(|| format!("{} needs --{}", self.command, flag))
+    }
+
+    fn number<T: FromStr>(&self, flag: &str) -> Result<Option<T>, String> {
+        self.value(flag)
+            .map(|value| {
+                value
+                    .parse()
+                    .map_err(|_| format!("--{} needs a number, not {}", flag, value))
+            })
+            .transpose()
+    }
+
+    fn required_number<T: FromStr>(&self, flag: &str) -> Result<T, String> {
+        self.number(flag)?
+            .ok_or_else(|| format!("{} needs --{}", self.command, flag))
+    }
+
+    // Enum values have the names the app sends, e.g. "title_case" or "suffix"
+    fn choice<T: DeserializeOwned>(&self, flag: &str) -> Result<Option<T>, String> {
+        self.value(flag)
+            .map(|value| parse_choice(flag, value))
+            .transpose()
+    }
+
+    // The files the renaming commands touch, every file without --select, --include or --exclude
+    fn selection(&self) -> Result<FileSelection, String> {
+        let current_dir = env::current_dir().map_err(|e| e.to_string())?;
+        let to_strings = |flag| -> Vec<String> {
+            self.all_values(flag)
+                .into_iter()
+                .map(str::to_string)
+                .collect()
+        };
+        // Canonical like the directory, or "../Show/ep.mkv" would never match a file in it
+        let paths = self
+            .all_values("select")
+            .into_iter()
+            .map(|path| {
+                let path = current_dir.join(path);
+                fs::canonicalize(&path)
+                    .map_err(|e| format!("Cannot open {}: {}", path.display(), e))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        FileSelection::new(SelectionOptions {
+            paths,
+            include: to_strings("include"),
+            exclude: to_strings("exclude"),
+        })
+    }
+
+    fn title_separator(&self) -> String {
+        self.value("separator")
+            .unwrap_or(DEFAULT_TITLE_SEPARATOR)
+            .to_string()
+    }
+}
+
+fn parse_choice<T: DeserializeOwned>(flag: &str, value: &str) -> Result<T, String> {
+    serde_json::from_value(serde_json::Value::String(value.to_string()))
+        .map_err(|_| format!("Invalid value for --{}: {}", flag, value))
+}
+
+// END ARGUMENTS
+
+// START OUTPUT
+
+fn print_json<T: Serialize>(value: &T) -> Result<(), String> {
+    let json = serde_json::to_string_pretty(value).map_err(|e| e.to_string())?;
+    println!("{}", json);
+    Ok(())
+}
+
+fn print_preview(entries: &[PreviewEntry]) {
+    for entry in entries {
+        let status = match entry.status {
+            PreviewStatus::Changed => "rename",
+            PreviewStatus::Unchanged => "keep",
+            PreviewStatus::Skipped => "skip",
+            PreviewStatus::Conflict => "conflict",
+            PreviewStatus::Deleted => "delete",
+        };
+        let moved = entry.proposed_path.parent() != entry.original_path.parent();
+        let target = if moved {
+            entry.proposed_path.display().to_string()
+        } else {
+            entry.proposed_name.clone()
+        };
+
+        match entry.status {
+            PreviewStatus::Changed => {
+                println!("{:<9}{} -> {}", status, entry.original_name, target)
+            }
+            _ => println!("{:<9}{}", status, entry.original_name),
+        }
+        if let Some(reason) = &entry.reason {
+            println!("{:<9}  {}", "", reason);
+        }
+        for note in &entry.notes {
+            println!("{:<9}  {}", "", note);
+        }
+    }
+}
+
+fn print_report(report: &RenameReport) {
+    for operation in &report.renamed {
+        println!("{} -> {}", operation.from.display(), operation.to.display());
+    }
+    for path in &report.deleted {
+        println!("Deleted {}", path.display());
+    }
+    println!(
+        "{} renamed, {} folders created, {} folders removed, {} deleted",
+        report.renamed.len(),
+        report.created_dirs.len(),
+        report.removed_dirs.len(),
+        report.deleted.len()
+    );
+    for warning in &report.warnings {
+        eprintln!("Warning: {}", warning);
+    }
+}
+
+// Prints the plan with --dry-run, applies it otherwise
+fn finish_plan(args: &Args, label: &str, mut plan: RenamePlan) -> Result<(), String> {
+    if args.switch("dry-run") {
+        // The names as they would end up on the file system
+        plan.sanitize_targets(load_sanitize_profile()?);
+        let entries = plan.preview();
+        return if args.switch("json") {
+            print_json(&entries)
+        } else {
+            print_preview(&entries);
+            Ok(())
+        };
+    }
+
+    let report = apply_plan(plan, label)?;
+    finish_report(args, &report)
+}
+
+fn finish_report(args: &Args, report: &RenameReport) -> Result<(), String> {
+    if args.switch("json") {
+        print_json(report)?;
+    } else {
+        print_report(report);
+    }
+
+    match &report.failed {
+        Some(failure) => Err(format!(
+            "Failed at {}: {}. All changes were rolled back.",
+            failure.path.display(),
+            failure.error
+        )),
+        None => Ok(()),
+    }
+}
+
+// END OUTPUT
+
+// START SHOWS
+
+async fn fetch_show(args: &Args) -> Result<ShowDetailsWithEpisodes, String> {
+    let show_id = args.number("show-id")?;
+    let show_name = args.value("show-name").map(str::to_string);
+    let year = args.number("year")?;
+
+    match args.required("provider")? {
+        "tvmaze" => fetch_tvmaze_show_details(show_id, show_name, year).await,
+        "jikan" => fetch_jikan_show_details(show_id, show_name, year).await,
+        other => Err(format!("Unknown provider {}, use tvmaze or jikan", other)),
+    }
+}
+
+// Show details are optional for commands that also work with the file names alone
+async fn optional_show(args: &Args) -> Result<Option<ShowDetailsWithEpisodes>, String> {
+    if args.value("provider").is_some() {
+        fetch_show(args).await.map(Some)
+    } else {
+        Ok(None)
+    }
+}
+
+fn print_seasons(seasons: &[SeasonedEpisodes]) {
+    for season in seasons {
+        println!("Season {}", season.season);
+        for (index, title) in season.titles.iter().enumerate() {
+            println!("  E{:02} {}", index + 1, title);
+        }
+    }
+}
+
+// END SHOWS
+
+// START COMMANDS
+
+async fn run(args: &Args) -> Result<(), String> {
+    let directory = args.directory.as_path();
+    let depth = args.number("depth")?;
+    let selection = args.selection()?;
+
+    match args.command.as_str() {
+        "titles" => {
+            let provider: MetadataProvider = parse_choice("provider", args.required("provider")?)?;
+            let show_id = args.required_number("show-id")?;
+            let episodes = provider
+                .fetch_episodes(show_id, args.value("tvdb-key"))
+                .await?;
+            if episodes.is_empty() {
+                return Err(format!(
+                    "{} has no episodes for show {}",
+                    provider.name(),
+                    show_id
+                ));
+            }
+            let title_separator = args.title_separator();
+            let folders = folders_to_process(directory, depth)?;
+            let plan = plan_for_folders(&folders, |folder| {
+                provider_titles_plan(folder, provider, &episodes, &title_separator, &selection)
+            })
+            .map_err(|e| format!("Failed to build rename plan: {}", e))?;
+            finish_plan(args, &format!("Add titles from {}", provider.name()), plan)
+        }
+        "add-titles" => {
+            let path = args.required("titles")?;
+            let content = std::fs::read_to_string(path)
+                .map_err(|e| format!("Failed to read {}: {}", path, e))?;
+            let episodes_by_season: Vec<SeasonedEpisodes> = serde_json::from_str(&content)
+                .map_err(|e| format!("Failed to parse {}: {}", path, e))?;
+            let title_separator = args.title_separator();
+            let folders = folders_to_process(directory, depth)?;
+            let plan = plan_for_folders(&folders, |folder| {
+                add_titles_to_episodes_plan(
+                    folder,
+                    &episodes_by_season,
+                    &title_separator,
+                    &selection,
+                )
+            })
+            .map_err(|e| format!("Failed to build rename plan: {:?}", e))?;
+            finish_plan(args, "Add episode titles", plan)
+        }
+        "template" => {
+            let template =
+                FilenameTemplate::parse(args.required("template")?).map_err(|e| e.to_string())?;
+            let show_details = optional_show(args).await?;
+            let folders = folders_to_process(directory, depth)?;
+            let plan = plan_for_folders(&folders, |folder| {
+                rename_with_template_plan(
+                    folder,
+                    &template,
+                    show_details.as_ref(),
+                    &args.title_separator(),
+                    &selection,
+                )
+            })
+            .map_err(|e| format!("Failed to build rename plan: {:?}", e))?;
+            finish_plan(args, "Rename with template", plan)
+        }
+        "search-replace" => {
+            let options = SearchOptions {
+                regex: args.switch("regex"),
+                case_insensitive: args.switch("case-insensitive"),
+                first_match_only: args.switch("first-match-only"),
+                include_extension: !args.switch("stem-only"),
+                all_files: args.switch("all-files"),
+            };
+            let search = FileNameSearch::new(
+                args.required("search")?,
+                args.value("replace").unwrap_or_default(),
+                options,
+            )?;
+            let folders = folders_to_process(directory, depth)?;
+            let plan = plan_for_folders(&folders, |folder| {
+                search_and_replace_plan(folder, &search, &selection)
+            })
+            .map_err(|e| format!("Failed to build rename plan: {}", e))?;
+            finish_plan(args, "Search and replace", plan)
+        }
+        "transform" => {
+            let transforms = args
+                .all_values("transform")
+                .into_iter()
+                .map(|value| parse_choice("transform", value))
+                .collect::<Result<Vec<NameTransform>, String>>()?;
+            if transforms.is_empty() {
+                return Err("Select at least one --transform.".to_string());
+            }
+            let folders = folders_to_process(directory, depth)?;
+            let plan = plan_for_folders(&folders, |folder| {
+                transform_file_names_plan(folder, &transforms, &selection)
+            })
+            .map_err(|e| format!("Failed to build rename plan: {}", e))?;
+            finish_plan(args, "Transform file names", plan)
+        }
+        "clean" => {
+            let keep = args.all_values("keep");
+            let remove_words = args.all_values("remove-word");
+            let rules = if keep.is_empty() && remove_words.is_empty() {
+                load_cleanup_rules()?
+            } else {
+                CleanupRules {
+                    keep: keep
+                        .into_iter()
+                        .map(|value| parse_choice("keep", value))
+                        .collect::<Result<_, String>>()?,
+                    remove_words: remove_words.into_iter().map(str::to_string).collect(),
+                }
+            };
+            let parser = ReleaseNameParser::new();
+            let folders = folders_to_process(directory, depth)?;
+            let plan = plan_for_folders(&folders, |folder| {
+                clean_release_names_plan(folder, &parser, &rules, &selection)
+            })
+            .map_err(|e| format!("Failed to build rename plan: {}", e))?;
+            finish_plan(args, "Clean release names", plan)
+        }
+        "adjust-episodes" => {
+            let adjustment_value = args.required_number("by")?;
+            let folders = folders_to_process(directory, depth)?;
+            check_episode_adjustment(&folders, adjustment_value, &selection)?;
+            let plan = plan_for_folders(&folders, |folder| {
+                adjust_episode_numbers_plan(folder, adjustment_value, &selection)
+            })
+            .map_err(|e| format!("Failed to build rename plan: {:?}", e))?;
+            finish_plan(args, "Adjust episode numbers", plan)
+        }
+        "adjust-seasons" => {
+            let adjustment_value = args.required_number("by")?;
+            let folders = folders_to_process(directory, depth)?;
+            check_season_adjustment(&folders, adjustment_value, &selection)?;
+            let plan = plan_for_folders(&folders, |folder| {
+                adjust_season_numbers_plan(folder, adjustment_value, &selection)
+            })
+            .map_err(|e| format!("Failed to build rename plan: {:?}", e))?;
+            finish_plan(args, "Adjust season numbers", plan)
+        }
+        "remap" => {
+            let remap = EpisodeRangeRemap {
+                season: args.required_number("season")?,
+                first_episode: args.required_number("first")?,
+                last_episode: args.required_number("last")?,
+                target_season: args.required_number("to-season")?,
+                target_first_episode: args.number("to-first")?.unwrap_or(1),
+            };
+            check_episode_range_remap(&remap)?;
+            let folders = folders_to_process(directory, depth)?;
+            let plan = plan_for_folders(&folders, |folder| {
+                remap_episode_range_plan(folder, &remap, &selection)
+            })
+            .map_err(|e| format!("Failed to build rename plan: {:?}", e))?;
+            finish_plan(args, "Move episodes to another season", plan)
+        }
+        "absolute" => {
+            let conversion = match args.required("to")? {
+                "seasonal" => NumberingConversion::ToSeasonal,
+                "absolute" => NumberingConversion::ToAbsolute,
+                other => {
+                    return Err(format!(
+                        "Invalid value for --to: {}, use seasonal or absolute",
+                        other
+                    ))
+                }
+            };
+            let show_details = fetch_show(args).await?;
+            let boundaries = SeasonBoundaries::from_seasons(&show_details.episodes_by_season)?;
+            let folders = folders_to_process(directory, depth)?;
+            let plan = plan_for_folders(&folders, |folder| {
+                convert_absolute_numbering_plan(folder, &boundaries, conversion, &selection)
+            })
+            .map_err(|e| format!("Failed to build rename plan: {:?}", e))?;
+            finish_plan(args, "Convert episode numbering", plan)
+        }
+        "number" => {
+            let defaults = SequentialNumbering::default();
+            let numbering = SequentialNumbering {
+                season: args.number("season")?.unwrap_or(defaults.season),
+                start: args.number("start")?.unwrap_or(defaults.start),
+                step: args.number("step")?.unwrap_or(defaults.step),
+                padding: args.number("padding")?.unwrap_or(defaults.padding),
+                order: args.choice("order")?.unwrap_or(defaults.order),
+                custom_order: defaults.custom_order,
+                show_name: args.value("show-name").map(str::to_string),
+            };
+            numbering.check()?;
+            let folders = folders_to_process(directory, depth)?;
+            let plan = plan_for_folders(&folders, |folder| {
+                number_files_plan(folder, &numbering, &selection)
+            })
+            .map_err(|e| format!("Failed to build rename plan: {}", e))?;
+            finish_plan(args, "Number files", plan)
+        }
+        "organize-videos" => {
+            let plan = organize_videos_plan(directory, &selection)?;
+            finish_plan(args, "Organize videos into directories", plan)
+        }
+        "flatten-single" => {
+            let plan = flatten_single_file_directories_plan(directory, &selection)?;
+            finish_plan(args, "Flatten single file directories", plan)
+        }
+        "organize-shows" => {
+            let show_details = optional_show(args).await?;
+            // Season boundaries turn absolute numbers like "Show - 37" into seasons
+            let boundaries = show_details.as_ref().and_then(|details| {
+                SeasonBoundaries::from_seasons(&details.episodes_by_season).ok()
+            });
+            let folders = folders_to_process(directory, depth)?;
+            let plan = plan_for_folders(&folders, |folder| {
+                organize_into_show_folders_plan(
+                    folder,
+                    directory,
+                    show_details.as_ref(),
+                    boundaries.as_ref(),
+                    &selection,
+                )
+            })
+            .map_err(|e| format!("Failed to build rename plan: {}", e))?;
+            finish_plan(args, "Organize into show folders", plan)
+        }
+        "organize-movies" => {
+            let folders = folders_to_process(directory, depth)?;
+            let parser = ReleaseNameParser::new();
+            let files = movie_files(&folders, &selection)
+                .map_err(|e| format!("Failed to read movies: {}", e))?;
+            let confirmed = confirm_movies(&files, &parser, args.value("tmdb-key")).await?;
+            let plan = organize_movies_plan(&files, directory, &parser, confirmed.as_ref())
+                .map_err(|e| format!("Failed to build rename plan: {}", e))?;
+            finish_plan(args, "Organize movies", plan)
+        }
+        "flatten" => {
+            let defaults = FlattenOptions::default();
+            let options = FlattenOptions {
+                depth: depth.unwrap_or(defaults.depth),
+                sidecars: args.choice("sidecars")?.unwrap_or(defaults.sidecars),
+                samples: args.choice("samples")?.unwrap_or(defaults.samples),
+                junk: args.choice("junk")?.unwrap_or(defaults.junk),
+                conflict: args.choice("conflict")?.unwrap_or(defaults.conflict),
+            };
+            if options.depth == 0 {
+                return Err("The depth has to be at least 1.".to_string());
+            }
+            let plan = flatten_plan(directory, &options, &selection)
+                .map_err(|e| format!("Failed to build rename plan: {}", e))?;
+            finish_plan(args, "Flatten directories", plan)
+        }
+        "undo" | "redo" if args.switch("dry-run") => {
+            // Only shows the batch, the journal and the files stay as they are
+            let (label, entries) = if args.command == "undo" {
+                preview_undo()?
+            } else {
+                preview_redo()?
+            };
+            if args.switch("json") {
+                return print_json(&entries);
+            }
+            let action = if args.command == "undo" {
+                "Would undo"
+            } else {
+                "Would redo"
+            };
+            println!("{}: {}", action, label);
+            print_preview(&entries);
+            Ok(())
+        }
+        "undo" | "redo" => {
+            let (label, report) = if args.command == "undo" {
+                undo_last_operation()?
+            } else {
+                redo_operation()?
+            };
+            if !args.switch("json") {
+                let action = if args.command == "undo" {
+                    "Undone"
+                } else {
+                    "Redone"
+                };
+                println!("{}: {}", action, label);
+            }
+            finish_report(args, &report)
+        }
+        "episode-titles" => {
+            let seasons = current_episode_names(directory, &args.title_separator())?;
+            if args.switch("json") {
+                return print_json(&seasons);
+            }
+            print_seasons(&seasons);
+            Ok(())
+        }
+        "list-media" => {
+            let media_files = list_media_files(directory, &CancellationToken::new())?;
+            for skipped in &media_files.skipped {
+                eprintln!("Skipped: {}", skipped);
+            }
+            if args.switch("json") {
+                return print_json(&media_files.files);
+            }
+            for file in &media_files.files {
+                println!("{}", file.path);
+            }
+            Ok(())
+        }
+        "sizes" => {
+            let sizes = list_file_sizes(directory, &CancellationToken::new())?;
+            if args.switch("json") {
+                let sizes: Vec<SizeEntry> = sizes
+                    .into_iter()
+                    .map(|(file, size)| SizeEntry { file, size })
+                    .collect();
+                return print_json(&sizes);
+            }
+            for (file, size) in &sizes {
+                println!("{:>12}  {}", format_size(*size), file.name);
+            }
+            Ok(())
+        }
+        "duplicates" => {
+            let report = find_duplicates(directory, &CancellationToken::new(), |_| {})?;
+            if args.switch("json") {
+                return print_json(&report);
+            }
+            for set in &report.sets {
+                println!(
+                    "{} each, {} wasted",
+                    format_size(set.size),
+                    format_size(set.wasted)
+                );
+                for path in &set.paths {
+                    println!("  {}", path.display());
+                }
+            }
+            for skipped in &report.skipped {
+                eprintln!("Skipped: {}", skipped);
+            }
+            println!(
+                "{} files scanned, {} wasted",
+                report.scanned_files,
+                format_size(report.wasted)
+            );
+            Ok(())
+        }
+        "fetch" => {
+            if args.required("provider")? == "tvdb" {
+                let seasons = fetch_tvdb_episode_titles_grouped_by_season(
+                    args.required("tvdb-key")?.to_string(),
+                    args.number("show-id")?,
+                    args.value("show-name").map(str::to_string),
+                    args.number("year")?,
+                )
+                .await?;
+                if args.switch("json") {
+                    return print_json(&seasons);
+                }
+                // Same fields as the seasons of the other providers
+                let seasons: Vec<SeasonedEpisodes> = serde_json::to_value(&seasons)
+                    .and_then(serde_json::from_value)
+                    .map_err(|e| e.to_string())?;
+                print_seasons(&seasons);
+                return Ok(());
+            }
+
+            let show_details = fetch_show(args).await?;
+            if args.switch("json") {
+                return print_json(&show_details);
+            }
+            match &show_details.premiered_year {
+                Some(year) => println!("{} ({}), ID {}", show_details.name, year, show_details.id),
+                None => println!("{}, ID {}", show_details.name, show_details.id),
+            }
+            print_seasons(&show_details.episodes_by_season);
+            Ok(())
+        }
+        "help" => {
+            println!("{}", USAGE);
+            Ok(())
+        }
+        other => Err(format!(
+            "Unknown command {}, run script-kiddie help for the commands",
+            other
+        )),
+    }
+}
+
+// END COMMANDS
+
+#[tokio::main]
+async fn main() {
+    let args = match Args::parse(env::args().skip(1).collect()) {
+        Ok(args) if args.switch("help") => {
+            println!("{}", USAGE);
+            return;
+        }
+        Ok(args) => args,
+        Err(error) => {
+            eprintln!("{}, run script-kiddie help for the options", error);
+            process::exit(2);
+        }
+    };
+
+    if let Err(error) = run(&args).await {
+        eprintln!("{}", error);
+        process::exit(1);
+    }
+}
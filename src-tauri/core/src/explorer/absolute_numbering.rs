@@ -0,0 +1,309 @@
+use serde::Deserialize;
+
+use crate::api::models::SeasonedEpisodes;
+use crate::explorer::episode_parser::{EpisodeIdentifier, NamingScheme};
+
+// STRUCTS
+
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum NumberingConversion {
+    ToSeasonal, // Show - 37 -> Show - S02E13
+    ToAbsolute, // Show - S02E13 -> Show - 37
+}
+
+// The absolute episodes `first..=last` belong to `season`
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct SeasonRange {
+    season: i32,
+    first: i32,
+    last: i32,
+}
+
+// Where every regular season starts and ends in absolute numbering.
+// Specials (season 0) have no absolute numbers and are not part of it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SeasonBoundaries {
+    ranges: Vec<SeasonRange>,
+}
+
+impl SeasonBoundaries {
+    // Uses `start_episode`/`end_episode` of the seasons. Providers that leave them at 0
+    // (TheTVDB) get them counted from the number of titles, season after season.
+    pub fn from_seasons(seasons: &[SeasonedEpisodes]) -> Result<Self, String> {
+        let mut regular: Vec<&SeasonedEpisodes> = seasons
+            .iter()
+            .filter(|season| season.season > 0 && !season.titles.is_empty())
+            .collect();
+        regular.sort_by_key(|season| season.season);
+
+        let has_boundaries = regular
+            .iter()
+            .all(|season| season.start_episode > 0 && season.end_episode >= season.start_episode);
+
+        let mut ranges = Vec::new();
+        let mut next_first = 1;
+        for season in regular {
+            let range = if has_boundaries {
+                SeasonRange {
+                    season: season.season,
+                    first: season.start_episode,
+                    last: season.end_episode,
+                }
+            } else {
+                SeasonRange {
+                    season: season.season,
+                    first: next_first,
+                    last: next_first + season.titles.len() as i32 - 1,
+                }
+            };
+            next_first = range.last + 1;
+            ranges.push(range);
+        }
+
+        if ranges.is_empty() {
+            return Err("No regular seasons to convert with".to_string());
+        }
+        ranges.sort_by_key(|range| range.first);
+        if let Some(overlap) = ranges.windows(2).find(|pair| pair[0].last >= pair[1].first) {
+            return Err(format!(
+                "Season {} and season {} overlap",
+                overlap[0].season, overlap[1].season
+            ));
+        }
+
+        Ok(Self { ranges })
+    }
+
+    pub fn to_seasonal(&self, absolute: i32) -> Option<(i32, i32)> {
+        self.ranges
+            .iter()
+            .find(|range| (range.first..=range.last).contains(&absolute))
+            .map(|range| (range.season, absolute - range.first + 1))
+    }
+
+    pub fn to_absolute(&self, season: i32, episode: i32) -> Result<i32, String> {
+        let range = self
+            .ranges
+            .iter()
+            .find(|range| range.season == season)
+            .ok_or_else(|| format!("Season {} is not known", season))?;
+        let episode_count = range.last - range.first + 1;
+        if episode < 1 || episode > episode_count {
+            return Err(format!(
+                "S{:02}E{:02} is outside season {} ({} episodes)",
+                season, episode, season, episode_count
+            ));
+        }
+        Ok(range.first + episode - 1)
+    }
+
+    // Digits of the highest absolute number, at least two
+    fn width(&self) -> usize {
+        let last = self
+            .ranges
+            .iter()
+            .map(|range| range.last)
+            .max()
+            .unwrap_or(0);
+        last.to_string().len().max(2)
+    }
+
+    // e.g. "S01: 1-12, S02: 13-24"
+    fn describe(&self) -> String {
+        self.ranges
+            .iter()
+            .map(|range| format!("S{:02}: {}-{}", range.season, range.first, range.last))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+}
+
+// START CONVERSION
+
+// Rewrites the episode identifier of `file_name`. The error explains why a file is left as is.
+// `folder_season` stands in for a missing season number when converting to absolute numbers.
+pub fn convert_episode_numbering(
+    file_name: &str,
+    identifier: &EpisodeIdentifier,
+    boundaries: &SeasonBoundaries,
+    conversion: NumberingConversion,
+    folder_season: Option<i32>,
+) -> Result<String, String> {
+    if identifier.scheme == NamingScheme::Dated {
+        return Err("Dated episodes have no episode number".to_string());
+    }
+
+    let (start, end, replacement) = match conversion {
+        NumberingConversion::ToSeasonal => {
+            if identifier.season.is_some() {
+                return Err("Already numbered by season".to_string());
+            }
+            let seasonal = |absolute: i32| {
+                boundaries.to_seasonal(absolute).ok_or_else(|| {
+                    format!(
+                        "Episode {} is outside the known seasons ({})",
+                        absolute,
+                        boundaries.describe()
+                    )
+                })
+            };
+            let absolutes = identifier.episode_numbers();
+            let (first, last) = match (absolutes.first(), absolutes.last()) {
+                (Some(first), Some(last)) => (seasonal(*first)?, seasonal(*last)?),
+                _ => return Err("No episode number found".to_string()),
+            };
+
+            let replacement = if first == last {
+                format!("S{:02}E{:02}", first.0, first.1)
+            } else if first.0 == last.0 {
+                format!("S{:02}E{:02}-E{:02}", first.0, first.1, last.1)
+            } else {
+                return Err("The episodes of this file belong to different seasons".to_string());
+            };
+            (identifier.start, identifier.end, replacement)
+        }
+        NumberingConversion::ToAbsolute => {
+            let season = match identifier.season_number().or(folder_season) {
+                Some(season) => season,
+                None => return Err("Already numbered absolutely".to_string()),
+            };
+            if season == 0 {
+                return Err("Specials have no absolute number".to_string());
+            }
+
+            let absolutes = identifier
+                .episode_numbers()
+                .into_iter()
+                .map(|episode| boundaries.to_absolute(season, episode))
+                .collect::<Result<Vec<_>, _>>()?;
+            let replacement = absolute_text(&absolutes, boundaries.width())?;
+
+            // "Folge 5" in a season folder keeps its word, only the number changes
+            if let (None, [episode]) = (identifier.season, identifier.episodes.as_slice()) {
+                (episode.start, episode.end, replacement)
+            } else {
+                // Written as "Show - 37", the form absolute numbers are recognized in
+                let show = file_name[..identifier.start].trim_end_matches(|c: char| {
+                    c == '.' || c == '_' || c == '-' || c.is_whitespace()
+                });
+                let replacement = if show.is_empty() {
+                    format!("Episode {}", replacement)
+                } else {
+                    format!(" - {}", replacement)
+                };
+                (show.len(), identifier.end, replacement)
+            }
+        }
+    };
+
+    Ok(format!(
+        "{}{}{}",
+        &file_name[..start],
+        replacement,
+        &file_name[end..]
+    ))
+}
+
+// "37", or "37-38" for a multi-episode file
+fn absolute_text(absolutes: &[i32], width: usize) -> Result<String, String> {
+    match absolutes {
+        [] => Err("No episode number found".to_string()),
+        [absolute] => Ok(format!("{:0width$}", absolute, width = width)),
+        [first, .., last] => {
+            let consecutive = absolutes.windows(2).all(|pair| pair[1] == pair[0] + 1);
+            if !consecutive {
+                return Err("The episodes of this file are not consecutive".to_string());
+            }
+            Ok(format!("{:0width$}-{:0width$}", first, last, width = width))
+        }
+    }
+}
+
+// END CONVERSION
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::explorer::episode_parser::EpisodeParser;
+
+    // Season 1 with 12 episodes, season 2 with 13
+    fn boundaries() -> SeasonBoundaries {
+        let season = |season: i32, count: usize| SeasonedEpisodes {
+            season,
+            start_episode: 0,
+            end_episode: 0,
+            titles: vec!["Title".to_string(); count],
+        };
+        SeasonBoundaries::from_seasons(&[season(1, 12), season(2, 13), season(0, 2)]).unwrap()
+    }
+
+    fn convert(file_name: &str, conversion: NumberingConversion) -> Result<String, String> {
+        let identifier = EpisodeParser::new().parse(file_name).unwrap();
+        convert_episode_numbering(file_name, &identifier, &boundaries(), conversion, None)
+    }
+
+    #[test]
+    fn counts_boundaries_from_titles() {
+        let boundaries = boundaries();
+        assert_eq!(boundaries.to_seasonal(13), Some((2, 1)));
+        assert_eq!(boundaries.to_seasonal(26), None);
+        assert_eq!(boundaries.to_absolute(2, 13), Ok(25));
+        assert!(boundaries.to_absolute(2, 14).is_err());
+        assert!(boundaries.to_absolute(0, 1).is_err());
+    }
+
+    #[test]
+    fn writes_absolute_numbers_after_a_dash() {
+        use NumberingConversion::ToAbsolute;
+        assert_eq!(
+            convert("Show.S02E01.1080p.mkv", ToAbsolute),
+            Ok("Show - 13.1080p.mkv".to_string())
+        );
+        assert_eq!(
+            convert("Show - S01E05 - Title.mkv", ToAbsolute),
+            Ok("Show - 05 - Title.mkv".to_string())
+        );
+        assert_eq!(
+            convert("S01E05.mkv", ToAbsolute),
+            Ok("Episode 05.mkv".to_string())
+        );
+    }
+
+    #[test]
+    fn converts_back_and_forth() {
+        use NumberingConversion::{ToAbsolute, ToSeasonal};
+        for name in [
+            "Show - S02E01 [1080p].mkv",
+            "Show.S01E12.mkv",
+            "Show - S02E03-E04.mkv",
+            "S01E05.mkv",
+        ] {
+            let absolute = convert(name, ToAbsolute).unwrap();
+            let parsed = EpisodeParser::new().parse(&absolute).unwrap();
+            assert_eq!(parsed.season, None, "{}", absolute);
+
+            let seasonal = convert(&absolute, ToSeasonal).unwrap();
+            let original = EpisodeParser::new().parse(name).unwrap();
+            let round_trip = EpisodeParser::new().parse(&seasonal).unwrap();
+            assert_eq!(
+                round_trip.season_number(),
+                original.season_number(),
+                "{}",
+                seasonal
+            );
+            assert_eq!(
+                round_trip.episode_numbers(),
+                original.episode_numbers(),
+                "{}",
+                seasonal
+            );
+        }
+    }
+
+    #[test]
+    fn rejects_ranges_across_seasons() {
+        assert!(convert("Show - 12-13.mkv", NumberingConversion::ToSeasonal).is_err());
+        assert!(convert("Show.S00E01.mkv", NumberingConversion::ToAbsolute).is_err());
+    }
+}
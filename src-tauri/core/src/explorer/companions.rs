@@ -0,0 +1,203 @@
+use crate::explorer::media_files::is_video_file;
+use crate::explorer::rename_plan::RenamePlan;
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+// Subtitles, metadata and artwork that media servers expect next to the video
+const COMPANION_EXTENSIONS: [&str; 12] = [
+    "srt", "ass", "ssa", "sub", "idx", "sup", "vtt", "nfo", "jpg", "jpeg", "png", "txt",
+];
+
+// The videos and possible companions of every folder a plan touches,
+// so each folder is read once and not once per video
+#[derive(Default)]
+pub struct Companions {
+    folders: HashMap<PathBuf, FolderFiles>,
+}
+
+#[derive(Default)]
+struct FolderFiles {
+    video_stems: Vec<String>,
+    candidates: Vec<PathBuf>,
+}
+
+impl Companions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // Files next to the video that share its stem, e.g. "Show S01E01.en.srt",
+    // "Show S01E01.forced.ass" or "Show S01E01-thumb.jpg" for "Show S01E01.mkv"
+    pub fn find(&mut self, video: &Path) -> io::Result<Vec<PathBuf>> {
+        let (directory, stem) = match (video.parent(), stem_of(video)) {
+            (Some(directory), Some(stem)) => (directory, stem),
+            _ => return Ok(vec![]),
+        };
+
+        if !self.folders.contains_key(directory) {
+            let files = scan_folder(directory)?;
+            self.folders.insert(directory.to_path_buf(), files);
+        }
+        let files = &self.folders[directory];
+        Ok(owned_companions(
+            &stem,
+            &files.video_stems,
+            &files.candidates,
+        ))
+    }
+
+    // Adds the rename of the video and moves its companions along, keeping
+    // language and flag suffixes like ".en.forced.srt"
+    pub fn push_with_companions(
+        &mut self,
+        plan: &mut RenamePlan,
+        from: PathBuf,
+        to: PathBuf,
+    ) -> io::Result<()> {
+        let companions = if from != to {
+            self.find(&from)?
+        } else {
+            vec![]
+        };
+
+        if let (Some(old_stem), Some(new_stem), Some(new_directory)) =
+            (stem_of(&from), stem_of(&to), to.parent())
+        {
+            for companion in companions {
+                let name = companion
+                    .file_name()
+                    .map(|name| name.to_string_lossy().to_string())
+                    .unwrap_or_default();
+                if let Some(suffix) = companion_suffix(&name, &old_stem) {
+                    let new_companion = new_directory.join(format!("{}{}", new_stem, suffix));
+                    plan.push_companion(&from, companion, new_companion);
+                }
+            }
+        }
+
+        plan.push(from, to);
+        Ok(())
+    }
+}
+
+fn scan_folder(directory: &Path) -> io::Result<FolderFiles> {
+    let mut files = FolderFiles::default();
+    for entry in fs::read_dir(directory)? {
+        let path = entry?.path();
+        if !path.is_file() {
+            continue;
+        }
+        if is_video_file(&path) {
+            if let Some(video_stem) = stem_of(&path) {
+                files.video_stems.push(video_stem);
+            }
+        } else if is_companion_extension(&path) {
+            files.candidates.push(path);
+        }
+    }
+    Ok(files)
+}
+
+// Each companion belongs to the video with the longest matching stem
+fn owned_companions(stem: &str, video_stems: &[String], candidates: &[PathBuf]) -> Vec<PathBuf> {
+    let mut companions: Vec<PathBuf> = candidates
+        .iter()
+        .filter(|candidate| {
+            let name = candidate
+                .file_name()
+                .map(|name| name.to_string_lossy().to_string())
+                .unwrap_or_default();
+            let owner = video_stems
+                .iter()
+                .filter(|video_stem| companion_suffix(&name, video_stem).is_some())
+                .max_by_key(|video_stem| video_stem.len());
+            owner.map(String::as_str) == Some(stem)
+        })
+        .cloned()
+        .collect();
+    companions.sort();
+    companions
+}
+
+fn stem_of(path: &Path) -> Option<String> {
+    path.file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+}
+
+pub fn is_companion_extension(path: &Path) -> bool {
+    path.extension()
+        .and_then(|extension| extension.to_str())
+        .map(|extension| COMPANION_EXTENSIONS.contains(&extension.to_lowercase().as_str()))
+        .unwrap_or(false)
+}
+
+// The part after the video stem, if the name starts with the stem followed by '.' or '-'
+fn companion_suffix<'a>(name: &'a str, video_stem: &str) -> Option<&'a str> {
+    // Compare case-insensitively, Windows does not care about case either
+    let prefix = name.get(..video_stem.len())?;
+    if !prefix.eq_ignore_ascii_case(video_stem) {
+        return None;
+    }
+
+    let suffix = &name[video_stem.len()..];
+    if suffix.starts_with('.') || suffix.starts_with('-') {
+        Some(suffix)
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn owned(stem: &str, video_stems: &[&str], candidates: &[&str]) -> Vec<PathBuf> {
+        let video_stems: Vec<String> = video_stems.iter().map(|s| s.to_string()).collect();
+        let candidates: Vec<PathBuf> = candidates.iter().map(PathBuf::from).collect();
+        owned_companions(stem, &video_stems, &candidates)
+    }
+
+    #[test]
+    fn splits_off_the_suffix_after_the_stem() {
+        assert_eq!(
+            companion_suffix("Show S01E01.en.forced.srt", "Show S01E01"),
+            Some(".en.forced.srt")
+        );
+        assert_eq!(
+            companion_suffix("show s01e01-thumb.jpg", "Show S01E01"),
+            Some("-thumb.jpg")
+        );
+        assert_eq!(companion_suffix("Show S01E10.en.srt", "Show S01E1"), None);
+        assert_eq!(companion_suffix("Show.srt", "Show S01E01"), None);
+    }
+
+    #[test]
+    fn gives_companions_to_the_longest_stem() {
+        let video_stems = ["Show", "Show.Extended", "Show S01E1", "Show S01E10"];
+        let candidates = [
+            "Show.en.srt",
+            "Show.Extended.en.srt",
+            "SHOW S01E10.en.srt",
+            "Show S01E1-thumb.jpg",
+        ];
+
+        assert_eq!(
+            owned("Show", &video_stems, &candidates),
+            [PathBuf::from("Show.en.srt")]
+        );
+        assert_eq!(
+            owned("Show.Extended", &video_stems, &candidates),
+            [PathBuf::from("Show.Extended.en.srt")]
+        );
+        assert_eq!(
+            owned("Show S01E10", &video_stems, &candidates),
+            [PathBuf::from("SHOW S01E10.en.srt")]
+        );
+        assert_eq!(
+            owned("Show S01E1", &video_stems, &candidates),
+            [PathBuf::from("Show S01E1-thumb.jpg")]
+        );
+    }
+}
@@ -0,0 +1,379 @@
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::collections::{HashMap, HashSet};
+use std::fs::{self, File};
+use std::io::{self, Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use tokio_util::sync::CancellationToken;
+
+use crate::explorer::media_files::walk_files;
+
+// Bytes read from the start and the end of a file for its partial hash
+const PARTIAL_BLOCK: u64 = 64 * 1024;
+const READ_BUFFER: usize = 1024 * 1024;
+
+// STRUCTS
+
+#[derive(Debug, Serialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum DuplicatePhase {
+    Scanning,    // Walking the folders, `done` counts files
+    PartialHash, // Hashing the start and end of files of the same size, `done` counts files
+    FullHash,    // Hashing whole files, `done` and `total` count bytes
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct DuplicateProgress {
+    pub phase: DuplicatePhase,
+    pub done: u64,
+    pub total: u64, // 0 while scanning, the total is not known yet
+}
+
+// Files with the same content. Every file but one is wasted space.
+#[derive(Debug, Serialize, Clone)]
+pub struct DuplicateSet {
+    pub size: u64,
+    pub hash: String, // SHA-256 of the whole content
+    pub paths: Vec<PathBuf>,
+    pub wasted: u64,
+}
+
+#[derive(Debug, Serialize, Clone, Default)]
+pub struct DuplicateReport {
+    pub root: PathBuf,
+    pub scanned_files: usize,
+    pub sets: Vec<DuplicateSet>, // Most wasted space first
+    pub wasted: u64,
+    pub skipped: Vec<String>, // Files and folders that could not be read, so they were not compared
+}
+
+impl DuplicateReport {
+    // The set `path` was found in
+    pub fn set_of(&self, path: &Path) -> Option<&DuplicateSet> {
+        self.sets
+            .iter()
+            .find(|set| set.paths.iter().any(|other| other == path))
+    }
+
+    // Drops deleted files, sets with a single file left are no duplicates anymore
+    pub fn remove_paths(&mut self, deleted: &[PathBuf]) {
+        for set in &mut self.sets {
+            set.paths.retain(|path| !deleted.contains(path));
+            set.wasted = set.size * (set.paths.len() as u64).saturating_sub(1);
+        }
+        self.sets.retain(|set| set.paths.len() > 1);
+        self.wasted = self.sets.iter().map(|set| set.wasted).sum();
+    }
+}
+
+// START FINDING
+
+// Finds files with the same content below `root`. Files are grouped by size first,
+// then by a hash of their start and end, and only the remaining candidates are
+// hashed completely. Nothing is changed on disk.
+pub fn find_duplicates(
+    root: &Path,
+    token: &CancellationToken,
+    mut progress: impl FnMut(DuplicateProgress),
+) -> Result<DuplicateReport, String> {
+    let mut files = Vec::new();
+    let mut skipped = Vec::new();
+    walk_files(
+        root,
+        true,
+        token,
+        &mut |path, size| {
+            files.push((path, size));
+            progress(DuplicatePhase::Scanning.progress(files.len() as u64, 0));
+        },
+        &mut skipped,
+    )?;
+    let scanned_files = files.len();
+
+    // Empty files are all the same, but they waste no space
+    let mut by_size: HashMap<u64, Vec<PathBuf>> = HashMap::new();
+    for (path, size) in files {
+        if size > 0 {
+            by_size.entry(size).or_default().push(path);
+        }
+    }
+    let same_size: Vec<(u64, Vec<PathBuf>)> = by_size
+        .into_iter()
+        .filter(|(_, paths)| paths.len() > 1)
+        .collect();
+
+    let partial_total = same_size.iter().map(|(_, paths)| paths.len() as u64).sum();
+    let mut partial_done = 0;
+    let mut same_start = Vec::new();
+    for (size, paths) in same_size {
+        let mut by_partial: HashMap<String, Vec<PathBuf>> = HashMap::new();
+        for path in paths {
+            check_cancelled(token)?;
+            match partial_hash(&path, size) {
+                Ok(hash) => by_partial.entry(hash).or_default().push(path),
+                Err(e) => skipped.push(format!("Failed to hash {}: {}", path.display(), e)),
+            }
+            partial_done += 1;
+            progress(DuplicatePhase::PartialHash.progress(partial_done, partial_total));
+        }
+        same_start.extend(
+            by_partial
+                .into_iter()
+                .filter(|(_, paths)| paths.len() > 1)
+                .map(|(hash, paths)| (size, hash, paths)),
+        );
+    }
+
+    let full_total = same_start
+        .iter()
+        .filter(|(size, _, _)| *size > PARTIAL_BLOCK * 2)
+        .map(|(size, _, paths)| size * paths.len() as u64)
+        .sum();
+    let mut full_done = 0;
+    let mut sets = Vec::new();
+    for (size, partial, paths) in same_start {
+        // Small files were read completely for the partial hash
+        let mut by_full: HashMap<String, Vec<PathBuf>> = HashMap::new();
+        if size <= PARTIAL_BLOCK * 2 {
+            by_full.insert(partial, paths);
+        } else {
+            for path in paths {
+                let hash = full_hash(&path, token, |read| {
+                    full_done += read;
+                    progress(DuplicatePhase::FullHash.progress(full_done, full_total));
+                });
+                match hash {
+                    Ok(hash) => by_full.entry(hash).or_default().push(path),
+                    Err(e) if token.is_cancelled() => return Err(e.to_string()),
+                    Err(e) => skipped.push(format!("Failed to hash {}: {}", path.display(), e)),
+                }
+            }
+        }
+
+        for (hash, mut paths) in by_full {
+            if paths.len() > 1 {
+                paths.sort();
+                sets.push(DuplicateSet {
+                    size,
+                    hash,
+                    wasted: size * (paths.len() as u64 - 1),
+                    paths,
+                });
+            }
+        }
+    }
+
+    sets.sort_by(|a, b| b.wasted.cmp(&a.wasted).then_with(|| a.paths.cmp(&b.paths)));
+    Ok(DuplicateReport {
+        root: root.to_path_buf(),
+        scanned_files,
+        wasted: sets.iter().map(|set| set.wasted).sum(),
+        sets,
+        skipped,
+    })
+}
+
+impl DuplicatePhase {
+    fn progress(self, done: u64, total: u64) -> DuplicateProgress {
+        DuplicateProgress {
+            phase: self,
+            done,
+            total,
+        }
+    }
+}
+
+fn check_cancelled(token: &CancellationToken) -> Result<(), String> {
+    if token.is_cancelled() {
+        return Err("Operation cancelled".into());
+    }
+    Ok(())
+}
+
+// END FINDING
+
+// START HASHING
+
+// SHA-256 of the first and last block, the whole file if it is small
+fn partial_hash(path: &Path, size: u64) -> io::Result<String> {
+    let mut file = File::open(path)?;
+    let mut hasher = Sha256::new();
+
+    let mut buffer = vec![0; PARTIAL_BLOCK.min(size) as usize];
+    file.read_exact(&mut buffer)?;
+    hasher.update(&buffer);
+
+    if size > PARTIAL_BLOCK {
+        let tail = PARTIAL_BLOCK.min(size - PARTIAL_BLOCK);
+        file.seek(SeekFrom::Start(size - tail))?;
+        let mut buffer = vec![0; tail as usize];
+        file.read_exact(&mut buffer)?;
+        hasher.update(&buffer);
+    }
+
+    Ok(hex(&hasher.finalize()))
+}
+
+// SHA-256 of the whole file, `on_read` gets the number of bytes of every chunk
+pub fn full_hash(
+    path: &Path,
+    token: &CancellationToken,
+    mut on_read: impl FnMut(u64),
+) -> io::Result<String> {
+    let mut file = File::open(path)?;
+    let mut hasher = Sha256::new();
+    let mut buffer = vec![0; READ_BUFFER];
+
+    loop {
+        if token.is_cancelled() {
+            return Err(io::Error::new(
+                io::ErrorKind::Interrupted,
+                "Operation cancelled",
+            ));
+        }
+        let read = file.read(&mut buffer)?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+        on_read(read as u64);
+    }
+
+    Ok(hex(&hasher.finalize()))
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+// END HASHING
+
+// START DELETING
+
+// Deletes files of a duplicate report. Every set keeps at least one file, and the files
+// are hashed again first, so nothing is deleted that changed since the search.
+// Returns the deleted files.
+pub fn delete_duplicates(
+    report: &DuplicateReport,
+    paths: &[PathBuf],
+    token: &CancellationToken,
+) -> Result<Vec<PathBuf>, String> {
+    // Everything is checked before the first file is deleted, a path listed twice counts once
+    let mut seen = HashSet::new();
+    let mut sets: Vec<(&DuplicateSet, Vec<&PathBuf>)> = Vec::new();
+    for path in paths {
+        if !seen.insert(path) {
+            continue;
+        }
+        let set = report.set_of(path).ok_or_else(|| {
+            format!(
+                "{} is not part of the last duplicate search",
+                path.display()
+            )
+        })?;
+        match sets.iter_mut().find(|(other, _)| other.hash == set.hash) {
+            Some((_, doomed)) => doomed.push(path),
+            None => sets.push((set, vec![path])),
+        }
+    }
+
+    for (set, doomed) in &sets {
+        let kept: Vec<&PathBuf> = set
+            .paths
+            .iter()
+            .filter(|path| !doomed.contains(path))
+            .collect();
+        if kept.is_empty() {
+            return Err(format!(
+                "Keep at least one copy of {}",
+                set.paths[0].display()
+            ));
+        }
+
+        // A copy that stays has to be unchanged, or the content would be lost
+        let mut kept_unchanged = false;
+        for path in kept {
+            if is_unchanged(path, set, token)? {
+                kept_unchanged = true;
+                break;
+            }
+        }
+        if !kept_unchanged {
+            return Err(format!(
+                "No unchanged copy of {} is left, search again",
+                set.paths[0].display()
+            ));
+        }
+
+        for path in doomed {
+            if !is_unchanged(path, set, token)? {
+                return Err(format!(
+                    "{} changed since the search, search again",
+                    path.display()
+                ));
+            }
+        }
+    }
+
+    let mut deleted = Vec::new();
+    for path in sets.iter().flat_map(|(_, doomed)| doomed) {
+        fs::remove_file(path).map_err(|e| {
+            format!(
+                "Failed to delete {}: {} ({} files were deleted)",
+                path.display(),
+                e,
+                deleted.len()
+            )
+        })?;
+        deleted.push(path.to_path_buf());
+    }
+
+    Ok(deleted)
+}
+
+fn is_unchanged(
+    path: &Path,
+    set: &DuplicateSet,
+    token: &CancellationToken,
+) -> Result<bool, String> {
+    match fs::metadata(path) {
+        Ok(metadata) if metadata.is_file() && metadata.len() == set.size => {}
+        _ => return Ok(false),
+    }
+    match full_hash(path, token, |_| {}) {
+        Ok(hash) => Ok(hash == set.hash),
+        Err(_) if token.is_cancelled() => Err("Operation cancelled".into()),
+        Err(_) => Ok(false),
+    }
+}
+
+// END DELETING
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::explorer::test_support::TestDir;
+
+    #[test]
+    fn deletes_a_path_listed_twice_once() {
+        let dir = TestDir::new();
+        let first = dir.file_with("a.mkv", "same");
+        let second = dir.file_with("b.mkv", "same");
+        dir.file_with("c.mkv", "other");
+
+        let token = CancellationToken::new();
+        let report = find_duplicates(&dir.join(""), &token, |_| {}).unwrap();
+        assert_eq!(report.sets.len(), 1);
+        assert_eq!(report.scanned_files, 3);
+        assert!(report.skipped.is_empty());
+
+        let deleted = delete_duplicates(&report, &[second.clone(), second.clone()], &token);
+        assert_eq!(deleted.unwrap(), [second]);
+        assert_eq!(dir.names(), ["a.mkv", "c.mkv"]);
+
+        // Nothing is deleted if one of the paths fails the checks
+        let stranger = dir.join("c.mkv");
+        assert!(delete_duplicates(&report, &[first, stranger], &token).is_err());
+        assert_eq!(dir.names(), ["a.mkv", "c.mkv"]);
+    }
+}
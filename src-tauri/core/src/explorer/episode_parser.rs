@@ -0,0 +1,426 @@
+use regex::{Captures, Regex};
+use serde::Serialize;
+
+// STRUCTS
+
+#[derive(Debug, Serialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum NamingScheme {
+    SeasonEpisode, // S01E02, S1E2, S01.E02, S01E01-E03
+    Crossed,       // 1x02, 1x02-03
+    Dated,         // 2024-03-15
+    Episode,       // Episode 12, Ep 12, Folge 12
+    Absolute,      // Show - 12 [1080p], Show - 12-13
+}
+
+// A number inside the file name, `start..end` is the byte span of its digits
+#[derive(Debug, Serialize, Clone, Copy, PartialEq)]
+pub struct NumberMatch {
+    pub value: i32,
+    pub start: usize,
+    pub end: usize,
+}
+
+impl NumberMatch {
+    pub fn digits(&self) -> usize {
+        self.end - self.start
+    }
+}
+
+// Everything one naming scheme found in a file name.
+// `start..end` is the byte span of the whole identifier, e.g. "S01E02".
+// `episodes` holds every episode number as written, S01E01E02 has two.
+// A range like S01E01-E03 also covers the episodes between its numbers.
+#[derive(Debug, Serialize, Clone, PartialEq)]
+pub struct EpisodeIdentifier {
+    pub scheme: NamingScheme,
+    pub season: Option<NumberMatch>,
+    pub episodes: Vec<NumberMatch>,
+    pub range: bool,
+    pub absolute: Option<i32>,
+    pub air_date: Option<String>,
+    pub start: usize,
+    pub end: usize,
+}
+
+impl EpisodeIdentifier {
+    pub fn season_number(&self) -> Option<i32> {
+        self.season.map(|season| season.value)
+    }
+
+    pub fn first_episode(&self) -> Option<i32> {
+        self.episodes.first().map(|episode| episode.value)
+    }
+
+    // Every episode the file contains, including the ones inside a range
+    pub fn episode_numbers(&self) -> Vec<i32> {
+        match (self.range, self.episodes.first(), self.episodes.last()) {
+            (true, Some(first), Some(last)) if first.value <= last.value => {
+                (first.value..=last.value).collect()
+            }
+            _ => self.episodes.iter().map(|episode| episode.value).collect(),
+        }
+    }
+
+    // The identifier as it is written in the file name
+    pub fn text<'a>(&self, file_name: &'a str) -> &'a str {
+        &file_name[self.start..self.end]
+    }
+}
+
+// A naming scheme that can find an episode identifier in a file name
+pub trait SchemeParser: Send + Sync {
+    fn parse(&self, file_name: &str) -> Option<EpisodeIdentifier>;
+}
+
+// START SCHEME PARSERS
+
+fn number_match(caps: &Captures, group: &str) -> Option<NumberMatch> {
+    let digits = caps.name(group)?;
+    Some(NumberMatch {
+        value: digits.as_str().parse().ok()?,
+        start: digits.start(),
+        end: digits.end(),
+    })
+}
+
+// Every number inside the `episodes` group, e.g. "E01E02" or "E01-E03"
+fn episode_matches(caps: &Captures, digits: &Regex) -> (Vec<NumberMatch>, bool) {
+    let episodes = match caps.name("episodes") {
+        Some(episodes) => episodes,
+        None => return (vec![], false),
+    };
+
+    let numbers = digits
+        .find_iter(episodes.as_str())
+        .filter_map(|number| {
+            Some(NumberMatch {
+                value: number.as_str().parse().ok()?,
+                start: episodes.start() + number.start(),
+                end: episodes.start() + number.end(),
+            })
+        })
+        .collect();
+
+    (numbers, episodes.as_str().contains('-'))
+}
+
+// Span of the `id` group, which every scheme pattern wraps its identifier in
+fn identifier_span(caps: &Captures) -> (usize, usize) {
+    let id = caps.name("id").unwrap();
+    (id.start(), id.end())
+}
+
+struct SeasonEpisodeParser {
+    pattern: Regex,
+    digits: Regex,
+}
+
+impl SchemeParser for SeasonEpisodeParser {
+    fn parse(&self, file_name: &str) -> Option<EpisodeIdentifier> {
+        let caps = self.pattern.captures(file_name)?;
+        let (start, end) = identifier_span(&caps);
+        let (episodes, range) = episode_matches(&caps, &self.digits);
+        if episodes.is_empty() {
+            return None;
+        }
+
+        Some(EpisodeIdentifier {
+            scheme: NamingScheme::SeasonEpisode,
+            season: number_match(&caps, "season"),
+            episodes,
+            range,
+            absolute: None,
+            air_date: None,
+            start,
+            end,
+        })
+    }
+}
+
+struct CrossedParser {
+    pattern: Regex,
+    digits: Regex,
+}
+
+impl SchemeParser for CrossedParser {
+    fn parse(&self, file_name: &str) -> Option<EpisodeIdentifier> {
+        let caps = self.pattern.captures(file_name)?;
+        let (start, end) = identifier_span(&caps);
+        let (episodes, range) = episode_matches(&caps, &self.digits);
+        if episodes.is_empty() {
+            return None;
+        }
+
+        Some(EpisodeIdentifier {
+            scheme: NamingScheme::Crossed,
+            season: number_match(&caps, "season"),
+            episodes,
+            range,
+            absolute: None,
+            air_date: None,
+            start,
+            end,
+        })
+    }
+}
+
+struct DatedParser {
+    pattern: Regex,
+}
+
+impl SchemeParser for DatedParser {
+    fn parse(&self, file_name: &str) -> Option<EpisodeIdentifier> {
+        // Look past dates that are not valid, e.g. version numbers
+        for caps in self.pattern.captures_iter(file_name) {
+            let month: u32 = caps["month"].parse().ok()?;
+            let day: u32 = caps["day"].parse().ok()?;
+            if !(1..=12).contains(&month) || !(1..=31).contains(&day) {
+                continue;
+            }
+
+            let (start, end) = identifier_span(&caps);
+            return Some(EpisodeIdentifier {
+                scheme: NamingScheme::Dated,
+                season: None,
+                episodes: vec![],
+                range: false,
+                absolute: None,
+                air_date: Some(format!("{}-{:02}-{:02}", &caps["year"], month, day)),
+                start,
+                end,
+            });
+        }
+        None
+    }
+}
+
+struct EpisodeWordParser {
+    pattern: Regex,
+}
+
+impl SchemeParser for EpisodeWordParser {
+    fn parse(&self, file_name: &str) -> Option<EpisodeIdentifier> {
+        let caps = self.pattern.captures(file_name)?;
+        let (start, end) = identifier_span(&caps);
+        Some(EpisodeIdentifier {
+            scheme: NamingScheme::Episode,
+            season: None,
+            episodes: vec![number_match(&caps, "episode")?],
+            range: false,
+            absolute: None,
+            air_date: None,
+            start,
+            end,
+        })
+    }
+}
+
+struct AbsoluteParser {
+    pattern: Regex,
+    digits: Regex,
+}
+
+impl SchemeParser for AbsoluteParser {
+    fn parse(&self, file_name: &str) -> Option<EpisodeIdentifier> {
+        let caps = self.pattern.captures(file_name)?;
+        let (start, end) = identifier_span(&caps);
+        let (episodes, range) = episode_matches(&caps, &self.digits);
+        let absolute = episodes.first()?.value;
+        Some(EpisodeIdentifier {
+            scheme: NamingScheme::Absolute,
+            season: None,
+            episodes,
+            range,
+            absolute: Some(absolute),
+            air_date: None,
+            start,
+            end,
+        })
+    }
+}
+
+// END SCHEME PARSERS
+
+// Tries every naming scheme in order, the first one that matches wins
+pub struct EpisodeParser {
+    schemes: Vec<Box<dyn SchemeParser>>,
+}
+
+impl EpisodeParser {
+    pub fn new() -> Self {
+        Self {
+            schemes: vec![
+                // S01E02, S1E2, S01.E02, S01 E02, S01E01E02, S01E01-E03, S01E01-02
+                Box::new(SeasonEpisodeParser {
+                    pattern: Regex::new(
+                        r"(?i)\b(?P<id>S(?P<season>\d{1,3})[ ._-]?(?P<episodes>E\d{1,4}(?:-?E\d{1,4}|-\d{2,3}\b)*))",
+                    )
+                    .unwrap(),
+                    digits: Regex::new(r"\d+").unwrap(),
+                }),
+                // 1x02, 1x02-03, but not resolutions like 1920x1080
+                Box::new(CrossedParser {
+                    pattern: Regex::new(
+                        r"(?i)\b(?P<id>(?P<season>\d{1,2})x(?P<episodes>\d{2,3}(?:-\d{2,3})*))\b",
+                    )
+                    .unwrap(),
+                    digits: Regex::new(r"\d+").unwrap(),
+                }),
+                // 2024-03-15, 2024.03.15
+                Box::new(DatedParser {
+                    pattern: Regex::new(
+                        r"\b(?P<id>(?P<year>(?:19|20)\d{2})[.\-_](?P<month>\d{2})[.\-_](?P<day>\d{2}))\b",
+                    )
+                    .unwrap(),
+                }),
+                // Episode 12, Ep.12, Folge 12
+                Box::new(EpisodeWordParser {
+                    pattern: Regex::new(
+                        r"(?i)\b(?P<id>(?:episode|ep|folge)[ ._-]?(?P<episode>\d{1,4}))\b",
+                    )
+                    .unwrap(),
+                }),
+                // Show - 12 [1080p], Show - 12v2.mkv, Show - 12-13.mkv
+                Box::new(AbsoluteParser {
+                    pattern: Regex::new(
+                        r"\s-\s(?P<id>(?P<episodes>\d{1,4}(?:-\d{1,4})?)(?:v\d)?)(?:[\s\[(.]|$)",
+                    )
+                    .unwrap(),
+                    digits: Regex::new(r"\d+").unwrap(),
+                }),
+            ],
+        }
+    }
+
+    pub fn parse(&self, file_name: &str) -> Option<EpisodeIdentifier> {
+        self.schemes
+            .iter()
+            .find_map(|scheme| scheme.parse(file_name))
+    }
+}
+
+impl Default for EpisodeParser {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(file_name: &str) -> EpisodeIdentifier {
+        EpisodeParser::new()
+            .parse(file_name)
+            .unwrap_or_else(|| panic!("no identifier in {}", file_name))
+    }
+
+    #[test]
+    fn parses_season_episode() {
+        for name in [
+            "Show.S01E02.1080p.mkv",
+            "Show S1E2.mkv",
+            "Show.s01.e02.mkv",
+            "Show - S01 E02 - Title.mkv",
+        ] {
+            let id = parse(name);
+            assert_eq!(id.scheme, NamingScheme::SeasonEpisode, "{}", name);
+            assert_eq!(id.season_number(), Some(1), "{}", name);
+            assert_eq!(id.episode_numbers(), [2], "{}", name);
+        }
+        let id = parse("Show.S01E02.1080p.mkv");
+        assert_eq!(id.text("Show.S01E02.1080p.mkv"), "S01E02");
+        assert_eq!(id.episodes[0].digits(), 2);
+    }
+
+    #[test]
+    fn parses_crossed() {
+        let id = parse("Show 2x05 Title.mkv");
+        assert_eq!(id.scheme, NamingScheme::Crossed);
+        assert_eq!(id.season_number(), Some(2));
+        assert_eq!(id.episode_numbers(), [5]);
+        assert_eq!(id.text("Show 2x05 Title.mkv"), "2x05");
+    }
+
+    #[test]
+    fn ignores_resolutions() {
+        assert_eq!(EpisodeParser::new().parse("Show 1920x1080.mkv"), None);
+    }
+
+    #[test]
+    fn parses_air_dates() {
+        let id = parse("Show.2024.03.15.mkv");
+        assert_eq!(id.scheme, NamingScheme::Dated);
+        assert_eq!(id.air_date.as_deref(), Some("2024-03-15"));
+        assert!(id.episodes.is_empty());
+
+        // 2024-13-01 is no date, the next one is
+        let id = parse("Show 2024-13-01 2024-01-13.mkv");
+        assert_eq!(id.air_date.as_deref(), Some("2024-01-13"));
+    }
+
+    #[test]
+    fn parses_episode_words() {
+        for (name, episode) in [
+            ("Show Episode 12.mkv", 12),
+            ("Show Ep.7.mkv", 7),
+            ("Serie Folge 3.mkv", 3),
+        ] {
+            let id = parse(name);
+            assert_eq!(id.scheme, NamingScheme::Episode, "{}", name);
+            assert_eq!(id.season_number(), None, "{}", name);
+            assert_eq!(id.episode_numbers(), [episode], "{}", name);
+        }
+    }
+
+    #[test]
+    fn parses_absolute_numbers() {
+        for (name, episode) in [
+            ("Show - 12 [1080p].mkv", 12),
+            ("Show - 105.mkv", 105),
+            ("Show - 07v2.mkv", 7),
+        ] {
+            let id = parse(name);
+            assert_eq!(id.scheme, NamingScheme::Absolute, "{}", name);
+            assert_eq!(id.absolute, Some(episode), "{}", name);
+        }
+        assert_eq!(EpisodeParser::new().parse("Show 12.mkv"), None);
+
+        let id = parse("Show - 12-13 [1080p].mkv");
+        assert_eq!(id.absolute, Some(12));
+        assert_eq!(id.episode_numbers(), [12, 13]);
+    }
+
+    #[test]
+    fn parses_multi_episode_files() {
+        for (name, episodes) in [
+            ("Show.S01E01E02.mkv", vec![1, 2]),
+            ("Show.S01E01-E03.mkv", vec![1, 2, 3]),
+            ("Show.S01E01-03.mkv", vec![1, 2, 3]),
+            ("Show 1x02-03.mkv", vec![2, 3]),
+        ] {
+            let id = parse(name);
+            assert_eq!(id.episode_numbers(), episodes, "{}", name);
+            assert_eq!(id.first_episode(), Some(episodes[0]), "{}", name);
+        }
+
+        let id = parse("Show.S01E01-E03.mkv");
+        assert!(id.range);
+        assert_eq!(id.episodes.len(), 2);
+        assert_eq!(id.text("Show.S01E01-E03.mkv"), "S01E01-E03");
+        assert!(!parse("Show.S01E01E02.mkv").range);
+    }
+
+    #[test]
+    fn keeps_reversed_ranges_as_written() {
+        assert_eq!(parse("Show.S01E05-E03.mkv").episode_numbers(), [5, 3]);
+    }
+
+    #[test]
+    fn prefers_season_episode_over_other_schemes() {
+        let id = parse("Show - 12 - S01E03.mkv");
+        assert_eq!(id.scheme, NamingScheme::SeasonEpisode);
+        assert_eq!(id.episode_numbers(), [3]);
+    }
+}
@@ -0,0 +1,307 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+
+// Device names Windows refuses as file names, with or without an extension
+const RESERVED_NAMES: [&str; 30] = [
+    "CON", "PRN", "AUX", "NUL", "COM0", "COM1", "COM2", "COM3", "COM4", "COM5", "COM6", "COM7",
+    "COM8", "COM9", "COM¹", "COM²", "COM³", "LPT0", "LPT1", "LPT2", "LPT3", "LPT4", "LPT5", "LPT6",
+    "LPT7", "LPT8", "LPT9", "LPT¹", "LPT²", "LPT³",
+];
+
+const MAX_NAME_LENGTH: usize = 255;
+
+// STRUCTS
+
+// The file system the renamed files have to be valid on
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum SanitizeProfile {
+    Posix,   // Only '/' is forbidden, names up to 255 bytes
+    Windows, // NTFS: no `<>:"/\|?*`, no reserved names or trailing dots, 255 UTF-16 units
+    Exfat,   // Like NTFS, but without reserved names
+    Smb,     // Windows clients on a share that stores names in UTF-8, 255 bytes
+}
+
+// Names that are valid on Windows, until another profile is chosen. Libraries on
+// Linux are often shared over SMB, so POSIX names are only used when chosen.
+impl Default for SanitizeProfile {
+    fn default() -> Self {
+        if cfg!(windows) {
+            SanitizeProfile::Windows
+        } else {
+            SanitizeProfile::Smb
+        }
+    }
+}
+
+impl SanitizeProfile {
+    fn forbids(&self, c: char) -> bool {
+        match self {
+            SanitizeProfile::Posix => c == '/' || c == '\0',
+            _ => {
+                c.is_control() || matches!(c, '<' | '>' | ':' | '"' | '/' | '\\' | '|' | '?' | '*')
+            }
+        }
+    }
+
+    fn has_reserved_names(&self) -> bool {
+        matches!(self, SanitizeProfile::Windows | SanitizeProfile::Smb)
+    }
+
+    fn trims_trailing_dots(&self) -> bool {
+        *self != SanitizeProfile::Posix
+    }
+
+    // Length of a name the way the file system counts it
+    fn length(&self, name: &str) -> usize {
+        match self {
+            SanitizeProfile::Posix | SanitizeProfile::Smb => name.len(),
+            SanitizeProfile::Windows | SanitizeProfile::Exfat => name.encode_utf16().count(),
+        }
+    }
+
+    fn unit(&self) -> &'static str {
+        match self {
+            SanitizeProfile::Posix | SanitizeProfile::Smb => "bytes",
+            SanitizeProfile::Windows | SanitizeProfile::Exfat => "characters",
+        }
+    }
+}
+
+// START PROFILE FILE
+
+fn profile_path() -> Result<PathBuf, String> {
+    let data_dir = dirs::data_local_dir().ok_or("Failed to get local data directory")?;
+    Ok(data_dir.join("script-kiddie").join("sanitize_profile.json"))
+}
+
+// The saved profile, or the one of the platform if none was saved yet
+pub fn load_sanitize_profile() -> Result<SanitizeProfile, String> {
+    let path = profile_path()?;
+    if !path.exists() {
+        return Ok(SanitizeProfile::default());
+    }
+
+    let content =
+        fs::read_to_string(&path).map_err(|e| format!("Failed to read sanitize profile: {}", e))?;
+    serde_json::from_str(&content).map_err(|e| format!("Failed to parse sanitize profile: {}", e))
+}
+
+pub fn save_sanitize_profile(profile: SanitizeProfile) -> Result<(), String> {
+    let path = profile_path()?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create settings directory: {}", e))?;
+    }
+
+    let content = serde_json::to_string_pretty(&profile).map_err(|e| e.to_string())?;
+    fs::write(&path, content).map_err(|e| format!("Failed to write sanitize profile: {}", e))
+}
+
+// END PROFILE FILE
+
+// START SANITIZING
+
+// Keeps a generated name from turning into a path, e.g. a title like "AC/DC".
+// Everything else is left to the sanitize profile, so it shows up in the preview.
+pub fn replace_path_separators(name: &str) -> String {
+    name.replace(['/', '\\'], "-")
+}
+
+// Makes a single file or folder name valid for the profile.
+// Returns the new name and a warning for every change that was made.
+pub fn sanitize_file_name(name: &str, profile: SanitizeProfile) -> (String, Vec<String>) {
+    let mut warnings = Vec::new();
+    let mut name = replace_forbidden(name, profile, &mut warnings);
+
+    if profile.trims_trailing_dots() {
+        name = trim_trailing_dots(name, &mut warnings);
+    }
+
+    if profile.has_reserved_names() {
+        let base_len = name.find('.').unwrap_or(name.len());
+        let base = name[..base_len].trim_end();
+        if RESERVED_NAMES.contains(&base.to_uppercase().as_str()) {
+            warnings.push(format!(
+                "'{}' is a reserved name on Windows, added '_'",
+                base
+            ));
+            name.insert(base.len(), '_');
+        }
+    }
+
+    let length = profile.length(&name);
+    if length > MAX_NAME_LENGTH {
+        name = shorten(&name, profile);
+        if profile.trims_trailing_dots() {
+            name = name.trim_end_matches(['.', ' ']).to_string();
+        }
+        warnings.push(format!(
+            "Shortened from {} to {} {}",
+            length,
+            profile.length(&name),
+            profile.unit()
+        ));
+    }
+
+    if name.is_empty() {
+        warnings.push("Nothing was left of the name, using '_'".to_string());
+        name = "_".to_string();
+    }
+
+    (name, warnings)
+}
+
+// "Show: Title" becomes "Show - Title", other forbidden characters are replaced or removed
+fn replace_forbidden(name: &str, profile: SanitizeProfile, warnings: &mut Vec<String>) -> String {
+    let mut result = String::new();
+    // Counts per replaced character, in the order they first appear
+    let mut replaced: Vec<(char, &str, usize)> = Vec::new();
+
+    let chars: Vec<char> = name.chars().collect();
+    for (index, &c) in chars.iter().enumerate() {
+        if !profile.forbids(c) {
+            result.push(c);
+            continue;
+        }
+
+        let replacement = match c {
+            ':' if chars.get(index + 1) == Some(&' ') => " -",
+            ':' | '/' | '\\' | '|' => "-",
+            '"' => "'",
+            _ => "",
+        };
+        result.push_str(replacement);
+
+        let shown = replacement.trim();
+        match replaced.iter_mut().find(|(other, _, _)| *other == c) {
+            Some((_, _, count)) => *count += 1,
+            None => replaced.push((c, shown, 1)),
+        }
+    }
+
+    for (c, replacement, count) in replaced {
+        let times = if count > 1 {
+            format!(" ({}x)", count)
+        } else {
+            String::new()
+        };
+        warnings.push(if replacement.is_empty() {
+            format!("Removed {:?}{}", c, times)
+        } else {
+            format!("Replaced {:?} with {:?}{}", c, replacement, times)
+        });
+    }
+
+    result
+}
+
+// Windows silently drops trailing dots and spaces, which breaks the rename
+fn trim_trailing_dots(name: String, warnings: &mut Vec<String>) -> String {
+    let trimmed = name.trim_end_matches(['.', ' ']);
+    if trimmed.len() == name.len() {
+        return name;
+    }
+
+    warnings.push("Removed trailing dots and spaces".to_string());
+    trimmed.to_string()
+}
+
+// Cuts the end of the name before the extension, e.g. a long episode title
+fn shorten(name: &str, profile: SanitizeProfile) -> String {
+    let extension = match name.rfind('.') {
+        Some(dot) if dot > 0 && profile.length(&name[dot..]) < MAX_NAME_LENGTH / 2 => &name[dot..],
+        _ => "",
+    };
+    let stem = &name[..name.len() - extension.len()];
+    let budget = MAX_NAME_LENGTH - profile.length(extension);
+
+    let mut cut = String::new();
+    for c in stem.chars() {
+        cut.push(c);
+        if profile.length(&cut) > budget {
+            cut.pop();
+            break;
+        }
+    }
+
+    format!("{}{}", cut.trim_end(), extension)
+}
+
+// END SANITIZING
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn replaces_forbidden_characters() {
+        let (name, warnings) =
+            sanitize_file_name("Show: Part 1/2 \"Live\"?.mkv", SanitizeProfile::Windows);
+        assert_eq!(name, "Show - Part 1-2 'Live'.mkv");
+        assert_eq!(
+            warnings,
+            [
+                "Replaced ':' with \"-\"",
+                "Replaced '/' with \"-\"",
+                "Replaced '\"' with \"'\" (2x)",
+                "Removed '?'",
+            ]
+        );
+
+        let (name, warnings) = sanitize_file_name("Show: Part 1?.mkv", SanitizeProfile::Posix);
+        assert_eq!(name, "Show: Part 1?.mkv");
+        assert!(warnings.is_empty());
+    }
+
+    #[test]
+    fn avoids_reserved_names_and_trailing_dots() {
+        assert_eq!(
+            sanitize_file_name("con.mkv", SanitizeProfile::Windows).0,
+            "con_.mkv"
+        );
+        assert_eq!(
+            sanitize_file_name("con.mkv", SanitizeProfile::Exfat).0,
+            "con.mkv"
+        );
+        assert_eq!(
+            sanitize_file_name("Vol. 2...", SanitizeProfile::Smb).0,
+            "Vol. 2"
+        );
+        assert_eq!(
+            sanitize_file_name("Vol. 2...", SanitizeProfile::Posix).0,
+            "Vol. 2..."
+        );
+        assert_eq!(
+            sanitize_file_name("lpt0.srt", SanitizeProfile::Smb).0,
+            "lpt0_.srt"
+        );
+        assert_eq!(
+            sanitize_file_name("COM¹.mkv", SanitizeProfile::Windows).0,
+            "COM¹_.mkv"
+        );
+        assert_eq!(sanitize_file_name("???", SanitizeProfile::Windows).0, "_");
+    }
+
+    #[test]
+    fn shortens_long_names_before_the_extension() {
+        let long = format!("{}.mkv", "ä".repeat(200));
+
+        // 200 UTF-16 units fit on NTFS, 400 bytes do not fit on POSIX
+        assert_eq!(sanitize_file_name(&long, SanitizeProfile::Windows).0, long);
+        let (name, warnings) = sanitize_file_name(&long, SanitizeProfile::Posix);
+        assert_eq!(name, format!("{}.mkv", "ä".repeat(125)));
+        assert_eq!(warnings, ["Shortened from 404 to 254 bytes"]);
+    }
+
+    #[test]
+    fn defaults_to_names_that_are_valid_on_windows() {
+        let expected = if cfg!(windows) {
+            SanitizeProfile::Windows
+        } else {
+            SanitizeProfile::Smb
+        };
+        assert_eq!(SanitizeProfile::default(), expected);
+    }
+}
@@ -0,0 +1,470 @@
+use std::collections::HashMap;
+use std::fmt;
+
+// Every variable a template can use
+pub const TEMPLATE_VARIABLES: [&str; 12] = [
+    "show",
+    "year",
+    "season",
+    "episode",
+    "episode_end",
+    "absolute",
+    "air_date",
+    "title",
+    "group",
+    "resolution",
+    "original",
+    "ext",
+];
+
+// STRUCTS
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum TemplateValue {
+    Text(String),
+    Number(i32),
+}
+
+pub type TemplateValues = HashMap<String, TemplateValue>;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Case {
+    Upper,
+    Lower,
+    Title,
+}
+
+// `{season:02}` or `{title:upper}`
+#[derive(Debug, Clone, PartialEq)]
+struct Variable {
+    name: String,
+    width: Option<usize>,
+    case: Option<Case>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Piece {
+    Literal(String),
+    Variable(Variable),
+}
+
+// Text outside of braces, or a placeholder with its fallbacks.
+// `{title|"Episode " episode}` has two alternatives, the first complete one is used.
+#[derive(Debug, Clone, PartialEq)]
+enum Segment {
+    Literal(String),
+    Placeholder {
+        source: String,
+        alternatives: Vec<Vec<Piece>>,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TemplateError {
+    pub position: usize,
+    pub message: String,
+}
+
+impl fmt::Display for TemplateError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} (at position {})", self.message, self.position)
+    }
+}
+
+impl std::error::Error for TemplateError {}
+
+// A parsed template like `{show} - S{season:02}E{episode:02} - {title}.{ext}`.
+// `{{` and `}}` write literal braces.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FilenameTemplate {
+    segments: Vec<Segment>,
+}
+
+impl FilenameTemplate {
+    pub fn parse(template: &str) -> Result<Self, TemplateError> {
+        let chars: Vec<(usize, char)> = template.char_indices().collect();
+        let mut segments = Vec::new();
+        let mut literal = String::new();
+        let mut index = 0;
+
+        while index < chars.len() {
+            let (position, c) = chars[index];
+            let next = chars.get(index + 1).map(|(_, c)| *c);
+
+            match (c, next) {
+                ('{', Some('{')) | ('}', Some('}')) => {
+                    literal.push(c);
+                    index += 2;
+                }
+                ('}', _) => {
+                    return Err(template_error(position, "Unexpected '}'"));
+                }
+                ('{', _) => {
+                    let close = find_placeholder_end(&chars, index)?;
+                    let source: String = chars[index..=close].iter().map(|(_, c)| c).collect();
+
+                    if !literal.is_empty() {
+                        segments.push(Segment::Literal(std::mem::take(&mut literal)));
+                    }
+                    segments.push(Segment::Placeholder {
+                        alternatives: parse_alternatives(&chars[index + 1..close])?,
+                        source,
+                    });
+                    index = close + 1;
+                }
+                _ => {
+                    literal.push(c);
+                    index += 1;
+                }
+            }
+        }
+
+        if !literal.is_empty() {
+            segments.push(Segment::Literal(literal));
+        }
+
+        Ok(Self { segments })
+    }
+
+    // Fills in the values. Fails if no alternative of a placeholder has all its values.
+    pub fn render(&self, values: &TemplateValues) -> Result<String, String> {
+        let mut rendered = String::new();
+
+        for segment in &self.segments {
+            match segment {
+                Segment::Literal(text) => rendered.push_str(text),
+                Segment::Placeholder {
+                    source,
+                    alternatives,
+                } => {
+                    let text = alternatives
+                        .iter()
+                        .find_map(|pieces| render_alternative(pieces, values))
+                        .ok_or_else(|| format!("No value for {}", source))?;
+                    rendered.push_str(&text);
+                }
+            }
+        }
+
+        Ok(rendered)
+    }
+
+    // Renders a whole file name. Templates without `{ext}` keep the extension of the file,
+    // so `{show} {absolute:03}` does not turn a video into a file without extension.
+    pub fn render_file_name(&self, values: &TemplateValues) -> Result<String, String> {
+        let mut rendered = self.render(values)?;
+        if let Some(TemplateValue::Text(ext)) = values.get("ext") {
+            if !ext.is_empty() && !self.uses_variable("ext") {
+                rendered.push('.');
+                rendered.push_str(ext);
+            }
+        }
+        Ok(rendered)
+    }
+
+    fn uses_variable(&self, name: &str) -> bool {
+        self.segments.iter().any(|segment| match segment {
+            Segment::Literal(_) => false,
+            Segment::Placeholder { alternatives, .. } => {
+                alternatives.iter().flatten().any(|piece| match piece {
+                    Piece::Variable(variable) => variable.name == name,
+                    Piece::Literal(_) => false,
+                })
+            }
+        })
+    }
+}
+
+// START PARSING
+
+fn template_error(position: usize, message: impl Into<String>) -> TemplateError {
+    TemplateError {
+        position,
+        message: message.into(),
+    }
+}
+
+// Index of the `}` closing the placeholder opened at `open`, skipping quoted text
+fn find_placeholder_end(chars: &[(usize, char)], open: usize) -> Result<usize, TemplateError> {
+    let mut in_quotes = false;
+    let mut index = open + 1;
+
+    while index < chars.len() {
+        match chars[index].1 {
+            '\\' if in_quotes => index += 1, // Skip the escaped character
+            '"' => in_quotes = !in_quotes,
+            '{' if !in_quotes => {
+                return Err(template_error(
+                    chars[index].0,
+                    "Unexpected '{' inside a placeholder",
+                ))
+            }
+            '}' if !in_quotes => return Ok(index),
+            _ => {}
+        }
+        index += 1;
+    }
+
+    let message = if in_quotes {
+        "Unclosed '\"' in placeholder"
+    } else {
+        "Unclosed '{'"
+    };
+    Err(template_error(chars[open].0, message))
+}
+
+// Splits `title|"Episode " episode` at every `|` outside of quotes
+fn parse_alternatives(chars: &[(usize, char)]) -> Result<Vec<Vec<Piece>>, TemplateError> {
+    let mut alternatives = Vec::new();
+    let mut pieces = Vec::new();
+    let mut index = 0;
+
+    while index < chars.len() {
+        let (position, c) = chars[index];
+
+        if c.is_whitespace() {
+            index += 1;
+        } else if c == '|' {
+            alternatives.push(std::mem::take(&mut pieces));
+            index += 1;
+        } else if c == '"' {
+            let mut text = String::new();
+            index += 1;
+            while index < chars.len() && chars[index].1 != '"' {
+                if chars[index].1 == '\\' && index + 1 < chars.len() {
+                    index += 1;
+                }
+                text.push(chars[index].1);
+                index += 1;
+            }
+            index += 1; // Skip the closing quote, `find_placeholder_end` made sure it exists
+            pieces.push(Piece::Literal(text));
+        } else if c.is_ascii_alphabetic() || c == '_' {
+            let start = index;
+            while index < chars.len()
+                && (chars[index].1.is_ascii_alphanumeric() || chars[index].1 == '_')
+            {
+                index += 1;
+            }
+            let name: String = chars[start..index].iter().map(|(_, c)| c).collect();
+            if !TEMPLATE_VARIABLES.contains(&name.as_str()) {
+                return Err(template_error(
+                    position,
+                    format!(
+                        "Unknown variable '{}', expected one of: {}",
+                        name,
+                        TEMPLATE_VARIABLES.join(", ")
+                    ),
+                ));
+            }
+
+            let mut variable = Variable {
+                name,
+                width: None,
+                case: None,
+            };
+
+            // Optional format after the name, e.g. `:02`, `:upper` or `:03,lower`
+            if index < chars.len() && chars[index].1 == ':' {
+                index += 1;
+                let spec_start = index;
+                while index < chars.len()
+                    && (chars[index].1.is_ascii_alphanumeric() || chars[index].1 == ',')
+                {
+                    index += 1;
+                }
+                let spec: String = chars[spec_start..index].iter().map(|(_, c)| c).collect();
+                let spec_position = chars
+                    .get(spec_start)
+                    .map(|(position, _)| *position)
+                    .unwrap_or(position);
+                apply_format(&mut variable, &spec, spec_position)?;
+            }
+
+            pieces.push(Piece::Variable(variable));
+        } else {
+            return Err(template_error(
+                position,
+                format!("Unexpected '{}' inside a placeholder", c),
+            ));
+        }
+    }
+    alternatives.push(pieces);
+
+    if alternatives.len() == 1 && alternatives[0].is_empty() {
+        let position = chars.first().map(|(position, _)| *position).unwrap_or(0);
+        return Err(template_error(position, "Empty placeholder"));
+    }
+
+    Ok(alternatives)
+}
+
+fn apply_format(variable: &mut Variable, spec: &str, position: usize) -> Result<(), TemplateError> {
+    if spec.is_empty() {
+        return Err(template_error(position, "Missing format after ':'"));
+    }
+
+    for part in spec.split(',') {
+        match part {
+            "upper" => variable.case = Some(Case::Upper),
+            "lower" => variable.case = Some(Case::Lower),
+            "title" => variable.case = Some(Case::Title),
+            _ => match part.parse::<usize>() {
+                Ok(width) if width <= 10 => variable.width = Some(width),
+                _ => {
+                    return Err(template_error(
+                        position,
+                        format!(
+                            "Unknown format '{}', expected a width like 02 or upper, lower, title",
+                            part
+                        ),
+                    ))
+                }
+            },
+        }
+    }
+
+    Ok(())
+}
+
+// END PARSING
+
+// START RENDERING
+
+// Renders one alternative, or None if one of its values is missing or empty
+fn render_alternative(pieces: &[Piece], values: &TemplateValues) -> Option<String> {
+    let mut rendered = String::new();
+
+    for piece in pieces {
+        match piece {
+            Piece::Literal(text) => rendered.push_str(text),
+            Piece::Variable(variable) => {
+                let text = match values.get(&variable.name)? {
+                    TemplateValue::Number(number) => match variable.width {
+                        Some(width) => format!("{:0width$}", number, width = width),
+                        None => number.to_string(),
+                    },
+                    TemplateValue::Text(text) if text.trim().is_empty() => return None,
+                    TemplateValue::Text(text) => text.clone(),
+                };
+
+                let text = match variable.case {
+                    Some(Case::Upper) => text.to_uppercase(),
+                    Some(Case::Lower) => text.to_lowercase(),
+                    Some(Case::Title) => title_case(&text),
+                    None => text,
+                };
+                rendered.push_str(&text);
+            }
+        }
+    }
+
+    Some(rendered)
+}
+
+fn title_case(text: &str) -> String {
+    let mut result = String::new();
+    let mut word_start = true;
+
+    for c in text.chars() {
+        if word_start {
+            result.extend(c.to_uppercase());
+        } else {
+            result.extend(c.to_lowercase());
+        }
+        word_start = c.is_whitespace() || c == '-' || c == '.' || c == '_';
+    }
+
+    result
+}
+
+// END RENDERING
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn values(pairs: &[(&str, TemplateValue)]) -> TemplateValues {
+        pairs
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.clone()))
+            .collect()
+    }
+
+    fn episode() -> TemplateValues {
+        let text = |value: &str| TemplateValue::Text(value.to_string());
+        values(&[
+            ("show", text("Show")),
+            ("season", TemplateValue::Number(1)),
+            ("episode", TemplateValue::Number(2)),
+            ("absolute", TemplateValue::Number(14)),
+            ("title", text("the pilot")),
+            ("ext", text("mkv")),
+        ])
+    }
+
+    fn render(template: &str, values: &TemplateValues) -> Result<String, String> {
+        FilenameTemplate::parse(template)
+            .unwrap()
+            .render_file_name(values)
+    }
+
+    #[test]
+    fn renders_variables_with_formats() {
+        assert_eq!(
+            render(
+                "{show} - S{season:02}E{episode:02} - {title:title}.{ext}",
+                &episode()
+            ),
+            Ok("Show - S01E02 - The Pilot.mkv".to_string())
+        );
+        assert_eq!(
+            render("{show:upper} {absolute:03}", &episode()),
+            Ok("SHOW 014.mkv".to_string())
+        );
+    }
+
+    #[test]
+    fn keeps_the_extension_without_ext() {
+        assert_eq!(
+            render("{show} {absolute:03} [{title}]", &episode()),
+            Ok("Show 014 [the pilot].mkv".to_string())
+        );
+        assert_eq!(
+            render("{show}.{ext|\"avi\"}", &episode()),
+            Ok("Show.mkv".to_string())
+        );
+    }
+
+    #[test]
+    fn uses_the_first_complete_alternative() {
+        let template = "{show} - {year|\"Episode \" episode:02}.{ext}";
+        assert_eq!(
+            render(template, &episode()),
+            Ok("Show - Episode 02.mkv".to_string())
+        );
+        assert!(render("{year}.{ext}", &episode()).is_err());
+    }
+
+    #[test]
+    fn writes_escaped_braces() {
+        assert_eq!(
+            render("{{{show}}}.{ext}", &episode()),
+            Ok("{Show}.mkv".to_string())
+        );
+    }
+
+    #[test]
+    fn reports_parse_errors_with_position() {
+        let error = |template: &str| FilenameTemplate::parse(template).unwrap_err();
+
+        assert_eq!(error("{show").position, 0);
+        assert_eq!(error("{show}}").message, "Unexpected '}'");
+        assert!(error("{name}")
+            .message
+            .starts_with("Unknown variable 'name'"));
+        assert_eq!(error("a {season:wide}").position, 10);
+        assert_eq!(error("{}").message, "Empty placeholder");
+        assert_eq!(error("{\"x}").message, "Unclosed '\"' in placeholder");
+    }
+}
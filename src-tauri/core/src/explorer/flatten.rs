@@ -0,0 +1,458 @@
+use serde::Deserialize;
+use std::cmp::Reverse;
+use std::collections::HashSet;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::explorer::companions::{is_companion_extension, Companions};
+use crate::explorer::folders::collect_folders;
+use crate::explorer::media_files::{is_sample, is_video_file};
+use crate::explorer::rename_plan::RenamePlan;
+use crate::explorer::selection::FileSelection;
+
+// Files that only clutter a release folder
+const JUNK_NAMES: [&str; 3] = ["thumbs.db", ".ds_store", "desktop.ini"];
+
+// STRUCTS
+
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum FileRule {
+    Bring, // Moved up with the videos
+    Delete,
+    Leave, // Stays, so its folder is kept
+}
+
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum ConflictPolicy {
+    Skip,      // The file stays where it is
+    Suffix,    // "Name (1).mkv"
+    Overwrite, // The existing file is deleted
+}
+
+// How `flatten_plan` pulls files out of the subfolders of a folder
+#[derive(Debug, Deserialize, Clone)]
+#[serde(default)]
+pub struct FlattenOptions {
+    pub depth: usize,       // Levels of subfolders that are emptied
+    pub sidecars: FileRule, // Subtitles, .nfo files and artwork
+    pub samples: FileRule,  // "sample.mkv", "show-sample.mkv" or anything in a "Sample" folder
+    pub junk: FileRule,     // Everything else, e.g. .url, .exe or Thumbs.db
+    pub conflict: ConflictPolicy,
+}
+
+impl Default for FlattenOptions {
+    fn default() -> Self {
+        Self {
+            depth: 1,
+            sidecars: FileRule::Bring,
+            samples: FileRule::Leave,
+            junk: FileRule::Leave,
+            conflict: ConflictPolicy::Skip,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum FileKind {
+    Video,
+    Sidecar,
+    Sample,
+    Junk,
+}
+
+impl FileKind {
+    fn label(&self) -> &'static str {
+        match self {
+            FileKind::Video => "video",
+            FileKind::Sidecar => "sidecar file",
+            FileKind::Sample => "sample",
+            FileKind::Junk => "junk file",
+        }
+    }
+}
+
+// Where a file and the files that follow it end up, by their new stem
+enum Placement {
+    Move(String),
+    Replace(String), // Existing files with the new names are deleted first
+    Skip(String),
+}
+
+// START FLATTENING
+
+// Moves the files of the subfolders of `root` into `root` and removes the folders
+// that end up empty. Folders that keep a file, e.g. a sample that is left or a file
+// outside the selection, stay.
+pub fn flatten_plan(
+    root: &Path,
+    options: &FlattenOptions,
+    selection: &FileSelection,
+) -> io::Result<RenamePlan> {
+    let folders: Vec<PathBuf> = collect_folders(root, options.depth)?
+        .into_iter()
+        .filter(|folder| folder != root)
+        .collect();
+
+    let mut flattening = Flattening::new(root, options, selection);
+    for folder in &folders {
+        flattening.flatten_folder(folder)?;
+    }
+    flattening.remove_emptied_folders(&folders)?;
+
+    Ok(flattening.plan)
+}
+
+struct Flattening<'a> {
+    root: &'a Path,
+    options: &'a FlattenOptions,
+    selection: &'a FileSelection,
+    companions: Companions,
+    claimed: HashSet<String>, // Lower case names in `root` that files are moved to
+    handled: HashSet<PathBuf>, // Files that are moved or deleted
+    kept: HashSet<PathBuf>,   // Sidecars that stay with their skipped video
+    plan: RenamePlan,
+}
+
+impl<'a> Flattening<'a> {
+    fn new(root: &'a Path, options: &'a FlattenOptions, selection: &'a FileSelection) -> Self {
+        Self {
+            root,
+            options,
+            selection,
+            companions: Companions::new(),
+            claimed: HashSet::new(),
+            handled: HashSet::new(),
+            kept: HashSet::new(),
+            plan: RenamePlan::new(),
+        }
+    }
+
+    fn flatten_folder(&mut self, folder: &Path) -> io::Result<()> {
+        let mut files = Vec::new();
+        for entry in fs::read_dir(folder)? {
+            let path = entry?.path();
+            if path.is_file() && self.selection.contains(&path) {
+                files.push(path);
+            }
+        }
+        files.sort();
+
+        // Videos first, so their sidecars can follow them
+        for path in &files {
+            if self.kind(path) == FileKind::Video {
+                self.bring_video(path)?;
+            }
+        }
+
+        for path in files {
+            if self.handled.contains(&path) || self.kept.contains(&path) {
+                continue;
+            }
+            let kind = self.kind(&path);
+            let rule = match kind {
+                FileKind::Video => continue,
+                FileKind::Sidecar => self.options.sidecars,
+                FileKind::Sample => self.options.samples,
+                FileKind::Junk => self.options.junk,
+            };
+
+            match rule {
+                FileRule::Bring => self.bring_file(&path),
+                FileRule::Delete => {
+                    let reason = format!("Deleted {}, undo can not restore it", kind.label());
+                    self.plan.delete_file(path.clone(), reason);
+                    self.handled.insert(path);
+                }
+                FileRule::Leave => {
+                    let reason = format!("The {} stays in its folder", kind.label());
+                    self.plan.skip(path, reason);
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn kind(&self, path: &Path) -> FileKind {
+        let name = path
+            .file_name()
+            .map(|name| name.to_string_lossy().to_lowercase())
+            .unwrap_or_default();
+
+        if JUNK_NAMES.contains(&name.as_str()) {
+            FileKind::Junk
+        } else if is_sample(path, self.root) {
+            FileKind::Sample
+        } else if is_video_file(path) {
+            FileKind::Video
+        } else if is_companion_extension(path) {
+            FileKind::Sidecar
+        } else {
+            FileKind::Junk
+        }
+    }
+
+    // Moves a video up, its subtitles and artwork follow it if sidecars are brought
+    fn bring_video(&mut self, video: &Path) -> io::Result<()> {
+        let stem = video
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().to_string())
+            .unwrap_or_default();
+        // Sidecars outside the selection stay, like every other unselected file
+        let companions = if self.options.sidecars == FileRule::Bring {
+            self.companions
+                .find(video)?
+                .into_iter()
+                .filter(|companion| self.selection.contains(companion))
+                .collect()
+        } else {
+            vec![]
+        };
+
+        // Every name is the stem followed by a suffix, ".mkv" or ".en.srt"
+        let suffix_of = |path: &Path| {
+            let name = path
+                .file_name()
+                .map(|name| name.to_string_lossy().to_string())
+                .unwrap_or_default();
+            name.get(stem.len()..).unwrap_or_default().to_string()
+        };
+        let mut suffixes = vec![suffix_of(video)];
+        suffixes.extend(companions.iter().map(|companion| suffix_of(companion)));
+
+        if let Some(new_stem) = self.place(video, &stem, &suffixes) {
+            let target = self.claim(&new_stem, &suffixes[0]);
+            self.plan.push(video.to_path_buf(), target);
+            self.handled.insert(video.to_path_buf());
+            for (companion, suffix) in companions.into_iter().zip(&suffixes[1..]) {
+                let target = self.claim(&new_stem, suffix);
+                self.plan.push_companion(video, companion.clone(), target);
+                self.handled.insert(companion);
+            }
+        } else {
+            let video_name = video
+                .file_name()
+                .map(|name| name.to_string_lossy().to_string())
+                .unwrap_or_default();
+            for companion in companions {
+                self.plan
+                    .skip(companion.clone(), format!("Stays with {}", video_name));
+                self.kept.insert(companion);
+            }
+        }
+
+        Ok(())
+    }
+
+    fn bring_file(&mut self, path: &Path) {
+        let name = path
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let (stem, suffix) = match name.rfind('.') {
+            Some(dot) if dot > 0 => (name[..dot].to_string(), name[dot..].to_string()),
+            _ => (name.clone(), String::new()),
+        };
+
+        if let Some(new_stem) = self.place(path, &stem, std::slice::from_ref(&suffix)) {
+            let target = self.claim(&new_stem, &suffix);
+            self.plan.push(path.to_path_buf(), target);
+            self.handled.insert(path.to_path_buf());
+        }
+    }
+
+    // Resolves name conflicts in `root` by the conflict policy.
+    // Returns the new stem, or None if the file is skipped.
+    fn place(&mut self, path: &Path, stem: &str, suffixes: &[String]) -> Option<String> {
+        let placement = self.placement(stem, suffixes);
+        match placement {
+            Placement::Move(new_stem) => {
+                if new_stem != stem {
+                    self.plan
+                        .note(path, "Renamed, a file with this name already exists");
+                }
+                Some(new_stem)
+            }
+            Placement::Replace(new_stem) => {
+                let source_name = path
+                    .file_name()
+                    .map(|name| name.to_string_lossy().to_string())
+                    .unwrap_or_default();
+                for suffix in suffixes {
+                    let existing = self.root.join(format!("{}{}", new_stem, suffix));
+                    if existing.is_file() {
+                        let reason =
+                            format!("Replaced by {}, undo can not restore it", source_name);
+                        self.plan.delete_file(existing, reason);
+                    }
+                }
+                self.plan.note(path, "Replaces the existing file");
+                Some(new_stem)
+            }
+            Placement::Skip(reason) => {
+                self.plan.skip(path.to_path_buf(), reason);
+                None
+            }
+        }
+    }
+
+    fn placement(&self, stem: &str, suffixes: &[String]) -> Placement {
+        let names = |stem: &str| -> Vec<String> {
+            suffixes
+                .iter()
+                .map(|suffix| format!("{}{}", stem, suffix))
+                .collect()
+        };
+        let taken: Vec<String> = names(stem)
+            .into_iter()
+            .filter(|name| !self.is_free(name))
+            .collect();
+        if taken.is_empty() {
+            return Placement::Move(stem.to_string());
+        }
+
+        match self.options.conflict {
+            ConflictPolicy::Skip => Placement::Skip(format!("{} already exists", taken.join(", "))),
+            ConflictPolicy::Suffix => {
+                let mut counter = 1;
+                loop {
+                    let candidate = format!("{} ({})", stem, counter);
+                    if names(&candidate).iter().all(|name| self.is_free(name)) {
+                        return Placement::Move(candidate);
+                    }
+                    counter += 1;
+                }
+            }
+            ConflictPolicy::Overwrite => {
+                // Only files that were there before are overwritten, never another moved file
+                let blocked = taken.iter().find(|name| {
+                    self.claimed.contains(&name.to_lowercase()) || !self.root.join(name).is_file()
+                });
+                match blocked {
+                    Some(name) => Placement::Skip(format!("Another file is moved to {}", name)),
+                    None => Placement::Replace(stem.to_string()),
+                }
+            }
+        }
+    }
+
+    fn is_free(&self, name: &str) -> bool {
+        !self.claimed.contains(&name.to_lowercase()) && !self.root.join(name).exists()
+    }
+
+    fn claim(&mut self, stem: &str, suffix: &str) -> PathBuf {
+        let name = format!("{}{}", stem, suffix);
+        self.claimed.insert(name.to_lowercase());
+        self.root.join(name)
+    }
+
+    // Deepest folders first, a folder is empty once all of its files and folders are gone
+    fn remove_emptied_folders(&mut self, folders: &[PathBuf]) -> io::Result<()> {
+        let mut deepest_first = folders.to_vec();
+        deepest_first.sort_by_key(|folder| Reverse(folder.components().count()));
+
+        let mut removed = HashSet::new();
+        for folder in deepest_first {
+            let mut is_emptied = true;
+            for entry in fs::read_dir(&folder)? {
+                let path = entry?.path();
+                if !self.handled.contains(&path) && !removed.contains(&path) {
+                    is_emptied = false;
+                    break;
+                }
+            }
+            if is_emptied {
+                self.plan.remove_dir(folder.clone());
+                removed.insert(folder);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+// END FLATTENING
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::explorer::selection::SelectionOptions;
+    use crate::explorer::test_support::TestDir;
+
+    fn flatten(dir: &TestDir, options: &FlattenOptions) {
+        let plan = flatten_plan(&dir.join(""), options, &FileSelection::all()).unwrap();
+        let report = plan.execute();
+        assert!(report.failed.is_none(), "{:?}", report.failed);
+    }
+
+    #[test]
+    fn brings_videos_with_their_sidecars() {
+        let dir = TestDir::new();
+        dir.file("Show.S01/Show.S01E01.mkv");
+        dir.file("Show.S01/Show.S01E01.en.srt");
+        dir.file("Show.S01/Thumbs.db");
+
+        let options = FlattenOptions {
+            junk: FileRule::Delete,
+            ..FlattenOptions::default()
+        };
+        flatten(&dir, &options);
+
+        assert_eq!(dir.names(), ["Show.S01E01.en.srt", "Show.S01E01.mkv"]);
+        assert_eq!(dir.read("Show.S01E01.mkv"), "Show.S01/Show.S01E01.mkv");
+    }
+
+    #[test]
+    fn only_takes_sample_names_as_samples() {
+        let dir = TestDir::new();
+        dir.file("Show/Free Samples.S01E01.mkv");
+        dir.file("Show/Sample Size.S01E02.mkv");
+        dir.file("Show/show.s01e01-sample.mkv");
+        dir.file("Show/Sample.mkv");
+        dir.file("Show/Sample/Clip.mkv");
+
+        flatten(&dir, &FlattenOptions::default());
+
+        assert_eq!(
+            dir.names(),
+            ["Free Samples.S01E01.mkv", "Sample Size.S01E02.mkv", "Show"]
+        );
+    }
+
+    #[test]
+    fn leaves_sidecars_outside_the_selection() {
+        let dir = TestDir::new();
+        dir.file("Show/Show.S01E01.mkv");
+        dir.file("Show/Show.S01E01.srt");
+
+        let selection = FileSelection::new(SelectionOptions {
+            exclude: vec!["*.srt".to_string()],
+            ..SelectionOptions::default()
+        })
+        .unwrap();
+        let plan = flatten_plan(&dir.join(""), &FlattenOptions::default(), &selection).unwrap();
+        assert!(plan.execute().failed.is_none());
+
+        assert_eq!(dir.names(), ["Show", "Show.S01E01.mkv"]);
+        assert!(dir.join("Show/Show.S01E01.srt").is_file());
+    }
+
+    #[test]
+    fn suffixes_names_that_are_taken() {
+        let dir = TestDir::new();
+        dir.file("Movie.mkv");
+        dir.file("A/Movie.mkv");
+
+        let options = FlattenOptions {
+            conflict: ConflictPolicy::Suffix,
+            ..FlattenOptions::default()
+        };
+        flatten(&dir, &options);
+
+        assert_eq!(dir.names(), ["Movie (1).mkv", "Movie.mkv"]);
+        assert_eq!(dir.read("Movie (1).mkv"), "A/Movie.mkv");
+    }
+}
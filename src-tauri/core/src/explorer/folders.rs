@@ -0,0 +1,49 @@
+use regex::Regex;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+// The folder itself followed by its subfolders down to `subfolder_depth` levels,
+// sorted by path. Hidden folders like ".git" are left out.
+pub fn collect_folders(root: &Path, subfolder_depth: usize) -> io::Result<Vec<PathBuf>> {
+    let mut folders = vec![root.to_path_buf()];
+    let mut current_level = vec![root.to_path_buf()];
+
+    for _ in 0..subfolder_depth {
+        let mut next_level = Vec::new();
+        for folder in &current_level {
+            for entry in fs::read_dir(folder)? {
+                let path = entry?.path();
+                let is_hidden = path
+                    .file_name()
+                    .map(|name| name.to_string_lossy().starts_with('.'))
+                    .unwrap_or(true);
+                if path.is_dir() && !is_hidden {
+                    next_level.push(path);
+                }
+            }
+        }
+        if next_level.is_empty() {
+            break;
+        }
+        next_level.sort();
+        folders.extend(next_level.iter().cloned());
+        current_level = next_level;
+    }
+
+    Ok(folders)
+}
+
+// Reads the season from folder names like "Season 02", "S2", "Staffel 2" or "Specials"
+pub fn season_from_folder_name(folder: &Path) -> Option<i32> {
+    let name = folder.file_name()?.to_str()?.trim();
+    let pattern = Regex::new(r"(?i)^(?:season|staffel|saison|series|s)[ ._-]*(\d{1,3})$").unwrap();
+
+    if let Some(caps) = pattern.captures(name) {
+        return caps[1].parse().ok();
+    }
+    if name.eq_ignore_ascii_case("specials") || name.eq_ignore_ascii_case("extras") {
+        return Some(0);
+    }
+    None
+}
@@ -0,0 +1,477 @@
+use crate::explorer::rename_plan::{PreviewEntry, RenamePlan, RenameReport};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+// Oldest batches are dropped once the journal grows past this
+const MAX_JOURNAL_BATCHES: usize = 100;
+// How long to wait for the app or the CLI to finish with the journal
+const LOCK_TIMEOUT: Duration = Duration::from_secs(10);
+// A lock older than this was left behind by a crashed process
+const STALE_LOCK_AGE: Duration = Duration::from_secs(120);
+
+// STRUCTS
+
+// Size and modification time of a file right after a batch moved it
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct FileFingerprint {
+    pub size: u64,
+    pub modified_ms: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct JournalRename {
+    pub from: PathBuf,
+    pub to: PathBuf,
+    pub fingerprint: FileFingerprint,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct JournalBatch {
+    pub id: String,
+    pub label: String,
+    pub created_at_ms: u64,
+    pub renames: Vec<JournalRename>,
+    pub created_dirs: Vec<PathBuf>,
+    pub removed_dirs: Vec<PathBuf>,
+    // Deleted files are gone for good, undo can not bring them back
+    #[serde(default)]
+    pub deleted: Vec<PathBuf>,
+}
+
+// Batches before `position` are applied and can be undone,
+// batches from `position` on were undone and can be redone
+#[derive(Debug, Serialize, Deserialize, Default)]
+pub struct OperationJournal {
+    pub batches: Vec<JournalBatch>,
+    pub position: usize,
+}
+
+// JOURNAL FILE
+
+fn journal_path() -> Result<PathBuf, String> {
+    let data_dir = dirs::data_local_dir().ok_or("Failed to get local data directory")?;
+    Ok(data_dir
+        .join("script-kiddie")
+        .join("operation_journal.json"))
+}
+
+// Held while the journal is read, changed and written. The app and the CLI share the file.
+struct JournalLock {
+    path: PathBuf,
+}
+
+impl JournalLock {
+    fn acquire(journal: &Path) -> Result<Self, String> {
+        if let Some(parent) = journal.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create journal directory: {}", e))?;
+        }
+        let path = journal.with_extension("lock");
+        let started = SystemTime::now();
+
+        loop {
+            match fs::OpenOptions::new()
+                .write(true)
+                .create_new(true)
+                .open(&path)
+            {
+                Ok(_) => return Ok(Self { path }),
+                Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => {
+                    let is_stale = fs::metadata(&path)
+                        .and_then(|metadata| metadata.modified())
+                        .ok()
+                        .and_then(|modified| modified.elapsed().ok())
+                        .map_or(false, |age| age > STALE_LOCK_AGE);
+                    if is_stale {
+                        let _ = fs::remove_file(&path);
+                        continue;
+                    }
+                    if started
+                        .elapsed()
+                        .map_or(true, |waited| waited > LOCK_TIMEOUT)
+                    {
+                        return Err(
+                            "The journal is in use by another Script Kiddie process.".to_string()
+                        );
+                    }
+                    thread::sleep(Duration::from_millis(50));
+                }
+                Err(e) => return Err(format!("Failed to lock journal: {}", e)),
+            }
+        }
+    }
+}
+
+impl Drop for JournalLock {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+fn load_journal(path: &Path) -> Result<OperationJournal, String> {
+    if !path.exists() {
+        return Ok(OperationJournal::default());
+    }
+
+    let content = fs::read_to_string(path).map_err(|e| format!("Failed to read journal: {}", e))?;
+    let mut journal: OperationJournal =
+        serde_json::from_str(&content).map_err(|e| format!("Failed to parse journal: {}", e))?;
+    journal.position = journal.position.min(journal.batches.len());
+
+    Ok(journal)
+}
+
+fn save_journal(path: &Path, journal: &OperationJournal) -> Result<(), String> {
+    // Write to a temporary file first so a crash never leaves a truncated journal
+    let content = serde_json::to_string_pretty(journal).map_err(|e| e.to_string())?;
+    let temp_path = path.with_extension("json.tmp");
+    fs::write(&temp_path, content).map_err(|e| format!("Failed to write journal: {}", e))?;
+    fs::rename(&temp_path, path).map_err(|e| format!("Failed to write journal: {}", e))?;
+
+    Ok(())
+}
+
+fn fingerprint(path: &Path) -> Option<FileFingerprint> {
+    let metadata = fs::metadata(path).ok()?;
+    let modified_ms = metadata
+        .modified()
+        .ok()?
+        .duration_since(UNIX_EPOCH)
+        .ok()?
+        .as_millis() as u64;
+
+    Some(FileFingerprint {
+        size: metadata.len(),
+        modified_ms,
+    })
+}
+
+fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+// Records a successfully executed plan. Failed reports are not recorded,
+// their changes have already been rolled back.
+pub fn record_operation(label: &str, report: &RenameReport) -> Result<(), String> {
+    record_operation_in(&journal_path()?, label, report)
+}
+
+fn record_operation_in(path: &Path, label: &str, report: &RenameReport) -> Result<(), String> {
+    let is_empty = report.renamed.is_empty()
+        && report.created_dirs.is_empty()
+        && report.removed_dirs.is_empty()
+        && report.deleted.is_empty();
+    if report.failed.is_some() || is_empty {
+        return Ok(());
+    }
+
+    let renames = report
+        .renamed
+        .iter()
+        .map(|operation| JournalRename {
+            from: operation.from.clone(),
+            to: operation.to.clone(),
+            fingerprint: fingerprint(&operation.to).unwrap_or(FileFingerprint {
+                size: 0,
+                modified_ms: 0,
+            }),
+        })
+        .collect();
+
+    let _lock = JournalLock::acquire(path)?;
+    let mut journal = load_journal(path)?;
+    journal.batches.truncate(journal.position); // A new batch discards the redo history
+    journal.batches.push(JournalBatch {
+        id: uuid::Uuid::new_v4().to_string(),
+        label: label.to_string(),
+        created_at_ms: now_ms(),
+        renames,
+        created_dirs: report.created_dirs.clone(),
+        removed_dirs: report.removed_dirs.clone(),
+        deleted: report.deleted.clone(),
+    });
+
+    if journal.batches.len() > MAX_JOURNAL_BATCHES {
+        let overflow = journal.batches.len() - MAX_JOURNAL_BATCHES;
+        journal.batches.drain(..overflow);
+    }
+    journal.position = journal.batches.len();
+
+    save_journal(path, &journal)
+}
+
+// Ensures every file is still exactly where and how the batch left it
+fn verify_unchanged(batch: &JournalBatch, undo: bool) -> Result<(), String> {
+    let action = if undo { "undone" } else { "redone" };
+    let locations = |rename: &JournalRename| {
+        if undo {
+            (rename.to.clone(), rename.from.clone())
+        } else {
+            (rename.from.clone(), rename.to.clone())
+        }
+    };
+    // A previous path may still exist if another file of the batch moved onto it
+    let current_paths: HashSet<PathBuf> = batch
+        .renames
+        .iter()
+        .map(|rename| locations(rename).0)
+        .collect();
+
+    for rename in &batch.renames {
+        let (current, previous) = locations(rename);
+
+        if fingerprint(&current).as_ref() != Some(&rename.fingerprint) {
+            return Err(format!(
+                "'{}' can not be {}: {} was moved or modified since.",
+                batch.label,
+                action,
+                current.display()
+            ));
+        }
+        if previous.exists() && !current_paths.contains(&previous) {
+            return Err(format!(
+                "'{}' can not be {}: {} already exists.",
+                batch.label,
+                action,
+                previous.display()
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+fn undo_plan(batch: &JournalBatch) -> RenamePlan {
+    let mut plan = RenamePlan::new();
+    for dir in &batch.removed_dirs {
+        plan.create_dir(dir.clone());
+    }
+    for rename in batch.renames.iter().rev() {
+        plan.push(rename.to.clone(), rename.from.clone());
+    }
+    for dir in batch.created_dirs.iter().rev() {
+        plan.remove_dir(dir.clone());
+    }
+    plan
+}
+
+fn redo_plan(batch: &JournalBatch) -> RenamePlan {
+    let mut plan = RenamePlan::new();
+    for dir in &batch.created_dirs {
+        plan.create_dir(dir.clone());
+    }
+    for rename in &batch.renames {
+        plan.push(rename.from.clone(), rename.to.clone());
+    }
+    for dir in &batch.removed_dirs {
+        plan.remove_dir(dir.clone());
+    }
+    plan
+}
+
+fn run_plan(plan: &RenamePlan) -> Result<RenameReport, String> {
+    plan.validate().map_err(|e| e.to_string())?;
+    let report = plan.execute();
+
+    if let Some(failure) = &report.failed {
+        return Err(format!(
+            "Failed at {}: {}. All changes were rolled back.",
+            failure.path.display(),
+            failure.error
+        ));
+    }
+
+    Ok(report)
+}
+
+// UNDO / REDO
+
+// Undoes the most recent batch, returns its label and what was moved back
+pub fn undo_last_operation() -> Result<(String, RenameReport), String> {
+    undo_last_operation_in(&journal_path()?)
+}
+
+fn undo_last_operation_in(path: &Path) -> Result<(String, RenameReport), String> {
+    let _lock = JournalLock::acquire(path)?;
+    let mut journal = load_journal(path)?;
+    if journal.position == 0 {
+        return Err("Nothing to undo.".to_string());
+    }
+
+    let batch = journal.batches[journal.position - 1].clone();
+    verify_unchanged(&batch, true)?;
+    let report = run_plan(&undo_plan(&batch))?;
+
+    journal.position -= 1;
+    save_journal(path, &journal)?;
+
+    Ok((undo_label(&batch), report))
+}
+
+// Shows what undoing the most recent batch would move back, without touching a file
+pub fn preview_undo() -> Result<(String, Vec<PreviewEntry>), String> {
+    preview_undo_in(&journal_path()?)
+}
+
+fn preview_undo_in(path: &Path) -> Result<(String, Vec<PreviewEntry>), String> {
+    let _lock = JournalLock::acquire(path)?;
+    let journal = load_journal(path)?;
+    if journal.position == 0 {
+        return Err("Nothing to undo.".to_string());
+    }
+
+    let batch = &journal.batches[journal.position - 1];
+    verify_unchanged(batch, true)?;
+    Ok((undo_label(batch), undo_plan(batch).preview()))
+}
+
+fn undo_label(batch: &JournalBatch) -> String {
+    match batch.deleted.len() {
+        0 => batch.label.clone(),
+        1 => format!("{} (1 deleted file can not be restored)", batch.label),
+        count => format!(
+            "{} ({} deleted files can not be restored)",
+            batch.label, count
+        ),
+    }
+}
+
+// Redoes the most recently undone batch, returns its label and what was moved again
+pub fn redo_operation() -> Result<(String, RenameReport), String> {
+    redo_operation_in(&journal_path()?)
+}
+
+fn redo_operation_in(path: &Path) -> Result<(String, RenameReport), String> {
+    let _lock = JournalLock::acquire(path)?;
+    let mut journal = load_journal(path)?;
+    if journal.position >= journal.batches.len() {
+        return Err("Nothing to redo.".to_string());
+    }
+
+    let batch = journal.batches[journal.position].clone();
+    verify_unchanged(&batch, false)?;
+    let report = run_plan(&redo_plan(&batch))?;
+
+    journal.position += 1;
+    save_journal(path, &journal)?;
+
+    Ok((batch.label, report))
+}
+
+// Shows what redoing the most recently undone batch would move, without touching a file
+pub fn preview_redo() -> Result<(String, Vec<PreviewEntry>), String> {
+    preview_redo_in(&journal_path()?)
+}
+
+fn preview_redo_in(path: &Path) -> Result<(String, Vec<PreviewEntry>), String> {
+    let _lock = JournalLock::acquire(path)?;
+    let journal = load_journal(path)?;
+    if journal.position >= journal.batches.len() {
+        return Err("Nothing to redo.".to_string());
+    }
+
+    let batch = &journal.batches[journal.position];
+    verify_unchanged(batch, false)?;
+    Ok((batch.label.clone(), redo_plan(batch).preview()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::explorer::test_support::TestDir;
+
+    fn run(plan: &RenamePlan) -> RenameReport {
+        plan.validate().unwrap();
+        let report = plan.execute();
+        assert!(report.failed.is_none());
+        report
+    }
+
+    #[test]
+    fn undoes_and_redoes_a_rename() {
+        let dir = TestDir::new();
+        let journal = dir.join("journal/operation_journal.json");
+        let mut plan = RenamePlan::new();
+        plan.create_dir(dir.join("Season 01"));
+        plan.push(dir.file("E01.mkv"), dir.join("Season 01/S01E01.mkv"));
+        record_operation_in(&journal, "Organize", &run(&plan)).unwrap();
+
+        let (label, _) = undo_last_operation_in(&journal).unwrap();
+        assert_eq!(label, "Organize");
+        assert_eq!(dir.names(), ["E01.mkv", "journal"]);
+
+        redo_operation_in(&journal).unwrap();
+        assert_eq!(dir.read("Season 01/S01E01.mkv"), "E01.mkv");
+        assert!(undo_last_operation_in(&journal).is_ok());
+        assert!(undo_last_operation_in(&journal).is_err());
+        assert!(!journal.with_extension("lock").exists());
+    }
+
+    #[test]
+    fn previews_undo_and_redo_without_moving_files() {
+        let dir = TestDir::new();
+        let journal = dir.join("journal/operation_journal.json");
+        let mut plan = RenamePlan::new();
+        plan.push(dir.file("a.mkv"), dir.join("b.mkv"));
+        record_operation_in(&journal, "Rename", &run(&plan)).unwrap();
+
+        let (label, entries) = preview_undo_in(&journal).unwrap();
+        assert_eq!(label, "Rename");
+        assert_eq!(entries[0].proposed_name, "a.mkv");
+        assert_eq!(dir.names(), ["b.mkv", "journal"]);
+        assert!(preview_redo_in(&journal).is_err());
+
+        undo_last_operation_in(&journal).unwrap();
+        let (_, entries) = preview_redo_in(&journal).unwrap();
+        assert_eq!(entries[0].proposed_name, "b.mkv");
+        assert_eq!(dir.names(), ["a.mkv", "journal"]);
+    }
+
+    #[test]
+    fn records_batches_without_renames() {
+        let dir = TestDir::new();
+        let journal = dir.join("operation_journal.json");
+        let mut plan = RenamePlan::new();
+        plan.create_dir(dir.join("Empty"));
+        record_operation_in(&journal, "Create folder", &run(&plan)).unwrap();
+
+        assert_eq!(load_journal(&journal).unwrap().batches.len(), 1);
+        undo_last_operation_in(&journal).unwrap();
+        assert!(!dir.join("Empty").exists());
+    }
+
+    #[test]
+    fn notes_deleted_files_on_undo() {
+        let dir = TestDir::new();
+        let journal = dir.join("operation_journal.json");
+        let mut plan = RenamePlan::new();
+        plan.push(dir.file("Show/Show.mkv"), dir.join("Show.mkv"));
+        plan.delete_file(dir.file("Show/sample.mkv"), "Sample");
+        plan.remove_dir(dir.join("Show"));
+        record_operation_in(&journal, "Flatten", &run(&plan)).unwrap();
+
+        let (label, _) = undo_last_operation_in(&journal).unwrap();
+        assert_eq!(label, "Flatten (1 deleted file can not be restored)");
+        assert_eq!(dir.read("Show/Show.mkv"), "Show/Show.mkv");
+    }
+
+    #[test]
+    fn refuses_to_undo_modified_files() {
+        let dir = TestDir::new();
+        let journal = dir.join("operation_journal.json");
+        let mut plan = RenamePlan::new();
+        plan.push(dir.file("a.mkv"), dir.join("b.mkv"));
+        record_operation_in(&journal, "Rename", &run(&plan)).unwrap();
+
+        dir.file_with("b.mkv", "changed since");
+        assert!(undo_last_operation_in(&journal).is_err());
+        assert_eq!(dir.read("b.mkv"), "changed since");
+    }
+}
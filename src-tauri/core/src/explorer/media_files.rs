@@ -0,0 +1,205 @@
+use once_cell::sync::Lazy;
+use regex::Regex;
+use sanitize_filename::sanitize;
+use serde::{Deserialize, Serialize};
+use std::cmp::Reverse;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tokio_util::sync::CancellationToken;
+
+#[derive(Debug, Serialize, Clone, Deserialize)]
+pub struct FileInfo {
+    pub path: String,
+    pub is_dir: bool,
+    pub is_video: bool,
+    pub name: String,
+}
+
+// The video files below a folder and the folders and entries that could not be read
+#[derive(Debug, Serialize, Clone)]
+pub struct MediaFileList {
+    pub files: Vec<FileInfo>,
+    pub skipped: Vec<String>,
+}
+
+// "sample", "show-sample" or "movie.sample", not titles like "Sample Size"
+static SAMPLE_NAME: Lazy<Regex> = Lazy::new(|| Regex::new(r"(?i)(?:^|[-.])sample$").unwrap());
+
+// A sample clip by its name, or any file in a "Sample" folder below `root`
+pub fn is_sample(path: &Path, root: &Path) -> bool {
+    let in_sample_folder = path
+        .parent()
+        .and_then(|parent| parent.strip_prefix(root).ok())
+        .map_or(false, |relative| {
+            relative.components().any(|component| {
+                let folder = component.as_os_str().to_string_lossy().to_lowercase();
+                folder == "sample" || folder == "samples"
+            })
+        });
+    let stem = path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+        .unwrap_or_default();
+
+    in_sample_folder || SAMPLE_NAME.is_match(&stem)
+}
+
+pub fn is_video_file(path: &Path) -> bool {
+    if let Some(extension) = path.extension() {
+        matches!(
+            extension.to_str().unwrap_or("").to_lowercase().as_str(),
+            "mp4" | "mkv" | "avi" | "mov" | "wmv" | "flv" | "webm" | "m4v"
+        )
+    } else {
+        false
+    }
+}
+
+// START MEDIA FILES
+
+// Every video file below `dir`. Folders that can not be read are skipped and listed.
+pub fn list_media_files(dir: &Path, token: &CancellationToken) -> Result<MediaFileList, String> {
+    let mut media_files = vec![];
+    let mut skipped = vec![];
+    walk_files(
+        dir,
+        false,
+        token,
+        &mut |path, _| {
+            let file_name = path
+                .file_name()
+                .map(|name| name.to_string_lossy().to_string())
+                .unwrap_or_default();
+            media_files.push(FileInfo {
+                path: path.to_string_lossy().to_string(),
+                is_dir: false,
+                is_video: true,
+                name: sanitize(&file_name),
+            });
+        },
+        &mut skipped,
+    )?;
+    Ok(MediaFileList {
+        files: media_files,
+        skipped,
+    })
+}
+
+// Calls `visit` with the path and size of every file below `dir`, only videos unless
+// `all_files` is set. Symlinks are not followed, so no file is visited twice.
+// Folders and entries that can not be read are skipped, with a message in `errors`.
+pub fn walk_files(
+    dir: &Path,
+    all_files: bool,
+    token: &CancellationToken,
+    visit: &mut impl FnMut(PathBuf, u64),
+    errors: &mut Vec<String>,
+) -> Result<(), String> {
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) => {
+            errors.push(format!("Failed to read directory {}: {}", dir.display(), e));
+            return Ok(());
+        }
+    };
+
+    for entry in entries {
+        if token.is_cancelled() {
+            return Err("Operation cancelled".into());
+        }
+        let entry = match entry {
+            Ok(entry) => entry,
+            Err(e) => {
+                errors.push(format!(
+                    "Failed to read entry in directory {}: {}",
+                    dir.display(),
+                    e
+                ));
+                continue;
+            }
+        };
+
+        let path = entry.path();
+        let metadata = match entry.metadata() {
+            Ok(metadata) => metadata,
+            Err(e) => {
+                errors.push(format!("Failed to read {}: {}", path.display(), e));
+                continue;
+            }
+        };
+        if metadata.is_dir() {
+            walk_files(&path, all_files, token, visit, errors)?;
+        } else if metadata.is_file() && (all_files || is_video_file(&path)) {
+            visit(path, metadata.len());
+        }
+    }
+
+    Ok(())
+}
+
+// END MEDIA FILES
+
+// START FILE SIZES
+
+pub fn format_size(size: u64) -> String {
+    if size >= 1_073_741_824 {
+        format!("{:.2} GB", size as f64 / 1_073_741_824.0)
+    } else if size >= 1_048_576 {
+        format!("{:.2} MB", size as f64 / 1_048_576.0)
+    } else {
+        format!("{:.2} KB", size as f64 / 1_024.0)
+    }
+}
+
+// The entries of `dir` with their size, largest first. A folder counts everything in it.
+pub fn list_file_sizes(
+    dir: &Path,
+    token: &CancellationToken,
+) -> Result<Vec<(FileInfo, u64)>, String> {
+    let mut file_info_list = vec![];
+    let entries = fs::read_dir(dir).map_err(|e| e.to_string())?;
+    for entry in entries {
+        let entry = entry.map_err(|e| e.to_string())?;
+        let path = entry.path();
+        let metadata = fs::metadata(&path).map_err(|e| e.to_string())?;
+        let size = if metadata.is_dir() {
+            get_directory_size(&path, token.clone())?
+        } else {
+            metadata.len()
+        };
+        let name = path.file_name().unwrap().to_string_lossy().to_string();
+        let file_info = FileInfo {
+            path: path.to_string_lossy().to_string(),
+            is_dir: path.is_dir(),
+            is_video: is_video_file(&path),
+            name,
+        };
+        file_info_list.push((file_info, size));
+    }
+
+    // Sort the file_info_list by size
+    file_info_list.sort_by_key(|info| Reverse(info.1));
+
+    Ok(file_info_list)
+}
+
+fn get_directory_size(path: &PathBuf, token: CancellationToken) -> Result<u64, String> {
+    if token.is_cancelled() {
+        return Err("Operation cancelled".into());
+    }
+
+    let mut total_size = 0;
+    if let Ok(entries) = fs::read_dir(path) {
+        for entry in entries.flatten() {
+            let metadata = fs::metadata(entry.path()).unwrap();
+            if metadata.is_dir() {
+                total_size += get_directory_size(&entry.path(), token.clone())?;
+            } else {
+                total_size += metadata.len();
+            }
+        }
+    }
+    Ok(total_size)
+}
+
+// END FILE SIZES
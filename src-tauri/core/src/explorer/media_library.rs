@@ -0,0 +1,80 @@
+use regex::Regex;
+
+// Folder and file names the way Plex and Jellyfin expect them:
+// `Show Name (2019)/Season 01/Show Name - S01E01 - Title.mkv`
+// `Movie Title (1982)/Movie Title (1982) {edition-Final Cut}.mkv`
+
+// START SHOWS
+
+// Splits "Show Name (2019)" into the name and its year
+pub fn split_year(show: &str) -> (String, Option<String>) {
+    let year_pattern = Regex::new(r"^(.*?)[\s.]*\(((?:19|20)\d{2})\)$").unwrap();
+    match year_pattern.captures(show.trim()) {
+        Some(caps) => (caps[1].trim().to_string(), Some(caps[2].to_string())),
+        None => (show.trim().to_string(), None),
+    }
+}
+
+// "Show Name (2019)", or "Show Name" without a year
+pub fn show_folder_name(show: &str, year: Option<&str>) -> String {
+    match year {
+        Some(year) if !year.trim().is_empty() => format!("{} ({})", show, year.trim()),
+        _ => show.to_string(),
+    }
+}
+
+// "Season 01", specials are "Season 00"
+pub fn season_folder_name(season: i32) -> String {
+    format!("Season {:02}", season)
+}
+
+// "Show Name - S01E01 - Title.mkv", or "Show Name - S01E01-E02.mkv" for two episodes without a title
+pub fn episode_file_name(
+    show: &str,
+    season: i32,
+    episodes: &[i32],
+    title: Option<&str>,
+    extension: &str,
+) -> String {
+    let mut name = format!("{} - S{:02}", show, season);
+    match episodes {
+        [] => {}
+        [episode] => name.push_str(&format!("E{:02}", episode)),
+        [first, .., last] => name.push_str(&format!("E{:02}-E{:02}", first, last)),
+    }
+    if let Some(title) = title.map(str::trim).filter(|title| !title.is_empty()) {
+        name.push_str(&format!(" - {}", title));
+    }
+    if !extension.is_empty() {
+        name.push_str(&format!(".{}", extension));
+    }
+    name
+}
+
+// END SHOWS
+
+// START MOVIES
+
+// "Movie Title (1982)", the same as a show folder
+pub fn movie_folder_name(title: &str, year: Option<&str>) -> String {
+    show_folder_name(title, year)
+}
+
+// "Movie Title (1982) {edition-Final Cut}.mkv"
+pub fn movie_file_name(
+    title: &str,
+    year: Option<&str>,
+    edition: Option<&str>,
+    extension: &str,
+) -> String {
+    let mut name = movie_folder_name(title, year);
+    if let Some(edition) = edition.map(str::trim).filter(|edition| !edition.is_empty()) {
+        name.push_str(&format!(" {{edition-{}}}", edition));
+    }
+    if !extension.is_empty() {
+        name.push_str(&format!(".{}", extension));
+    }
+    name
+}
+
+// END MOVIES
@@ -0,0 +1,20 @@
+pub mod absolute_numbering;
+pub mod companions;
+pub mod duplicates;
+pub mod episode_parser;
+pub mod filename_policy;
+pub mod filename_template;
+pub mod flatten;
+pub mod folders;
+pub mod journal;
+pub mod media_files;
+pub mod media_library;
+pub mod name_transform;
+pub mod operations;
+pub mod release_name;
+pub mod rename_plan;
+pub mod search_replace;
+pub mod selection;
+pub mod sequential_numbering;
+#[cfg(test)]
+pub(crate) mod test_support;
@@ -0,0 +1,233 @@
+use crate::explorer::episode_parser::{EpisodeParser, NamingScheme};
+use deunicode::deunicode_with_tofu;
+use once_cell::sync::Lazy;
+use serde::Deserialize;
+use unicode_normalization::UnicodeNormalization;
+
+// Words that stay lower case in Title Case unless they start or end the name
+const SMALL_WORDS: [&str; 27] = [
+    "a", "an", "and", "as", "at", "but", "by", "en", "for", "from", "if", "in", "into", "nor",
+    "of", "on", "or", "over", "per", "so", "the", "to", "up", "via", "vs", "with", "yet",
+];
+
+// Roman numerals that stay upper case, e.g. "Rocky II"
+const ROMAN_NUMERALS: [&str; 11] = [
+    "ii", "iii", "iv", "vi", "vii", "viii", "ix", "xi", "xii", "xiii", "xiv",
+];
+
+static EPISODE_PARSER: Lazy<EpisodeParser> = Lazy::new(EpisodeParser::new);
+
+// STRUCTS
+
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum NameTransform {
+    TitleCase,
+    LowerCase,
+    UpperCase,
+    Nfc,                // Composes "e" + combining accent into "é"
+    Nfkc,               // Also turns full-width "ＡＢＣ" into "ABC"
+    Ascii,              // "Pokémon" -> "Pokemon", "Grüße" -> "Gruesse"
+    SeparatorsToSpaces, // "Show.Name_S01E01" -> "Show Name S01E01"
+}
+
+// Applies the transforms in order to a file name without its extension
+pub fn apply_transforms(stem: &str, transforms: &[NameTransform]) -> String {
+    transforms
+        .iter()
+        .fold(stem.to_string(), |name, transform| match transform {
+            NameTransform::TitleCase => title_case(&name),
+            NameTransform::LowerCase => name.to_lowercase(),
+            NameTransform::UpperCase => name.to_uppercase(),
+            NameTransform::Nfc => name.nfc().collect(),
+            NameTransform::Nfkc => name.nfkc().collect(),
+            NameTransform::Ascii => to_ascii(&name),
+            NameTransform::SeparatorsToSpaces => separators_to_spaces(&name),
+        })
+}
+
+// START TRANSFORMS
+
+// "the lord OF the rings - the return of the king s01e01" ->
+// "The Lord of the Rings - The Return of the King S01E01"
+fn title_case(name: &str) -> String {
+    // "S01E02" and "1x02" stay upper case, found the same way as everywhere else
+    let episode_span = EPISODE_PARSER
+        .parse(name)
+        .filter(|id| {
+            matches!(
+                id.scheme,
+                NamingScheme::SeasonEpisode | NamingScheme::Crossed
+            )
+        })
+        .map(|id| id.start..id.end);
+    let words: Vec<&str> = name.split(' ').collect();
+    let last = words.iter().rposition(|word| !word.is_empty()).unwrap_or(0);
+    let mut segment_start = true; // After " - " or ": " a new part of the title begins
+    let mut word_start = 0;
+
+    let cased: Vec<String> = words
+        .iter()
+        .enumerate()
+        .map(|(index, word)| {
+            let is_separator = |c: char| !c.is_alphanumeric();
+            let core = word.trim_matches(is_separator);
+            let lower = core.to_lowercase();
+            let core_start = word_start + word.len() - word.trim_start_matches(is_separator).len();
+            let is_episode_id = !core.is_empty()
+                && episode_span.as_ref().map_or(false, |span| {
+                    span.start <= core_start && core_start + core.len() <= span.end
+                });
+            word_start += word.len() + 1;
+
+            let cased = if is_episode_id || ROMAN_NUMERALS.contains(&lower.as_str()) {
+                word.to_uppercase()
+            } else if core.chars().any(|c| c.is_ascii_digit()) {
+                word.to_string() // "1080p", "x264"
+            } else if !segment_start && index != last && SMALL_WORDS.contains(&lower.as_str()) {
+                word.to_lowercase()
+            } else {
+                capitalize_parts(word)
+            };
+
+            if !word.is_empty() {
+                segment_start = *word == "-" || word.ends_with(':');
+            }
+            cased
+        })
+        .collect();
+
+    cased.join(" ")
+}
+
+// Capitalizes every part of "spider-man" or "(director's", the rest is lower case
+fn capitalize_parts(word: &str) -> String {
+    let mut result = String::new();
+    let mut part_start = true;
+
+    for c in word.chars() {
+        if part_start && c.is_alphabetic() {
+            result.extend(c.to_uppercase());
+            part_start = false;
+        } else {
+            result.extend(c.to_lowercase());
+            if c == '-' || c == '(' || c == '[' || c == '"' {
+                part_start = true;
+            }
+        }
+    }
+
+    result
+}
+
+fn to_ascii(name: &str) -> String {
+    // German spells umlauts out rather than dropping the dots
+    let spelled_out: String = name
+        .nfc()
+        .map(|c| match c {
+            'ä' => "ae".to_string(),
+            'ö' => "oe".to_string(),
+            'ü' => "ue".to_string(),
+            'Ä' => "Ae".to_string(),
+            'Ö' => "Oe".to_string(),
+            'Ü' => "Ue".to_string(),
+            'ß' => "ss".to_string(),
+            c => c.to_string(),
+        })
+        .collect();
+
+    deunicode_with_tofu(&spelled_out, "_")
+}
+
+// Dots between digits stay, e.g. "5.1" or "2024.03.15"
+fn separators_to_spaces(name: &str) -> String {
+    let chars: Vec<char> = name.chars().collect();
+    let replaced: String = chars
+        .iter()
+        .enumerate()
+        .map(|(index, c)| match c {
+            '_' => ' ',
+            '.' => {
+                let digit_before = index > 0 && chars[index - 1].is_ascii_digit();
+                let digit_after = chars.get(index + 1).map_or(false, |c| c.is_ascii_digit());
+                if digit_before && digit_after {
+                    '.'
+                } else {
+                    ' '
+                }
+            }
+            c => *c,
+        })
+        .collect();
+
+    replaced.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+// END TRANSFORMS
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use NameTransform::*;
+
+    #[test]
+    fn title_cases_names() {
+        assert_eq!(
+            apply_transforms(
+                "the lord OF the rings - the return of the king s01e01",
+                &[TitleCase]
+            ),
+            "The Lord of the Rings - The Return of the King S01E01"
+        );
+        assert_eq!(
+            apply_transforms("rocky ii: the spider-man of 1080p x264", &[TitleCase]),
+            "Rocky II: The Spider-Man of 1080p x264"
+        );
+    }
+
+    #[test]
+    fn keeps_episode_tags_upper_case() {
+        assert_eq!(
+            apply_transforms("show s01e01-e03 pilot", &[TitleCase]),
+            "Show S01E01-E03 Pilot"
+        );
+        assert_eq!(
+            apply_transforms("show s01 e02", &[TitleCase]),
+            "Show S01 E02"
+        );
+        assert_eq!(
+            apply_transforms("show 1x02 - the title", &[TitleCase]),
+            "Show 1X02 - The Title"
+        );
+    }
+
+    #[test]
+    fn normalizes_unicode() {
+        assert_eq!(apply_transforms("Pok\u{65}\u{301}mon", &[Nfc]), "Pokémon");
+        assert_eq!(apply_transforms("ＡＢＣ", &[Nfkc]), "ABC");
+        assert_eq!(
+            apply_transforms("Pokémon Grüße", &[Ascii]),
+            "Pokemon Gruesse"
+        );
+    }
+
+    #[test]
+    fn replaces_separators() {
+        assert_eq!(
+            apply_transforms("Show.Name_S01E01.DD5.1..2024.03.15", &[SeparatorsToSpaces]),
+            "Show Name S01E01 DD5.1 2024.03.15"
+        );
+    }
+
+    #[test]
+    fn applies_transforms_in_order() {
+        assert_eq!(
+            apply_transforms("show.name.s01e01", &[SeparatorsToSpaces, TitleCase]),
+            "Show Name S01E01"
+        );
+        assert_eq!(
+            apply_transforms("Show Name", &[UpperCase, LowerCase]),
+            "show name"
+        );
+    }
+}
@@ -0,0 +1,1833 @@
+use crate::api::metadata_provider::MetadataProvider;
+use crate::api::models::{ProviderEpisode, SeasonedEpisodes, ShowDetailsWithEpisodes, TMDBMovie};
+use crate::api::movies_tmdb::search_tmdb_movie;
+use crate::explorer::absolute_numbering::{
+    convert_episode_numbering, NumberingConversion, SeasonBoundaries,
+};
+use crate::explorer::companions::Companions;
+use crate::explorer::episode_parser::{EpisodeIdentifier, EpisodeParser};
+use crate::explorer::filename_policy::{load_sanitize_profile, replace_path_separators};
+use crate::explorer::filename_template::{FilenameTemplate, TemplateValue, TemplateValues};
+use crate::explorer::folders::{collect_folders, season_from_folder_name};
+use crate::explorer::journal::record_operation;
+use crate::explorer::media_files::{is_sample, is_video_file};
+use crate::explorer::media_library::{
+    episode_file_name, movie_file_name, movie_folder_name, season_folder_name, show_folder_name,
+    split_year,
+};
+use crate::explorer::name_transform::{apply_transforms, NameTransform};
+use crate::explorer::release_name::{
+    dropped_parts, rebuild_release_name, CleanupRules, MovieName, ReleaseNameParser,
+};
+use crate::explorer::rename_plan::{RenameError, RenamePlan, RenameReport};
+use crate::explorer::search_replace::FileNameSearch;
+use crate::explorer::selection::FileSelection;
+use crate::explorer::sequential_numbering::{sort_files, SequentialNumbering};
+use reqwest::Client;
+use serde::Deserialize;
+use std::collections::hash_map::Entry;
+use std::collections::BTreeMap;
+use std::collections::HashMap;
+use std::ffi::OsStr;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+// Joins the titles of a multi-episode file, e.g. "Title A + Title B"
+pub const DEFAULT_TITLE_SEPARATOR: &str = " + ";
+
+// Moves the episodes `first_episode..=last_episode` of `season` to `target_season`,
+// starting at `target_first_episode`. E.g. S02E13-S02E24 -> S03E01-S03E12.
+#[derive(Deserialize, Debug, Clone)]
+pub struct EpisodeRangeRemap {
+    pub season: i32,
+    pub first_episode: i32,
+    pub last_episode: i32,
+    pub target_season: i32,
+    pub target_first_episode: i32,
+}
+
+// START APPLY PLANS
+
+// Renames the files of a plan right away, the way an applied preview does
+pub fn apply_plan(mut plan: RenamePlan, label: &str) -> Result<RenameReport, String> {
+    plan.sanitize_targets(load_sanitize_profile()?);
+    plan.validate()
+        .map_err(|e| format!("Failed to rename files: {}", e))?;
+    let mut report = plan.execute();
+    // The files are already renamed, so a journal error must not hide the report
+    if let Err(e) = record_operation(label, &report) {
+        report
+            .warnings
+            .push(format!("The changes can not be undone: {}", e));
+    }
+
+    Ok(report)
+}
+
+// Builds the plan of every folder and merges them, so all folders share one
+// preview and are renamed in one transaction
+pub fn plan_for_folders<E: From<io::Error>>(
+    folders: &[PathBuf],
+    mut build: impl FnMut(&Path) -> Result<RenamePlan, E>,
+) -> Result<RenamePlan, E> {
+    let mut plan = RenamePlan::new();
+    for folder in folders {
+        plan.append(build(folder)?);
+    }
+    Ok(plan)
+}
+
+// The current folder and, in recursive mode, its subfolders
+pub fn folders_to_process(
+    current_path: &Path,
+    subfolder_depth: Option<usize>,
+) -> Result<Vec<PathBuf>, String> {
+    collect_folders(current_path, subfolder_depth.unwrap_or(0))
+        .map_err(|e| format!("Failed to read subfolders: {}", e))
+}
+
+// END APPLY PLANS
+
+// START GET EPISODE TITLES
+
+// Returns the titles found in the file names, grouped by season. Each season's
+// titles are indexed by episode number, so episodes without a file stay empty.
+// The title of a multi-episode file is split by the separator when possible.
+pub fn current_episode_names(
+    current_path: &Path,
+    title_separator: &str,
+) -> Result<Vec<SeasonedEpisodes>, String> {
+    let entries =
+        fs::read_dir(current_path).map_err(|e| format!("Failed to read directory: {}", e))?;
+    let parser = EpisodeParser::new();
+    let mut titles_by_season: BTreeMap<i32, BTreeMap<i32, String>> = BTreeMap::new();
+
+    for entry in entries {
+        let entry = entry.map_err(|e| format!("Failed to read directory entry: {}", e))?;
+        let path = entry.path();
+
+        if path.is_file() && is_video_file(&path) {
+            if let Some(file_name) = path.file_name().and_then(OsStr::to_str) {
+                let identifier = parser.parse(file_name);
+                // Only names with a season and an episode number can be grouped
+                let numbers = identifier.as_ref().and_then(|id| {
+                    let episodes = id.episode_numbers();
+                    if episodes.is_empty() {
+                        return None;
+                    }
+                    Some((id, id.season_number()?, episodes))
+                });
+
+                if let Some((identifier, season, episodes)) = numbers {
+                    // Extract the title after the episode number if it exists
+                    let episode_title_with_ext = &file_name[identifier.end..];
+
+                    // Remove file extension
+                    let episode_title = match episode_title_with_ext.rfind('.') {
+                        Some(index) => &episode_title_with_ext[..index],
+                        None => episode_title_with_ext,
+                    }
+                    .trim() // Trim leading/trailing whitespace
+                    .trim_start_matches("- ") // Remove leading " - " if present
+                    .trim();
+
+                    // Give every episode its part of the title, or all of it to the first one
+                    let parts: Vec<&str> = episode_title.split(title_separator).collect();
+                    let season_titles = titles_by_season.entry(season).or_default();
+                    if parts.len() == episodes.len() {
+                        for (episode, part) in episodes.iter().zip(parts) {
+                            season_titles.insert(*episode, part.trim().to_string());
+                        }
+                    } else {
+                        season_titles.insert(episodes[0], episode_title.to_string());
+                        for episode in &episodes[1..] {
+                            season_titles.entry(*episode).or_default();
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    let seasons = titles_by_season
+        .into_iter()
+        .map(|(season, episodes)| {
+            let end_episode = episodes.keys().max().copied().unwrap_or(0);
+            let titles = (1..=end_episode)
+                .map(|episode| episodes.get(&episode).cloned().unwrap_or_default())
+                .collect();
+            SeasonedEpisodes {
+                season,
+                start_episode: 1,
+                end_episode,
+                titles,
+            }
+        })
+        .collect();
+
+    Ok(seasons)
+}
+
+// END GET EPISODE TITLES
+
+// START RENAME EPISODES WITH TITLES
+
+pub fn add_titles_to_episodes_plan(
+    directory: &Path,
+    episodes_by_season: &[SeasonedEpisodes],
+    title_separator: &str,
+    selection: &FileSelection,
+) -> Result<RenamePlan, io::Error> {
+    let entries = fs::read_dir(directory)?;
+    let parser = EpisodeParser::new();
+    let episode_titles = episode_titles_by_number(episodes_by_season);
+    let folder_season = season_from_folder_name(directory); // e.g. "Season 02"
+    let mut plan = RenamePlan::new();
+    let mut companions = Companions::new();
+
+    for entry in entries {
+        let entry = entry?;
+        let path = entry.path();
+
+        if path.is_file() && is_video_file(&path) && selection.contains(&path) {
+            let file_name = match path.file_name().and_then(OsStr::to_str) {
+                Some(file_name) => file_name,
+                None => {
+                    plan.skip(path, "File name is not valid UTF-8");
+                    continue;
+                }
+            };
+
+            // Multi-episode files use up one title per episode
+            let episode_count = parser
+                .parse(file_name)
+                .map(|id| id.episode_numbers().len())
+                .unwrap_or(0);
+            if episode_count > 1 {
+                plan.note(&path, format!("{} episodes", episode_count));
+            }
+
+            match create_new_file_name(
+                file_name,
+                &parser,
+                &episode_titles,
+                title_separator,
+                folder_season,
+            ) {
+                Ok(new_file_name) => {
+                    // Split the filename and extension
+                    let extension = path.extension().and_then(OsStr::to_str).unwrap_or("");
+                    let sanitized_base_name = replace_path_separators(&new_file_name);
+
+                    // Construct the new filename by attaching the extension back
+                    let final_file_name = format!("{}.{}", sanitized_base_name, extension);
+
+                    let new_path = path.with_file_name(final_file_name);
+                    companions.push_with_companions(&mut plan, path, new_path)?;
+                }
+                Err(reason) => plan.skip(path, reason),
+            }
+        }
+    }
+
+    Ok(plan)
+}
+
+// Keys every title by (season, episode). The title at index 0 of a season is episode 1.
+pub fn episode_titles_by_number(
+    episodes_by_season: &[SeasonedEpisodes],
+) -> HashMap<(i32, i32), String> {
+    let mut episode_titles = HashMap::new();
+    for season in episodes_by_season {
+        for (index, title) in season.titles.iter().enumerate() {
+            episode_titles.insert((season.season, index as i32 + 1), title.clone());
+        }
+    }
+    episode_titles
+}
+
+pub fn create_new_file_name(
+    file_name: &str,
+    parser: &EpisodeParser,
+    episode_titles: &HashMap<(i32, i32), String>,
+    title_separator: &str,
+    folder_season: Option<i32>, // Used when the file name has no season number
+) -> Result<String, String> {
+    // Check if the file name contains an episode identifier
+    let identifier = parser
+        .parse(file_name)
+        .ok_or_else(|| "No episode number found".to_string())?;
+    let episode_counter = identifier.text(file_name); // e.g., "S01E02" or "S01E01-E03"
+    let episodes = identifier.episode_numbers();
+    let season = match identifier.season_number().or(folder_season) {
+        Some(season) if !episodes.is_empty() => season,
+        _ => {
+            return Err(format!(
+                "No season and episode number in {}",
+                episode_counter
+            ))
+        }
+    };
+
+    // Files without a title for one of their episodes are reported instead of shifted
+    let mut titles = Vec::new();
+    for episode in episodes {
+        let title = episode_titles
+            .get(&(season, episode))
+            .ok_or_else(|| format!("No title for S{:02}E{:02}", season, episode))?;
+        titles.push(title.as_str());
+    }
+
+    Ok(append_titles(
+        file_name,
+        &identifier,
+        &titles,
+        title_separator,
+    ))
+}
+
+// Replaces everything after the episode counter with the titles
+pub fn append_titles(
+    file_name: &str,
+    identifier: &EpisodeIdentifier,
+    titles: &[&str],
+    title_separator: &str,
+) -> String {
+    let base_name = &file_name[..identifier.end];
+    let titles: Vec<&str> = titles
+        .iter()
+        .map(|title| title.trim())
+        .filter(|title| !title.is_empty())
+        .collect();
+
+    // If all titles are empty or just spaces, return the base name (i.e., remove the old title)
+    if titles.is_empty() {
+        return base_name.to_string();
+    }
+
+    format!("{} - {}", base_name, titles.join(title_separator))
+}
+
+// END RENAME EPISODES WITH TITLES
+
+// START FETCH AND APPLY TITLES
+
+pub fn provider_titles_plan(
+    directory: &Path,
+    provider: MetadataProvider,
+    episodes: &[ProviderEpisode],
+    title_separator: &str,
+    selection: &FileSelection,
+) -> io::Result<RenamePlan> {
+    let parser = EpisodeParser::new();
+    let folder_season = season_from_folder_name(directory); // e.g. "Season 02"
+    let mut plan = RenamePlan::new();
+    let mut companions = Companions::new();
+
+    for entry in fs::read_dir(directory)? {
+        let path = entry?.path();
+        if !path.is_file() || !is_video_file(&path) || !selection.contains(&path) {
+            continue;
+        }
+
+        let file_name = match path.file_name().and_then(OsStr::to_str) {
+            Some(file_name) => file_name,
+            None => {
+                plan.skip(path, "File name is not valid UTF-8");
+                continue;
+            }
+        };
+        let identifier = match parser.parse(file_name) {
+            Some(identifier) => identifier,
+            None => {
+                plan.skip(path, "No episode number found");
+                continue;
+            }
+        };
+        let matched = match find_provider_episodes(episodes, &identifier, folder_season) {
+            Ok(matched) => matched,
+            Err(reason) => {
+                plan.skip(path, reason);
+                continue;
+            }
+        };
+
+        // Keep the provider's episode ID and air date visible in the preview
+        for episode in &matched {
+            let mut note = format!("{} episode {}", provider.name(), episode.absolute);
+            if let Some(id) = episode.id {
+                note.push_str(&format!(", ID {}", id));
+            }
+            if let Some(air_date) = &episode.air_date {
+                note.push_str(&format!(", aired {}", air_date));
+            }
+            plan.note(&path, note);
+        }
+
+        let titles: Vec<&str> = matched
+            .iter()
+            .map(|episode| episode.title.as_deref().unwrap_or(""))
+            .collect();
+        let new_base_name = append_titles(file_name, &identifier, &titles, title_separator);
+        let extension = path.extension().and_then(OsStr::to_str).unwrap_or("");
+        let new_path = path.with_file_name(format!(
+            "{}.{}",
+            replace_path_separators(&new_base_name),
+            extension
+        ));
+        companions.push_with_companions(&mut plan, path, new_path)?;
+    }
+
+    Ok(plan)
+}
+
+// Matches a file to provider episodes by air date, by season and episode,
+// or by absolute number when neither the file nor its folder has a season
+pub fn find_provider_episodes<'a>(
+    episodes: &'a [ProviderEpisode],
+    identifier: &EpisodeIdentifier,
+    folder_season: Option<i32>,
+) -> Result<Vec<&'a ProviderEpisode>, String> {
+    if let Some(air_date) = &identifier.air_date {
+        return episodes
+            .iter()
+            .find(|episode| episode.air_date.as_ref() == Some(air_date))
+            .map(|episode| vec![episode])
+            .ok_or_else(|| format!("No episode aired on {}", air_date));
+    }
+
+    let numbers = identifier.episode_numbers();
+    if numbers.is_empty() {
+        return Err("No episode number found".to_string());
+    }
+
+    let season = identifier.season_number().or(folder_season);
+    numbers
+        .into_iter()
+        .map(|number| {
+            let found = match season {
+                // Providers without seasons only have the episode number to go by
+                Some(season) => episodes.iter().find(|episode| {
+                    episode.number == number && episode.season.map_or(true, |s| s == season)
+                }),
+                None => episodes.iter().find(|episode| episode.absolute == number),
+            };
+            found.ok_or_else(|| match season {
+                Some(season) => format!("No episode S{:02}E{:02} at the provider", season, number),
+                None => format!("No episode {} at the provider", number),
+            })
+        })
+        .collect()
+}
+
+// END FETCH AND APPLY TITLES
+
+// START RENAME WITH TEMPLATE
+
+pub fn rename_with_template_plan(
+    directory: &Path,
+    template: &FilenameTemplate,
+    show_details: Option<&ShowDetailsWithEpisodes>,
+    title_separator: &str,
+    selection: &FileSelection,
+) -> Result<RenamePlan, io::Error> {
+    let entries = fs::read_dir(directory)?;
+    let parser = ReleaseNameParser::new();
+    let episode_titles = show_details
+        .map(|details| episode_titles_by_number(&details.episodes_by_season))
+        .unwrap_or_default();
+    let folder_season = season_from_folder_name(directory); // e.g. "Season 02"
+    let mut plan = RenamePlan::new();
+    let mut companions = Companions::new();
+
+    for entry in entries {
+        let entry = entry?;
+        let path = entry.path();
+
+        if path.is_file() && is_video_file(&path) && selection.contains(&path) {
+            let file_name = match path.file_name().and_then(OsStr::to_str) {
+                Some(file_name) => file_name,
+                None => {
+                    plan.skip(path, "File name is not valid UTF-8");
+                    continue;
+                }
+            };
+
+            let values = episode_template_values(
+                file_name,
+                &parser,
+                show_details,
+                &episode_titles,
+                title_separator,
+                folder_season,
+            );
+            match template.render_file_name(&values) {
+                Ok(new_file_name) => {
+                    let new_path = path.with_file_name(replace_path_separators(&new_file_name));
+                    companions.push_with_companions(&mut plan, path, new_path)?;
+                }
+                Err(reason) => plan.skip(path, reason),
+            }
+        }
+    }
+
+    Ok(plan)
+}
+
+// Collects the template variables of a file name. Show name, year and titles
+// come from the fetched show details when they are available.
+pub fn episode_template_values(
+    file_name: &str,
+    parser: &ReleaseNameParser,
+    show_details: Option<&ShowDetailsWithEpisodes>,
+    episode_titles: &HashMap<(i32, i32), String>,
+    title_separator: &str,
+    folder_season: Option<i32>, // Used when the file name has no season number
+) -> TemplateValues {
+    let mut values = TemplateValues::new();
+    let (stem, extension) = match file_name.rfind('.') {
+        Some(index) if index > 0 => (&file_name[..index], &file_name[index + 1..]),
+        _ => (file_name, ""),
+    };
+    let text = |value: &str| TemplateValue::Text(value.to_string());
+
+    values.insert("original".to_string(), text(stem));
+    values.insert("ext".to_string(), text(extension));
+
+    // Release group, e.g. "[SubGroup] Show - 01" or "x264-GROUP", and resolution
+    let parts = parser.parse(stem);
+    if let Some(group) = &parts.group {
+        values.insert("group".to_string(), text(group));
+    }
+    if let Some(resolution) = &parts.resolution {
+        values.insert("resolution".to_string(), text(resolution));
+    }
+
+    let identifier = parser.episode_parser().parse(file_name);
+    if let Some(identifier) = &identifier {
+        // The show name is everything before the episode identifier
+        values.insert("show".to_string(), text(&parts.show));
+
+        let season = identifier.season_number().or(folder_season);
+        if let Some(season) = season {
+            values.insert("season".to_string(), TemplateValue::Number(season));
+        }
+        let episodes = identifier.episode_numbers();
+        if let Some(first) = episodes.first() {
+            values.insert("episode".to_string(), TemplateValue::Number(*first));
+        }
+        if episodes.len() > 1 {
+            values.insert(
+                "episode_end".to_string(),
+                TemplateValue::Number(episodes[episodes.len() - 1]),
+            );
+        }
+        if let Some(absolute) = identifier.absolute {
+            values.insert("absolute".to_string(), TemplateValue::Number(absolute));
+        }
+        if let Some(air_date) = &identifier.air_date {
+            values.insert("air_date".to_string(), text(air_date));
+        }
+
+        // The current title is everything between the identifier and the extension
+        let current_title = stem
+            .get(identifier.end..)
+            .unwrap_or("")
+            .trim()
+            .trim_start_matches("- ")
+            .trim();
+        values.insert("title".to_string(), text(current_title));
+
+        // Fetched titles replace the current title, one per episode
+        if let Some(season) = season {
+            let titles: Option<Vec<&str>> = episodes
+                .iter()
+                .map(|episode| {
+                    episode_titles
+                        .get(&(season, *episode))
+                        .map(|title| title.trim())
+                })
+                .collect();
+            if let Some(titles) = titles.filter(|titles| !titles.is_empty()) {
+                let titles: Vec<&str> = titles.into_iter().filter(|t| !t.is_empty()).collect();
+                values.insert("title".to_string(), text(&titles.join(title_separator)));
+            }
+        }
+    }
+
+    if let Some(details) = show_details {
+        values.insert("show".to_string(), text(&details.name));
+        if let Some(year) = &details.premiered_year {
+            values.insert("year".to_string(), text(year));
+        }
+    }
+
+    values
+}
+
+// END RENAME WITH TEMPLATE
+
+// START SEARCH AND REPLACE FILE TITLES
+
+pub fn search_and_replace_plan(
+    directory: &Path,
+    search: &FileNameSearch,
+    selection: &FileSelection,
+) -> Result<RenamePlan, RenameError> {
+    let mut files = Vec::new();
+    for entry in fs::read_dir(directory)? {
+        let path = entry?.path();
+        if path.is_file() && search.searches(is_video_file(&path)) && selection.contains(&path) {
+            files.push(path);
+        }
+    }
+    // Videos first, so a subtitle that follows its video is not renamed a second time
+    files.sort_by_key(|path| !is_video_file(path));
+
+    let mut plan = RenamePlan::new();
+    let mut companions = Companions::new();
+    for path in files {
+        if plan.contains_source(&path) {
+            continue;
+        }
+        let file_name = path
+            .file_name()
+            .and_then(OsStr::to_str)
+            .ok_or(RenameError::InvalidFilename)?;
+        let (new_file_name, spans) = search.replace(file_name);
+        plan.highlight(&path, spans);
+        // A "/" in the replacement must not move the file into another folder
+        let new_path = path.with_file_name(replace_path_separators(&new_file_name));
+        if is_video_file(&path) {
+            companions.push_with_companions(&mut plan, path, new_path)?;
+        } else {
+            plan.push(path, new_path);
+        }
+    }
+
+    Ok(plan)
+}
+
+// END SEARCH AND REPLACE FILE TITLES
+
+// START TRANSFORM FILE NAMES
+
+pub fn transform_file_names_plan(
+    directory: &Path,
+    transforms: &[NameTransform],
+    selection: &FileSelection,
+) -> io::Result<RenamePlan> {
+    let mut plan = RenamePlan::new();
+    let mut companions = Companions::new();
+
+    for entry in fs::read_dir(directory)? {
+        let path = entry?.path();
+        if !path.is_file() || !is_video_file(&path) || !selection.contains(&path) {
+            continue;
+        }
+
+        let stem = match path.file_stem().and_then(OsStr::to_str) {
+            Some(stem) => stem,
+            None => {
+                plan.skip(path, "File name is not valid UTF-8");
+                continue;
+            }
+        };
+
+        let new_stem = replace_path_separators(&apply_transforms(stem, transforms));
+        if new_stem.trim().is_empty() {
+            plan.skip(path, "The file name would be empty");
+            continue;
+        }
+
+        let new_file_name = match path.extension().and_then(OsStr::to_str) {
+            Some(extension) => format!("{}.{}", new_stem, extension),
+            None => new_stem,
+        };
+        let new_path = path.with_file_name(new_file_name);
+        companions.push_with_companions(&mut plan, path, new_path)?;
+    }
+
+    Ok(plan)
+}
+
+// END TRANSFORM FILE NAMES
+
+// START CLEAN RELEASE NAMES
+
+pub fn clean_release_names_plan(
+    directory: &Path,
+    parser: &ReleaseNameParser,
+    rules: &CleanupRules,
+    selection: &FileSelection,
+) -> io::Result<RenamePlan> {
+    let mut plan = RenamePlan::new();
+    let mut companions = Companions::new();
+
+    for entry in fs::read_dir(directory)? {
+        let path = entry?.path();
+        if !path.is_file() || !is_video_file(&path) || !selection.contains(&path) {
+            continue;
+        }
+
+        let stem = match path.file_stem().and_then(OsStr::to_str) {
+            Some(stem) => stem,
+            None => {
+                plan.skip(path, "File name is not valid UTF-8");
+                continue;
+            }
+        };
+
+        let parts = parser.parse(stem);
+        let clean_name = match rebuild_release_name(&parts, rules) {
+            Some(clean_name) => clean_name,
+            None => {
+                plan.skip(path, "No show title or episode found");
+                continue;
+            }
+        };
+
+        let dropped = dropped_parts(&parts, rules);
+        if !dropped.is_empty() {
+            plan.note(&path, format!("Removed: {}", dropped.join(", ")));
+        }
+
+        let new_file_name = match path.extension().and_then(OsStr::to_str) {
+            Some(extension) => format!("{}.{}", replace_path_separators(&clean_name), extension),
+            None => replace_path_separators(&clean_name),
+        };
+        let new_path = path.with_file_name(new_file_name);
+        companions.push_with_companions(&mut plan, path, new_path)?;
+    }
+
+    Ok(plan)
+}
+
+// END CLEAN RELEASE NAMES
+
+// START ADJUST EPISODE NUMBERS
+
+// Fails if the adjustment would result in negative episode numbers
+pub fn check_episode_adjustment(
+    folders: &[PathBuf],
+    adjustment_value: i32,
+    selection: &FileSelection,
+) -> Result<(), String> {
+    let min_episode_number = find_min_episode_number(folders, selection)
+        .map_err(|e| format!("Failed to find minimum episode number: {:?}", e))?;
+
+    if adjustment_value < 0 && min_episode_number + adjustment_value < 0 {
+        return Err(format!(
+            "Cannot adjust episode numbers by {}. Minimum episode number is E{:02}, which would result in negative episode numbers.",
+            adjustment_value, min_episode_number
+        ));
+    }
+
+    Ok(())
+}
+
+pub fn find_min_episode_number(
+    folders: &[PathBuf],
+    selection: &FileSelection,
+) -> Result<i32, io::Error> {
+    let parser = EpisodeParser::new();
+    let mut min_episode_number = i32::MAX;
+
+    for entry in folders
+        .iter()
+        .map(fs::read_dir)
+        .collect::<Result<Vec<_>, _>>()?
+        .into_iter()
+        .flatten()
+    {
+        let entry = entry?;
+        let path = entry.path();
+
+        if path.is_file() && is_video_file(&path) && selection.contains(&path) {
+            if let Some(file_name) = path.file_name().and_then(OsStr::to_str) {
+                // Every number of a multi-episode file counts
+                let episode_numbers = parser
+                    .parse(file_name)
+                    .map(|id| id.episode_numbers())
+                    .unwrap_or_default();
+                for episode_number in episode_numbers {
+                    if episode_number < min_episode_number {
+                        min_episode_number = episode_number;
+                    }
+                }
+            }
+        }
+    }
+
+    if min_episode_number == i32::MAX {
+        return Err(io::Error::new(io::ErrorKind::NotFound, "No episodes found"));
+    }
+
+    Ok(min_episode_number)
+}
+
+pub fn adjust_episode_numbers_plan(
+    directory: &Path,
+    adjustment_value: i32,
+    selection: &FileSelection,
+) -> Result<RenamePlan, io::Error> {
+    let mut entries: Vec<_> = fs::read_dir(directory)?
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| {
+            let path = entry.path();
+            path.is_file() && is_video_file(&path) && selection.contains(&path)
+        })
+        .collect();
+
+    let parser = EpisodeParser::new();
+
+    // Sort entries based on the episode number
+    entries.sort_by_key(|entry| {
+        entry
+            .path()
+            .file_name()
+            .and_then(OsStr::to_str)
+            .and_then(|file_name| parser.parse(file_name))
+            .and_then(|id| id.first_episode())
+            .unwrap_or(0)
+    });
+
+    // If adjustment is positive, rename from the highest episode number first
+    if adjustment_value > 0 {
+        entries.reverse();
+    }
+
+    // Collect the renames
+    let mut plan = RenamePlan::new();
+    let mut companions = Companions::new();
+    for entry in entries {
+        let path = entry.path();
+        if let Some(file_name) = path.file_name().and_then(OsStr::to_str) {
+            if parser
+                .parse(file_name)
+                .and_then(|id| id.first_episode())
+                .is_none()
+            {
+                plan.skip(path, "No episode number found");
+            } else if let Some(new_file_name) =
+                adjust_episode_counter_in_filename(file_name, &parser, adjustment_value)
+            {
+                let episode_count = parser
+                    .parse(file_name)
+                    .map(|id| id.episode_numbers().len())
+                    .unwrap_or(0);
+                if episode_count > 1 {
+                    plan.note(&path, format!("{} episodes", episode_count));
+                }
+
+                let new_path = path.with_file_name(new_file_name);
+                companions.push_with_companions(&mut plan, path, new_path)?;
+            } else if adjustment_value > 0 {
+                plan.skip(path, "Episode number is out of range");
+            } else {
+                plan.skip(path, "Episode number would become negative");
+            }
+        } else {
+            plan.skip(path, "File name is not valid UTF-8");
+        }
+    }
+
+    Ok(plan)
+}
+
+pub fn adjust_episode_counter_in_filename(
+    file_name: &str,
+    parser: &EpisodeParser,
+    adjustment_value: i32,
+) -> Option<String> {
+    let identifier = parser.parse(file_name)?;
+    renumber_episode_identifier(file_name, &identifier, None, adjustment_value)
+}
+
+// Rewrites the season and every episode number of a file, e.g. "01" and "03" of
+// "S01E01-E03". Only the digits change, keeping their width.
+pub fn renumber_episode_identifier(
+    file_name: &str,
+    identifier: &EpisodeIdentifier,
+    new_season: Option<i32>,
+    episode_adjustment: i32,
+) -> Option<String> {
+    if identifier.episodes.is_empty() {
+        return None;
+    }
+
+    let mut replacements = Vec::new();
+    if let Some(new_season) = new_season {
+        replacements.push((identifier.season?, new_season));
+    }
+    for episode in &identifier.episodes {
+        // Numbers past i32::MAX cannot be written back
+        replacements.push((*episode, episode.value.checked_add(episode_adjustment)?));
+    }
+
+    let mut new_file_name = String::new();
+    let mut last_end = 0;
+    for (number, new_value) in replacements {
+        // Block renaming to negative season or episode numbers
+        if new_value < 0 {
+            return None; // Skip renaming if the new number would be negative
+        }
+
+        new_file_name.push_str(&file_name[last_end..number.start]);
+        new_file_name.push_str(&format!("{:0width$}", new_value, width = number.digits()));
+        last_end = number.end;
+    }
+    new_file_name.push_str(&file_name[last_end..]);
+
+    Some(new_file_name)
+}
+
+// END ADJUST EPISODE NUMBERS
+
+// START ADJUST SEASON NUMBERS
+
+// Fails if the adjustment would result in negative season numbers
+pub fn check_season_adjustment(
+    folders: &[PathBuf],
+    adjustment_value: i32,
+    selection: &FileSelection,
+) -> Result<(), String> {
+    let min_season_number = find_min_season_number(folders, selection)
+        .map_err(|e| format!("Failed to find minimum season number: {:?}", e))?;
+
+    if adjustment_value < 0 && min_season_number + adjustment_value < 0 {
+        return Err(format!(
+            "Cannot adjust season numbers by {}. Minimum season number is S{:02}, which would result in negative season numbers.",
+            adjustment_value, min_season_number
+        ));
+    }
+
+    Ok(())
+}
+
+pub fn find_min_season_number(
+    folders: &[PathBuf],
+    selection: &FileSelection,
+) -> Result<i32, io::Error> {
+    let parser = EpisodeParser::new();
+    let mut min_season_number = i32::MAX;
+
+    for entry in folders
+        .iter()
+        .map(fs::read_dir)
+        .collect::<Result<Vec<_>, _>>()?
+        .into_iter()
+        .flatten()
+    {
+        let entry = entry?;
+        let path = entry.path();
+
+        if path.is_file() && is_video_file(&path) && selection.contains(&path) {
+            if let Some(file_name) = path.file_name().and_then(OsStr::to_str) {
+                if let Some(season_number) =
+                    parser.parse(file_name).and_then(|id| id.season_number())
+                {
+                    if season_number < min_season_number {
+                        min_season_number = season_number;
+                    }
+                }
+            }
+        }
+    }
+
+    if min_season_number == i32::MAX {
+        return Err(io::Error::new(io::ErrorKind::NotFound, "No seasons found"));
+    }
+
+    Ok(min_season_number)
+}
+
+pub fn adjust_season_numbers_plan(
+    directory: &Path,
+    adjustment_value: i32,
+    selection: &FileSelection,
+) -> Result<RenamePlan, io::Error> {
+    let mut entries: Vec<_> = fs::read_dir(directory)?
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| {
+            let path = entry.path();
+            path.is_file() && is_video_file(&path) && selection.contains(&path)
+        })
+        .collect();
+
+    let parser = EpisodeParser::new();
+
+    // Sort entries based on the season number
+    entries.sort_by_key(|entry| {
+        entry
+            .path()
+            .file_name()
+            .and_then(OsStr::to_str)
+            .and_then(|file_name| parser.parse(file_name))
+            .and_then(|id| id.season_number())
+            .unwrap_or(0)
+    });
+
+    // If adjustment is positive, rename from the highest season number first
+    if adjustment_value > 0 {
+        entries.reverse();
+    }
+
+    // Collect the renames
+    let mut plan = RenamePlan::new();
+    let mut companions = Companions::new();
+    for entry in entries {
+        let path = entry.path();
+        let file_name = match path.file_name().and_then(OsStr::to_str) {
+            Some(file_name) => file_name,
+            None => {
+                plan.skip(path, "File name is not valid UTF-8");
+                continue;
+            }
+        };
+
+        let identifier = parser.parse(file_name);
+        match identifier
+            .as_ref()
+            .and_then(|id| Some((id, id.season_number()?)))
+        {
+            None => plan.skip(path, "No season number found"),
+            Some((_, season)) if season.checked_add(adjustment_value).is_none() => {
+                plan.skip(path, "Season number is out of range")
+            }
+            Some((identifier, season)) => {
+                match renumber_episode_identifier(
+                    file_name,
+                    identifier,
+                    Some(season + adjustment_value),
+                    0,
+                ) {
+                    Some(new_file_name) => {
+                        let new_path = path.with_file_name(new_file_name);
+                        companions.push_with_companions(&mut plan, path, new_path)?;
+                    }
+                    None => plan.skip(path, "Season number would become negative"),
+                }
+            }
+        }
+    }
+
+    Ok(plan)
+}
+
+pub fn check_episode_range_remap(remap: &EpisodeRangeRemap) -> Result<(), String> {
+    if remap.first_episode > remap.last_episode {
+        return Err(format!(
+            "Invalid range: E{:02} comes after E{:02}.",
+            remap.first_episode, remap.last_episode
+        ));
+    }
+    if remap.season < 0 || remap.first_episode < 0 {
+        return Err("Season and episode numbers cannot be negative.".to_string());
+    }
+    if remap.target_season < 0 || remap.target_first_episode < 0 {
+        return Err(format!(
+            "Cannot move episodes to S{:02}E{:02}, which would result in negative numbers.",
+            remap.target_season, remap.target_first_episode
+        ));
+    }
+    Ok(())
+}
+
+pub fn remap_episode_range_plan(
+    directory: &Path,
+    remap: &EpisodeRangeRemap,
+    selection: &FileSelection,
+) -> Result<RenamePlan, io::Error> {
+    let entries = fs::read_dir(directory)?;
+    let parser = EpisodeParser::new();
+    let episode_adjustment = remap.target_first_episode - remap.first_episode;
+    let in_range = |episode: &i32| (remap.first_episode..=remap.last_episode).contains(episode);
+    let mut plan = RenamePlan::new();
+    let mut companions = Companions::new();
+
+    for entry in entries {
+        let entry = entry?;
+        let path = entry.path();
+
+        if path.is_file() && is_video_file(&path) && selection.contains(&path) {
+            let file_name = match path.file_name().and_then(OsStr::to_str) {
+                Some(file_name) => file_name,
+                None => {
+                    plan.skip(path, "File name is not valid UTF-8");
+                    continue;
+                }
+            };
+
+            let identifier = match parser.parse(file_name) {
+                Some(identifier) if identifier.season_number() == Some(remap.season) => identifier,
+                // Episodes of other seasons stay where they are
+                _ => {
+                    plan.push(path.clone(), path);
+                    continue;
+                }
+            };
+
+            let episodes = identifier.episode_numbers();
+            if !episodes.iter().any(in_range) {
+                plan.push(path.clone(), path);
+            } else if !episodes.iter().all(in_range) {
+                plan.skip(path, "Only part of the episodes are inside the range");
+            } else if let Some(new_file_name) = renumber_episode_identifier(
+                file_name,
+                &identifier,
+                Some(remap.target_season),
+                episode_adjustment,
+            ) {
+                let new_path = path.with_file_name(new_file_name);
+                companions.push_with_companions(&mut plan, path, new_path)?;
+            } else {
+                // The season matched above, so only the episode numbers can overflow
+                plan.skip(path, "Episode number is out of range");
+            }
+        }
+    }
+
+    Ok(plan)
+}
+
+// END ADJUST SEASON NUMBERS
+
+// START CONVERT ABSOLUTE NUMBERING
+
+// Files outside the known seasons, specials and files already in the target numbering
+// are skipped with the reason, so the preview reports them
+pub fn convert_absolute_numbering_plan(
+    directory: &Path,
+    boundaries: &SeasonBoundaries,
+    conversion: NumberingConversion,
+    selection: &FileSelection,
+) -> io::Result<RenamePlan> {
+    let parser = EpisodeParser::new();
+    let folder_season = season_from_folder_name(directory); // e.g. "Season 02"
+    let mut plan = RenamePlan::new();
+    let mut companions = Companions::new();
+
+    for entry in fs::read_dir(directory)? {
+        let path = entry?.path();
+        if !path.is_file() || !is_video_file(&path) || !selection.contains(&path) {
+            continue;
+        }
+
+        let file_name = match path.file_name().and_then(OsStr::to_str) {
+            Some(file_name) => file_name,
+            None => {
+                plan.skip(path, "File name is not valid UTF-8");
+                continue;
+            }
+        };
+        let identifier = match parser.parse(file_name) {
+            Some(identifier) => identifier,
+            None => {
+                plan.skip(path, "No episode number found");
+                continue;
+            }
+        };
+
+        match convert_episode_numbering(
+            file_name,
+            &identifier,
+            boundaries,
+            conversion,
+            folder_season,
+        ) {
+            Ok(new_file_name) => {
+                let new_path = path.with_file_name(new_file_name);
+                companions.push_with_companions(&mut plan, path, new_path)?;
+            }
+            Err(reason) => plan.skip(path, reason),
+        }
+    }
+
+    Ok(plan)
+}
+
+// END CONVERT ABSOLUTE NUMBERING
+
+// START NUMBER UNTAGGED FILES
+
+pub fn number_files_plan(
+    directory: &Path,
+    numbering: &SequentialNumbering,
+    selection: &FileSelection,
+) -> io::Result<RenamePlan> {
+    let parser = EpisodeParser::new();
+    let season = season_from_folder_name(directory).unwrap_or(numbering.season);
+    let mut plan = RenamePlan::new();
+    let mut companions = Companions::new();
+    let mut files = Vec::new();
+
+    for entry in fs::read_dir(directory)? {
+        let path = entry?.path();
+        if !path.is_file() || !is_video_file(&path) || !selection.contains(&path) {
+            continue;
+        }
+
+        let file_name = match path.file_name().and_then(OsStr::to_str) {
+            Some(file_name) => file_name,
+            None => {
+                plan.skip(path, "File name is not valid UTF-8");
+                continue;
+            }
+        };
+        // Track and title numbers are no episode tag, only a season is
+        match parser.parse(file_name) {
+            Some(identifier) if identifier.season.is_some() => {
+                let reason = format!("Already numbered: {}", identifier.text(file_name));
+                plan.skip(path, reason);
+            }
+            _ => files.push(path),
+        }
+    }
+
+    sort_files(&mut files, numbering.order, &numbering.custom_order)?;
+
+    for (index, path) in files.into_iter().enumerate() {
+        let episode = match numbering.episode_at(index) {
+            Some(episode) => episode,
+            None => {
+                plan.skip(path, "Episode number is out of range");
+                continue;
+            }
+        };
+        let new_path = path.with_file_name(numbering.file_name(&path, season, episode));
+        plan.note(&path, format!("Position {}", index + 1));
+        companions.push_with_companions(&mut plan, path, new_path)?;
+    }
+
+    Ok(plan)
+}
+
+// END NUMBER UNTAGGED FILES
+
+// START ORGANIZE VIDEOS INTO DIRECTORIES
+
+// Moves every video of `current_dir` into a folder named after it
+pub fn organize_videos_plan(
+    current_dir: &Path,
+    selection: &FileSelection,
+) -> Result<RenamePlan, String> {
+    let mut plan = RenamePlan::new();
+    let mut companions = Companions::new();
+    for entry in fs::read_dir(current_dir).map_err(|e| e.to_string())? {
+        let entry = entry.map_err(|e| e.to_string())?;
+        let path = entry.path();
+        if path.is_file() && is_video_file(&path) && selection.contains(&path) {
+            // The folder is named after the file name without extension
+            let file_stem = match path.file_stem().and_then(OsStr::to_str) {
+                Some(file_stem) => file_stem,
+                None => {
+                    plan.skip(path, "File name is not valid UTF-8");
+                    continue;
+                }
+            };
+            let new_dir = current_dir.join(file_stem.replace('.', "_"));
+            let new_path = new_dir.join(entry.file_name());
+            plan.create_dir(new_dir);
+            companions
+                .push_with_companions(&mut plan, path, new_path)
+                .map_err(|e| e.to_string())?;
+        }
+    }
+
+    Ok(plan)
+}
+
+// Moves the video out of every subfolder that holds nothing else and removes the folder
+pub fn flatten_single_file_directories_plan(
+    current_dir: &Path,
+    selection: &FileSelection,
+) -> Result<RenamePlan, String> {
+    let mut plan = RenamePlan::new();
+    for entry in fs::read_dir(current_dir).map_err(|e| e.to_string())? {
+        let entry = entry.map_err(|e| e.to_string())?;
+        let path = entry.path();
+        if path.is_dir() {
+            let mut video_files = vec![];
+            let mut file_count = 0;
+            for sub_entry in fs::read_dir(&path).map_err(|e| e.to_string())? {
+                let sub_entry = sub_entry.map_err(|e| e.to_string())?;
+                let sub_path = sub_entry.path();
+                if sub_path.is_file() {
+                    file_count += 1;
+                    if is_video_file(&sub_path) {
+                        video_files.push(sub_path);
+                    }
+                }
+                // Skip the directory if it contains more than one file
+                if file_count > 1 {
+                    break;
+                }
+            }
+            // The folder stays when its video is not selected
+            if file_count == 1 && video_files.len() == 1 && selection.contains(&video_files[0]) {
+                let video_file = video_files.pop().unwrap();
+                let file_name = video_file.file_name().unwrap().to_owned();
+                plan.push(video_file, current_dir.join(file_name));
+                plan.remove_dir(path);
+            }
+        }
+    }
+
+    Ok(plan)
+}
+
+// END ORGANIZE VIDEOS INTO DIRECTORIES
+
+// START ORGANIZE INTO SHOW FOLDERS
+
+pub fn organize_into_show_folders_plan(
+    directory: &Path,
+    library_root: &Path,
+    show_details: Option<&ShowDetailsWithEpisodes>,
+    boundaries: Option<&SeasonBoundaries>,
+    selection: &FileSelection,
+) -> io::Result<RenamePlan> {
+    let parser = ReleaseNameParser::new();
+    let episode_titles = show_details
+        .map(|details| episode_titles_by_number(&details.episodes_by_season))
+        .unwrap_or_default();
+    let folder_season = season_from_folder_name(directory);
+    let mut plan = RenamePlan::new();
+    let mut companions = Companions::new();
+
+    for entry in fs::read_dir(directory)? {
+        let path = entry?.path();
+        if !path.is_file() || !is_video_file(&path) || !selection.contains(&path) {
+            continue;
+        }
+
+        let file_name = match path.file_name().and_then(OsStr::to_str) {
+            Some(file_name) => file_name,
+            None => {
+                plan.skip(path, "File name is not valid UTF-8");
+                continue;
+            }
+        };
+        let identifier = match parser.episode_parser().parse(file_name) {
+            Some(identifier) => identifier,
+            None => {
+                plan.skip(path, "No episode number found");
+                continue;
+            }
+        };
+
+        let values = episode_template_values(
+            file_name,
+            &parser,
+            show_details,
+            &episode_titles,
+            DEFAULT_TITLE_SEPARATOR,
+            folder_season,
+        );
+        let text_value = |key: &str| match values.get(key) {
+            Some(TemplateValue::Text(text)) if !text.trim().is_empty() => Some(text.clone()),
+            _ => None,
+        };
+
+        let (show, parsed_year) = match text_value("show") {
+            Some(show) => split_year(&show),
+            None => {
+                plan.skip(path, "No show name found, load the show first");
+                continue;
+            }
+        };
+        let year = text_value("year").or(parsed_year);
+
+        let (season, episodes) = match episode_season(&identifier, folder_season, boundaries) {
+            Ok((season, episodes)) => (season, episodes),
+            Err(reason) => {
+                plan.skip(path, reason);
+                continue;
+            }
+        };
+        if identifier.season.is_none() {
+            if let Some(absolute) = identifier.first_episode() {
+                plan.note(&path, format!("Absolute episode {}", absolute));
+            }
+        }
+
+        // Fetched titles win over the title in the file name
+        let fetched_titles: Option<Vec<&str>> = episodes
+            .iter()
+            .map(|episode| {
+                episode_titles
+                    .get(&(season, *episode))
+                    .map(|title| title.trim())
+            })
+            .collect();
+        let title = match fetched_titles {
+            Some(titles) if !titles.is_empty() => Some(titles.join(DEFAULT_TITLE_SEPARATOR)),
+            _ => text_value("title"),
+        };
+
+        let season_dir = library_root
+            .join(replace_path_separators(&show_folder_name(
+                &show,
+                year.as_deref(),
+            )))
+            .join(season_folder_name(season));
+        let extension = path.extension().and_then(OsStr::to_str).unwrap_or("");
+        let new_file_name =
+            episode_file_name(&show, season, &episodes, title.as_deref(), extension);
+        let new_path = season_dir.join(replace_path_separators(&new_file_name));
+
+        plan.create_dir(season_dir);
+        companions.push_with_companions(&mut plan, path, new_path)?;
+    }
+
+    Ok(plan)
+}
+
+// The season and episodes of a file. Files without a season take the one of their
+// folder ("Season 02") or are converted from absolute numbers.
+pub fn episode_season(
+    identifier: &EpisodeIdentifier,
+    folder_season: Option<i32>,
+    boundaries: Option<&SeasonBoundaries>,
+) -> Result<(i32, Vec<i32>), String> {
+    let episodes = identifier.episode_numbers();
+    if episodes.is_empty() {
+        return Err("Dated episodes have no episode number".to_string());
+    }
+    if let Some(season) = identifier.season_number().or(folder_season) {
+        return Ok((season, episodes));
+    }
+
+    let boundaries =
+        boundaries.ok_or("No season number found, load the show to convert absolute numbers")?;
+    let seasonal: Vec<(i32, i32)> = episodes
+        .iter()
+        .map(|absolute| {
+            boundaries
+                .to_seasonal(*absolute)
+                .ok_or_else(|| format!("Episode {} is outside the known seasons", absolute))
+        })
+        .collect::<Result<_, _>>()?;
+
+    let season = seasonal[0].0;
+    if seasonal.iter().any(|(other, _)| *other != season) {
+        return Err("The episodes of this file span several seasons".to_string());
+    }
+    Ok((
+        season,
+        seasonal.into_iter().map(|(_, episode)| episode).collect(),
+    ))
+}
+
+// END ORGANIZE INTO SHOW FOLDERS
+
+// START ORGANIZE MOVIES
+
+// TMDB's match by parsed title and year, None if TMDB knows no such movie
+pub type ConfirmedMovies = HashMap<(String, Option<String>), Option<TMDBMovie>>;
+
+// Looks every parsed title and year up once, e.g. for two editions of a movie.
+// None without an API key, the movies are then filed by their parsed names.
+pub async fn confirm_movies(
+    files: &[PathBuf],
+    parser: &ReleaseNameParser,
+    tmdb_api_key: Option<&str>,
+) -> Result<Option<ConfirmedMovies>, String> {
+    let api_key = match tmdb_api_key.map(str::trim) {
+        Some(api_key) if !api_key.is_empty() => api_key,
+        _ => return Ok(None),
+    };
+
+    let client = Client::new();
+    let mut confirmed = HashMap::new();
+    for movie in files
+        .iter()
+        .filter_map(|path| parse_movie_file(parser, path))
+    {
+        let key = (movie.title.clone(), movie.year.clone());
+        if let Entry::Vacant(slot) = confirmed.entry(key) {
+            let found =
+                search_tmdb_movie(&client, api_key, &movie.title, movie.year.as_deref()).await?;
+            slot.insert(found);
+        }
+    }
+
+    Ok(Some(confirmed))
+}
+
+// The video files of the folders, samples included so the preview can list them
+pub fn movie_files(folders: &[PathBuf], selection: &FileSelection) -> io::Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+    for folder in folders {
+        for entry in fs::read_dir(folder)? {
+            let path = entry?.path();
+            if path.is_file() && is_video_file(&path) && selection.contains(&path) {
+                files.push(path);
+            }
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+pub fn parse_movie_file(parser: &ReleaseNameParser, path: &Path) -> Option<MovieName> {
+    path.file_stem()
+        .and_then(OsStr::to_str)
+        .and_then(|stem| parser.parse_movie(stem))
+}
+
+pub fn organize_movies_plan(
+    files: &[PathBuf],
+    library_root: &Path,
+    parser: &ReleaseNameParser,
+    confirmed: Option<&ConfirmedMovies>,
+) -> io::Result<RenamePlan> {
+    let mut plan = RenamePlan::new();
+    let mut companions = Companions::new();
+
+    for path in files {
+        if is_sample(path, library_root) {
+            plan.skip(path.clone(), "Samples are not moved");
+            continue;
+        }
+        let movie = match parse_movie_file(parser, path) {
+            Some(movie) => movie,
+            None => {
+                plan.skip(
+                    path.clone(),
+                    "No movie title found, episodes belong in show folders",
+                );
+                continue;
+            }
+        };
+
+        let (title, year) = match confirmed {
+            Some(confirmed) => match confirmed.get(&(movie.title.clone(), movie.year.clone())) {
+                Some(Some(found)) => {
+                    let found_year = found.year().or_else(|| movie.year.clone());
+                    if found.title == movie.title && found_year == movie.year {
+                        plan.note(path, "Confirmed by TMDB");
+                    } else {
+                        plan.note(
+                            path,
+                            format!(
+                                "TMDB knows it as {}",
+                                movie_folder_name(&found.title, found_year.as_deref())
+                            ),
+                        );
+                    }
+                    (found.title.clone(), found_year)
+                }
+                _ => {
+                    plan.note(path, "Not found on TMDB, using the name from the file");
+                    (movie.title.clone(), movie.year.clone())
+                }
+            },
+            None => (movie.title.clone(), movie.year.clone()),
+        };
+        if year.is_none() {
+            plan.note(
+                path,
+                "No year found, Plex and Jellyfin match movies better with one",
+            );
+        }
+
+        let movie_dir = library_root.join(replace_path_separators(&movie_folder_name(
+            &title,
+            year.as_deref(),
+        )));
+        let extension = path.extension().and_then(OsStr::to_str).unwrap_or("");
+        let new_file_name =
+            movie_file_name(&title, year.as_deref(), movie.edition.as_deref(), extension);
+        let new_path = movie_dir.join(replace_path_separators(&new_file_name));
+
+        plan.create_dir(movie_dir);
+        companions.push_with_companions(&mut plan, path.clone(), new_path)?;
+    }
+
+    Ok(plan)
+}
+
+// END ORGANIZE MOVIES
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::explorer::rename_plan::PreviewStatus;
+    use crate::explorer::search_replace::SearchOptions;
+    use crate::explorer::test_support::TestDir;
+
+    fn titles(season: i32, titles: &[&str]) -> HashMap<(i32, i32), String> {
+        episode_titles_by_number(&[SeasonedEpisodes {
+            season,
+            start_episode: 1,
+            end_episode: titles.len() as i32,
+            titles: titles.iter().map(|title| title.to_string()).collect(),
+        }])
+    }
+
+    #[test]
+    fn renumbers_every_episode_of_a_file() {
+        let parser = EpisodeParser::new();
+        let renumber = |name: &str, value| adjust_episode_counter_in_filename(name, &parser, value);
+
+        assert_eq!(
+            renumber("Show.S01E01-E03.mkv", 2).as_deref(),
+            Some("Show.S01E03-E05.mkv")
+        );
+        assert_eq!(
+            renumber("Show.S01E09E10.mkv", 1).as_deref(),
+            Some("Show.S01E10E11.mkv")
+        );
+        assert_eq!(renumber("Show.S01E01E02.mkv", -2), None);
+    }
+
+    #[test]
+    fn refuses_episode_numbers_past_the_maximum() {
+        let parser = EpisodeParser::new();
+
+        assert_eq!(
+            adjust_episode_counter_in_filename("Show S01E02.mkv", &parser, i32::MAX),
+            None
+        );
+
+        let dir = TestDir::new();
+        dir.file("Show S01E02.mkv");
+        let plan =
+            adjust_episode_numbers_plan(&dir.join(""), i32::MAX, &FileSelection::all()).unwrap();
+        let reasons: Vec<Option<String>> = plan
+            .preview()
+            .into_iter()
+            .map(|entry| entry.reason)
+            .collect();
+        assert_eq!(
+            reasons,
+            [Some("Episode number is out of range".to_string())]
+        );
+    }
+
+    #[test]
+    fn renumbers_season_and_episodes() {
+        let parser = EpisodeParser::new();
+        let file_name = "Show 1x02-03.mkv";
+        let identifier = parser.parse(file_name).unwrap();
+
+        assert_eq!(
+            renumber_episode_identifier(file_name, &identifier, Some(2), -1).as_deref(),
+            Some("Show 2x01-02.mkv")
+        );
+    }
+
+    #[test]
+    fn titles_every_episode_of_a_file() {
+        let parser = EpisodeParser::new();
+        let titles = titles(1, &["Pilot", "Second", "Third"]);
+
+        assert_eq!(
+            create_new_file_name(
+                "Show.S01E01-E03.old title.mkv",
+                &parser,
+                &titles,
+                " & ",
+                None
+            ),
+            Ok("Show.S01E01-E03 - Pilot & Second & Third".to_string())
+        );
+        assert_eq!(
+            create_new_file_name("Show.S01E02E03.mkv", &parser, &titles, " + ", None),
+            Ok("Show.S01E02E03 - Second + Third".to_string())
+        );
+        assert!(
+            create_new_file_name("Show.S01E03-E04.mkv", &parser, &titles, " & ", None).is_err()
+        );
+    }
+
+    #[test]
+    fn renders_templates_for_file_names() {
+        let parser = ReleaseNameParser::new();
+        let values = episode_template_values(
+            "[Grp] Show - 01 [1080p].mkv",
+            &parser,
+            None,
+            &HashMap::new(),
+            " & ",
+            None,
+        );
+        let template =
+            FilenameTemplate::parse("{show} {absolute:03} [{group}] {resolution}").unwrap();
+
+        assert_eq!(
+            template.render_file_name(&values),
+            Ok("Show 001 [Grp] 1080p.mkv".to_string())
+        );
+
+        let values = episode_template_values(
+            "Show.Name.S01E02.720p.WEB-DL.x264-GRP.mkv",
+            &parser,
+            None,
+            &HashMap::new(),
+            " & ",
+            None,
+        );
+        let template =
+            FilenameTemplate::parse("{show} {season}x{episode:02} [{group}] {resolution}").unwrap();
+        assert_eq!(
+            template.render_file_name(&values),
+            Ok("Show Name 1x02 [GRP] 720p.mkv".to_string())
+        );
+    }
+
+    #[test]
+    fn renders_templates_with_the_folder_season() {
+        let dir = TestDir::new();
+        dir.file("Season 02/Show - 05.mkv");
+        dir.file("Season 02/Show - 05.srt");
+        let folders = folders_to_process(&dir.join(""), Some(1)).unwrap();
+        let template = FilenameTemplate::parse("{show} - S{season:02}E{episode:02}").unwrap();
+
+        let plan = plan_for_folders(&folders, |folder| {
+            rename_with_template_plan(folder, &template, None, " & ", &FileSelection::all())
+        })
+        .unwrap();
+        assert!(plan.execute().failed.is_none());
+
+        assert_eq!(
+            dir.read("Season 02/Show - S02E05.mkv"),
+            "Season 02/Show - 05.mkv"
+        );
+        assert_eq!(
+            dir.read("Season 02/Show - S02E05.srt"),
+            "Season 02/Show - 05.srt"
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn skips_videos_with_invalid_names_when_organizing() {
+        use std::os::unix::ffi::OsStrExt;
+
+        let dir = TestDir::new();
+        dir.file("Movie.2020.mkv");
+        let invalid = dir.join("").join(OsStr::from_bytes(b"Movie \xff.mkv"));
+        fs::write(&invalid, "invalid").unwrap();
+
+        let plan = organize_videos_plan(&dir.join(""), &FileSelection::all()).unwrap();
+        assert!(plan.execute().failed.is_none());
+
+        assert_eq!(dir.read("Movie_2020/Movie.2020.mkv"), "Movie.2020.mkv");
+        assert!(invalid.is_file());
+    }
+
+    #[test]
+    fn checks_season_adjustments_in_subfolders() {
+        let dir = TestDir::new();
+        dir.file("S02E01.mkv");
+        dir.file("Season 01/Show.S01E01.mkv");
+        let folders = folders_to_process(&dir.join(""), Some(1)).unwrap();
+        let selection = FileSelection::all();
+
+        assert_eq!(find_min_season_number(&folders, &selection).unwrap(), 1);
+        assert!(check_season_adjustment(&folders, -1, &selection).is_ok());
+        assert!(check_season_adjustment(&folders, -2, &selection).is_err());
+
+        let plan = plan_for_folders(&folders, |folder| {
+            adjust_season_numbers_plan(folder, 1, &selection)
+        })
+        .unwrap();
+        let proposed: Vec<String> = plan
+            .preview()
+            .into_iter()
+            .map(|entry| entry.proposed_name)
+            .collect();
+        assert_eq!(proposed, ["S03E01.mkv", "Show.S02E01.mkv"]);
+    }
+
+    #[test]
+    fn keeps_replacements_with_separators_in_the_folder() {
+        let dir = TestDir::new();
+        dir.file("Show ACDC.mkv");
+        let search = FileNameSearch::new("ACDC", "AC/DC", SearchOptions::default()).unwrap();
+
+        let plan = search_and_replace_plan(&dir.join(""), &search, &FileSelection::all()).unwrap();
+        let proposed: Vec<(String, PathBuf)> = plan
+            .preview()
+            .into_iter()
+            .map(|entry| (entry.proposed_name, entry.proposed_path))
+            .collect();
+        assert_eq!(
+            proposed,
+            [("Show AC-DC.mkv".to_string(), dir.join("Show AC-DC.mkv"))]
+        );
+    }
+
+    #[test]
+    fn renames_companions_once_when_searching_all_files() {
+        let dir = TestDir::new();
+        dir.file("Show 1080p.mkv");
+        dir.file("Show 1080p.en.srt");
+        dir.file("notes 1080p.txt");
+        let options = SearchOptions {
+            all_files: true,
+            ..SearchOptions::default()
+        };
+        let search = FileNameSearch::new("1080p", "720p", options).unwrap();
+
+        let plan = search_and_replace_plan(&dir.join(""), &search, &FileSelection::all()).unwrap();
+        plan.validate().unwrap();
+        let proposed: Vec<(String, bool)> = plan
+            .preview()
+            .into_iter()
+            .map(|entry| (entry.proposed_name, entry.companion_of.is_some()))
+            .collect();
+        assert_eq!(
+            proposed,
+            [
+                ("Show 720p.mkv".to_string(), false),
+                ("Show 720p.en.srt".to_string(), true),
+                ("notes 720p.txt".to_string(), false),
+            ]
+        );
+    }
+
+    #[test]
+    fn takes_the_season_from_the_folder() {
+        let parser = EpisodeParser::new();
+        let titles = titles(2, &["Pilot"]);
+
+        assert_eq!(
+            create_new_file_name("Show Episode 1.mkv", &parser, &titles, " & ", Some(2)),
+            Ok("Show Episode 1 - Pilot".to_string())
+        );
+    }
+
+    #[test]
+    fn only_skips_sample_clips_when_organizing_movies() {
+        let dir = TestDir::new();
+        dir.file("Sample.Size.2019.1080p.mkv");
+        dir.file("Movie.2020.1080p-sample.mkv");
+        dir.file("Sample/Movie.2020.mkv");
+        let folders = folders_to_process(&dir.join(""), Some(1)).unwrap();
+        let files = movie_files(&folders, &FileSelection::all()).unwrap();
+
+        let plan =
+            organize_movies_plan(&files, &dir.join(""), &ReleaseNameParser::new(), None).unwrap();
+        let statuses: Vec<(String, PreviewStatus)> = plan
+            .preview()
+            .into_iter()
+            .map(|entry| (entry.proposed_name, entry.status))
+            .collect();
+        assert_eq!(
+            statuses,
+            [
+                (
+                    "Movie.2020.1080p-sample.mkv".to_string(),
+                    PreviewStatus::Skipped
+                ),
+                ("Movie.2020.mkv".to_string(), PreviewStatus::Skipped),
+                ("Sample Size (2019).mkv".to_string(), PreviewStatus::Changed),
+            ]
+        );
+    }
+}
@@ -0,0 +1,584 @@
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+
+use crate::explorer::episode_parser::{EpisodeParser, NamingScheme};
+
+// STRUCTS
+
+// Parts of a release name besides the show title and episode, which are always kept
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum ReleasePart {
+    EpisodeTitle,
+    Resolution,
+    Source,
+    Codec,
+    Group,
+    Crc,
+}
+
+// What a release name like `[SubGroup] Show.Name.S01E03.1080p.WEB-DL.x264-GROUP [ABCD1234]`
+// is made of. `episode` is the identifier as written, e.g. "S01E03".
+#[derive(Debug, Serialize, Clone, Default, PartialEq)]
+pub struct ReleaseParts {
+    pub show: String,
+    pub episode: Option<String>,
+    pub episode_title: Option<String>,
+    pub resolution: Option<String>,
+    pub source: Option<String>,
+    pub codec: Option<String>,
+    pub group: Option<String>,
+    pub crc: Option<String>,
+}
+
+// Title, year and edition of a movie like `Blade.Runner.1982.The.Final.Cut.1080p.BluRay.x264-GROUP`
+#[derive(Debug, Serialize, Clone, Default, PartialEq)]
+pub struct MovieName {
+    pub title: String,
+    pub year: Option<String>,
+    pub edition: Option<String>, // "Final Cut", written as `{edition-Final Cut}`
+}
+
+// The parts to keep when rebuilding a name, and words to drop from the titles
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct CleanupRules {
+    pub keep: Vec<ReleasePart>,
+    pub remove_words: Vec<String>,
+}
+
+impl Default for CleanupRules {
+    fn default() -> Self {
+        Self {
+            keep: vec![ReleasePart::EpisodeTitle, ReleasePart::Resolution],
+            remove_words: vec![],
+        }
+    }
+}
+
+impl CleanupRules {
+    fn keeps(&self, part: ReleasePart) -> bool {
+        self.keep.contains(&part)
+    }
+}
+
+// START RULES FILE
+
+fn rules_path() -> Result<PathBuf, String> {
+    let data_dir = dirs::data_local_dir().ok_or("Failed to get local data directory")?;
+    Ok(data_dir.join("script-kiddie").join("cleanup_rules.json"))
+}
+
+// The saved rules, or the default rules if none were saved yet
+pub fn load_cleanup_rules() -> Result<CleanupRules, String> {
+    let path = rules_path()?;
+    if !path.exists() {
+        return Ok(CleanupRules::default());
+    }
+
+    let content =
+        fs::read_to_string(&path).map_err(|e| format!("Failed to read cleanup rules: {}", e))?;
+    serde_json::from_str(&content).map_err(|e| format!("Failed to parse cleanup rules: {}", e))
+}
+
+pub fn save_cleanup_rules(rules: &CleanupRules) -> Result<(), String> {
+    let path = rules_path()?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create rules directory: {}", e))?;
+    }
+
+    let content = serde_json::to_string_pretty(rules).map_err(|e| e.to_string())?;
+    fs::write(&path, content).map_err(|e| format!("Failed to write cleanup rules: {}", e))
+}
+
+// END RULES FILE
+
+// START TOKENIZING
+
+pub struct ReleaseNameParser {
+    episodes: EpisodeParser,
+    leading_group: Regex,
+    bracket: Regex,
+    crc: Regex,
+    year: Regex,
+    resolution: Regex,
+    source: Regex,
+    bare_source: Regex,
+    codec: Regex,
+    scene_group: Regex,
+    edition_tag: Regex,
+    edition: Regex,
+    title_year: Regex,
+}
+
+impl ReleaseNameParser {
+    pub fn new() -> Self {
+        Self {
+            episodes: EpisodeParser::new(),
+            leading_group: Regex::new(r"^\s*\[([^\[\]]+)\]").unwrap(),
+            bracket: Regex::new(r"[\[(]([^\[\]()]*)[\])]").unwrap(),
+            crc: Regex::new(r"^[0-9A-Fa-f]{8}$").unwrap(),
+            year: Regex::new(r"^(?:19|20)\d{2}$").unwrap(),
+            resolution: Regex::new(r"(?i)\b(?:\d{3,4}p|4k|uhd|\d{3,4}x\d{3,4})\b").unwrap(),
+            source: Regex::new(
+                r"(?i)\b(?:web-?dl|web-?rip|blu-?ray|bd-?rip|br-?rip|hdtv|dvd-?rip|remux|hd-?rip)\b",
+            )
+            .unwrap(),
+            // Also ordinary words, "The.Dark.Web" is a title, "1080p.WEB" a source
+            bare_source: Regex::new(r"(?i)\b(?:web|bd|dvd)\b").unwrap(),
+            codec: Regex::new(r"(?i)\b(?:[xh]\.?26[45]|hevc|avc|av1|xvid|divx|vp9)\b").unwrap(),
+            // "x264-GROUP" at the very end
+            scene_group: Regex::new(r"-([A-Za-z0-9]+)\s*$").unwrap(),
+            // "{edition-Director's Cut}", the way Plex and Jellyfin name editions
+            edition_tag: Regex::new(r"\{edition-([^{}]+)\}").unwrap(),
+            edition: Regex::new(
+                r"(?i)\b(?:director'?s[ ._]cut|(?:extended|theatrical|ultimate|special|collector'?s|\d+(?:th)?[ ._]anniversary)[ ._](?:edition|cut)|final[ ._]cut|extended|unrated|uncut|remastered|criterion|imax)\b",
+            )
+            .unwrap(),
+            title_year: Regex::new(r"\(?\b((?:19|20)\d{2})\b\)?").unwrap(),
+        }
+    }
+
+    pub fn episode_parser(&self) -> &EpisodeParser {
+        &self.episodes
+    }
+
+    // Splits a file name without its extension into its parts
+    pub fn parse(&self, stem: &str) -> ReleaseParts {
+        let mut parts = ReleaseParts::default();
+        let mut text = stem.to_string();
+
+        // "[SubGroup] Show - 03" names the fansub group first
+        if let Some(caps) = self.leading_group.captures(&text) {
+            parts.group = Some(caps[1].trim().to_string());
+            text = text[caps[0].len()..].to_string();
+        }
+
+        // Bracketed tags like "[1080p]", "(BD x265)" or "[ABCD1234]", a year stays part of the title
+        let mut tags = Vec::new();
+        text = self
+            .bracket
+            .replace_all(&text, |caps: &regex::Captures| {
+                let content = caps[1].trim();
+                if self.year.is_match(content) {
+                    caps[0].to_string()
+                } else {
+                    tags.push(content.to_string());
+                    " ".to_string()
+                }
+            })
+            .to_string();
+        for tag in &tags {
+            if self.crc.is_match(tag) {
+                parts.crc = Some(tag.to_uppercase());
+            } else {
+                self.take_technical(tag, &mut parts);
+            }
+        }
+
+        // The episode identifier splits the show title from everything after it
+        let (title_text, rest) = match self.episodes.parse(&text) {
+            Some(identifier) => {
+                let episode = identifier.text(&text);
+                parts.episode = Some(match identifier.scheme {
+                    NamingScheme::SeasonEpisode | NamingScheme::Crossed => {
+                        episode.to_uppercase().replace(['.', ' ', '_'], "")
+                    }
+                    _ => episode.to_string(),
+                });
+                (
+                    text[..identifier.start].to_string(),
+                    text[identifier.end..].to_string(),
+                )
+            }
+            _ => (String::new(), text.clone()),
+        };
+
+        // A scene group is attached to the last technical tag, "x264-GROUP".
+        // Titles like "Spider-Man" and tags like "WEB-DL" have no technical tag before the '-'.
+        let mut rest = rest;
+        if let Some(caps) = self.scene_group.captures(&rest) {
+            let hyphen = caps.get(0).unwrap().start();
+            let tag_start = rest[..hyphen]
+                .rfind([' ', '.', '_'])
+                .map(|index| index + 1)
+                .unwrap_or(0);
+            if self.is_technical(&rest[tag_start..hyphen])
+                && !self.is_technical(rest[tag_start..].trim())
+            {
+                // A leading fansub group wins over the scene group
+                if parts.group.is_none() {
+                    parts.group = Some(caps[1].to_string());
+                }
+                rest = rest[..hyphen].to_string();
+            }
+        }
+
+        // Technical tags end the episode title, whatever follows them is dropped
+        let first_technical = self.take_technical(&rest, &mut parts);
+        let episode_title_text = &rest[..first_technical.unwrap_or(rest.len())];
+
+        if parts.episode.is_some() {
+            parts.show = clean_title(&title_text);
+            parts.episode_title = Some(clean_title(episode_title_text)).filter(|t| !t.is_empty());
+        } else {
+            parts.show = clean_title(episode_title_text);
+        }
+
+        parts
+    }
+
+    fn is_technical(&self, word: &str) -> bool {
+        [
+            &self.resolution,
+            &self.source,
+            &self.bare_source,
+            &self.codec,
+        ]
+        .iter()
+        .any(|regex| {
+            regex
+                .find(word)
+                .map_or(false, |found| found.as_str() == word)
+        })
+    }
+
+    // Fills in resolution, source and codec from `text`, returns where the first of them starts
+    fn take_technical(&self, text: &str, parts: &mut ReleaseParts) -> Option<usize> {
+        let mut first = None;
+        for (found, slot) in [
+            (self.resolution.find(text), &mut parts.resolution),
+            (self.find_source(text), &mut parts.source),
+            (self.codec.find(text), &mut parts.codec),
+        ] {
+            if let Some(found) = found {
+                if slot.is_none() {
+                    *slot = Some(found.as_str().to_string());
+                }
+                first = Some(first.map_or(found.start(), |first: usize| first.min(found.start())));
+            }
+        }
+        first
+    }
+
+    // The first source tag. A bare "WEB", "BD" or "DVD" only counts after another
+    // technical tag, at the start of the tags or as a bracketed tag of its own.
+    fn find_source<'t>(&self, text: &'t str) -> Option<regex::Match<'t>> {
+        let is_separator = |c: char| matches!(c, ' ' | '.' | '_' | '-');
+        let bare = self.bare_source.find_iter(text).find(|found| {
+            let before = text[..found.start()].trim_end_matches(is_separator);
+            let after = text[found.end()..].trim_start_matches(is_separator);
+            let previous = before.rsplit(is_separator).next().unwrap_or("");
+            let next = after.split(is_separator).next().unwrap_or("");
+            if previous.is_empty() {
+                next.is_empty() || self.is_technical(next)
+            } else {
+                self.is_technical(previous)
+            }
+        });
+
+        match (self.source.find(text), bare) {
+            (Some(source), Some(bare)) if bare.start() < source.start() => Some(bare),
+            (source, bare) => source.or(bare),
+        }
+    }
+}
+
+impl Default for ReleaseNameParser {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// Dots and underscores are separators when the name has no spaces, e.g. "Show.Name"
+fn clean_title(text: &str) -> String {
+    let text = text.trim();
+    let text = if text.contains(' ') {
+        text.replace('_', " ")
+    } else {
+        text.replace(['.', '_'], " ")
+    };
+    text.split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .trim_matches(|c: char| c == '-' || c == ' ')
+        .to_string()
+}
+
+// END TOKENIZING
+
+// START MOVIES
+
+impl ReleaseNameParser {
+    // Splits a movie file name without its extension into title, year and edition.
+    // None if the name has an episode identifier or no title.
+    pub fn parse_movie(&self, stem: &str) -> Option<MovieName> {
+        let mut edition = self
+            .edition_tag
+            .captures(stem)
+            .map(|caps| caps[1].trim().to_string());
+        let stem = self.edition_tag.replace_all(stem, " ");
+        if edition.is_none() {
+            edition = self
+                .edition
+                .find(&stem)
+                .map(|found| edition_name(found.as_str()));
+        }
+
+        let parts = self.parse(&stem);
+        if parts.episode.is_some() {
+            return None;
+        }
+
+        // The last year that doesn't start the name, so "2001 A Space Odyssey 1968" keeps its title
+        let mut title = parts.show.clone();
+        let mut year = None;
+        if let Some(found) = self
+            .title_year
+            .captures_iter(&parts.show)
+            .filter(|caps| caps.get(0).unwrap().start() > 0)
+            .last()
+        {
+            title = parts.show[..found.get(0).unwrap().start()].to_string();
+            year = Some(found[1].to_string());
+        }
+        let title = clean_title(&self.edition.replace_all(&title, " "));
+
+        if title.is_empty() {
+            return None;
+        }
+        Some(MovieName {
+            title,
+            year,
+            edition,
+        })
+    }
+}
+
+// "directors.cut" -> "Director's Cut", "IMAX" stays upper case
+fn edition_name(text: &str) -> String {
+    text.split([' ', '.', '_'])
+        .filter(|word| !word.is_empty())
+        .map(|word| match word.to_lowercase().as_str() {
+            "imax" => "IMAX".to_string(),
+            "directors" | "director's" => "Director's".to_string(),
+            "collectors" | "collector's" => "Collector's".to_string(),
+            lower => {
+                let mut chars = lower.chars();
+                match chars.next() {
+                    Some(first) => first.to_uppercase().chain(chars).collect(),
+                    None => String::new(),
+                }
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+// END MOVIES
+
+// START REBUILDING
+
+// Builds "[Group] Show - S01E03 - Title [1080p WEB-DL x264] [ABCD1234]" from the kept parts.
+// None if not even a show title or episode was recognized.
+pub fn rebuild_release_name(parts: &ReleaseParts, rules: &CleanupRules) -> Option<String> {
+    let show = remove_words(&parts.show, &rules.remove_words);
+    let mut pieces = Vec::new();
+    if !show.is_empty() {
+        pieces.push(show);
+    }
+    if let Some(episode) = &parts.episode {
+        pieces.push(episode.clone());
+    }
+    if pieces.is_empty() {
+        return None;
+    }
+    if rules.keeps(ReleasePart::EpisodeTitle) {
+        if let Some(episode_title) = &parts.episode_title {
+            let episode_title = remove_words(episode_title, &rules.remove_words);
+            if !episode_title.is_empty() {
+                pieces.push(episode_title);
+            }
+        }
+    }
+    let mut name = pieces.join(" - ");
+
+    if rules.keeps(ReleasePart::Group) {
+        if let Some(group) = &parts.group {
+            name = format!("[{}] {}", group, name);
+        }
+    }
+
+    let technical: Vec<&str> = [
+        (ReleasePart::Resolution, &parts.resolution),
+        (ReleasePart::Source, &parts.source),
+        (ReleasePart::Codec, &parts.codec),
+    ]
+    .iter()
+    .filter(|(part, _)| rules.keeps(*part))
+    .filter_map(|(_, value)| value.as_deref())
+    .collect();
+    if !technical.is_empty() {
+        name.push_str(&format!(" [{}]", technical.join(" ")));
+    }
+
+    if rules.keeps(ReleasePart::Crc) {
+        if let Some(crc) = &parts.crc {
+            name.push_str(&format!(" [{}]", crc));
+        }
+    }
+
+    Some(name)
+}
+
+// The recognized parts the rules leave out, e.g. ["WEB-DL", "x264", "GROUP"]
+pub fn dropped_parts(parts: &ReleaseParts, rules: &CleanupRules) -> Vec<String> {
+    [
+        (ReleasePart::EpisodeTitle, &parts.episode_title),
+        (ReleasePart::Resolution, &parts.resolution),
+        (ReleasePart::Source, &parts.source),
+        (ReleasePart::Codec, &parts.codec),
+        (ReleasePart::Group, &parts.group),
+        (ReleasePart::Crc, &parts.crc),
+    ]
+    .iter()
+    .filter(|(part, _)| !rules.keeps(*part))
+    .filter_map(|(_, value)| (*value).clone())
+    .collect()
+}
+
+// Drops whole words, ignoring case
+fn remove_words(text: &str, words: &[String]) -> String {
+    text.split_whitespace()
+        .filter(|word| {
+            !words
+                .iter()
+                .any(|remove| !remove.is_empty() && word.eq_ignore_ascii_case(remove.trim()))
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+        .trim_matches(|c: char| c == '-' || c == ' ')
+        .to_string()
+}
+
+// END REBUILDING
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn some(text: &str) -> Option<String> {
+        Some(text.to_string())
+    }
+
+    #[test]
+    fn parses_scene_releases() {
+        let parts =
+            ReleaseNameParser::new().parse("Show.Name.S01E03.The.Title.1080p.WEB-DL.x264-GROUP");
+        assert_eq!(
+            parts,
+            ReleaseParts {
+                show: "Show Name".to_string(),
+                episode: some("S01E03"),
+                episode_title: some("The Title"),
+                resolution: some("1080p"),
+                source: some("WEB-DL"),
+                codec: some("x264"),
+                group: some("GROUP"),
+                crc: None,
+            }
+        );
+    }
+
+    #[test]
+    fn parses_fansub_releases() {
+        let parts = ReleaseNameParser::new().parse("[SubGroup] Show - 03 [1080p][ABCD1234]");
+        assert_eq!(parts.show, "Show");
+        assert_eq!(parts.episode, some("03"));
+        assert_eq!(parts.episode_title, None);
+        assert_eq!(parts.group, some("SubGroup"));
+        assert_eq!(parts.resolution, some("1080p"));
+        assert_eq!(parts.crc, some("ABCD1234"));
+    }
+
+    #[test]
+    fn keeps_hyphens_in_titles() {
+        let parts = ReleaseNameParser::new().parse("Spider-Man S01E01 Pilot");
+        assert_eq!(parts.show, "Spider-Man");
+        assert_eq!(parts.episode_title, some("Pilot"));
+        assert_eq!(parts.group, None);
+    }
+
+    #[test]
+    fn keeps_source_words_in_titles() {
+        let parser = ReleaseNameParser::new();
+
+        let parts = parser.parse("Show.S01E03.The.Dark.Web.1080p");
+        assert_eq!(parts.episode_title, some("The Dark Web"));
+        assert_eq!(parts.source, None);
+
+        let parts = parser.parse("Show.S01E03.Title.1080p.WEB.h264-GROUP");
+        assert_eq!(parts.episode_title, some("Title"));
+        assert_eq!(parts.source, some("WEB"));
+        assert_eq!(parts.group, some("GROUP"));
+
+        let parts = parser.parse("[SubGroup] Show - 03 [BD][1080p]");
+        assert_eq!(parts.source, some("BD"));
+    }
+
+    #[test]
+    fn rebuilds_with_the_kept_parts() {
+        let parser = ReleaseNameParser::new();
+        let parts = parser.parse("[SubGroup] Show.S01E03.Title.1080p.WEB-DL.x264 [ABCD1234]");
+        let rules = CleanupRules::default();
+
+        assert_eq!(
+            rebuild_release_name(&parts, &rules),
+            some("Show - S01E03 - Title [1080p]")
+        );
+        assert_eq!(
+            dropped_parts(&parts, &rules),
+            ["WEB-DL", "x264", "SubGroup", "ABCD1234"]
+        );
+
+        let everything = CleanupRules {
+            keep: vec![
+                ReleasePart::EpisodeTitle,
+                ReleasePart::Resolution,
+                ReleasePart::Source,
+                ReleasePart::Codec,
+                ReleasePart::Group,
+                ReleasePart::Crc,
+            ],
+            remove_words: vec!["title".to_string()],
+        };
+        assert_eq!(
+            rebuild_release_name(&parts, &everything),
+            some("[SubGroup] Show - S01E03 [1080p WEB-DL x264] [ABCD1234]")
+        );
+    }
+
+    #[test]
+    fn parses_movies() {
+        let parser = ReleaseNameParser::new();
+        assert_eq!(
+            parser.parse_movie("Blade.Runner.1982.The.Final.Cut.1080p.BluRay.x264-GROUP"),
+            Some(MovieName {
+                title: "Blade Runner".to_string(),
+                year: some("1982"),
+                edition: some("Final Cut"),
+            })
+        );
+        assert_eq!(
+            parser.parse_movie("2001 A Space Odyssey (1968) {edition-Director's Cut}"),
+            Some(MovieName {
+                title: "2001 A Space Odyssey".to_string(),
+                year: some("1968"),
+                edition: some("Director's Cut"),
+            })
+        );
+        assert_eq!(parser.parse_movie("Show.S01E01.1080p"), None);
+    }
+}
@@ -0,0 +1,952 @@
+use crate::explorer::filename_policy::{sanitize_file_name, SanitizeProfile};
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+// STRUCTS
+
+#[derive(Debug, Serialize, Clone, PartialEq)]
+pub struct RenameOperation {
+    pub from: PathBuf,
+    pub to: PathBuf,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct RenameFailure {
+    pub path: PathBuf,
+    pub error: String,
+}
+
+// Outcome of executing a plan. If `failed` is set, every rename listed in
+// `rolled_back` has been reverted and nothing else is reported as done.
+#[derive(Debug, Serialize, Clone, Default)]
+pub struct RenameReport {
+    pub renamed: Vec<RenameOperation>,
+    pub created_dirs: Vec<PathBuf>,
+    pub removed_dirs: Vec<PathBuf>,
+    pub deleted: Vec<PathBuf>,
+    pub failed: Option<RenameFailure>,
+    pub rolled_back: Vec<RenameOperation>,
+    pub rollback_errors: Vec<String>,
+    // Problems after the files were renamed, e.g. a journal that could not be written
+    pub warnings: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Clone, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum ConflictKind {
+    // Several files would be renamed to the same name
+    DuplicateTarget,
+    // The target already exists and is not moved away by the plan
+    TargetExists,
+    // The target only differs in case from another target or an existing file
+    CaseCollision,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct RenameConflict {
+    pub kind: ConflictKind,
+    pub target: PathBuf,
+    pub sources: Vec<PathBuf>,
+}
+
+impl std::fmt::Display for RenameConflict {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let reason = match self.kind {
+            ConflictKind::DuplicateTarget => "several files would be renamed to",
+            ConflictKind::TargetExists => "target already exists",
+            ConflictKind::CaseCollision => "target only differs in case from",
+        };
+        write!(f, "{}: {}", reason, self.target.display())
+    }
+}
+
+#[derive(Debug, Serialize, Clone, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum PreviewStatus {
+    Changed,
+    Unchanged,
+    Skipped,
+    Conflict,
+    Deleted,
+}
+
+// One file of a preview, the same shape for every rename operation
+#[derive(Debug, Serialize, Clone)]
+pub struct PreviewEntry {
+    pub original_path: PathBuf,
+    pub original_name: String,
+    pub proposed_name: String,
+    // Differs from the folder of `original_path` when the file is moved
+    pub proposed_path: PathBuf,
+    pub status: PreviewStatus,
+    pub reason: Option<String>,
+    pub notes: Vec<String>,
+    // Spans of `original_name` to highlight, e.g. search matches, in UTF-16 units
+    pub highlights: Vec<(usize, usize)>,
+    // Set for subtitles, .nfo files and thumbnails that follow a video
+    pub companion_of: Option<PathBuf>,
+}
+
+// A step that already happened and has to be reverted on failure
+enum CompletedStep {
+    Moved(PathBuf, PathBuf),
+    CreatedDir(PathBuf),
+    RemovedDir(PathBuf),
+    DeletedFile(PathBuf),
+}
+
+#[derive(Debug)]
+pub enum RenameError {
+    IoError(io::Error),
+    InvalidFilename,
+    SourceMissing(PathBuf),
+    DuplicateSource(PathBuf),
+    Conflicts(Vec<RenameConflict>),
+}
+
+impl From<io::Error> for RenameError {
+    fn from(error: io::Error) -> Self {
+        RenameError::IoError(error)
+    }
+}
+
+impl std::fmt::Display for RenameError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RenameError::IoError(e) => write!(f, "IO Error: {}", e),
+            RenameError::InvalidFilename => write!(f, "Invalid Filename"),
+            RenameError::SourceMissing(path) => {
+                write!(f, "Source file does not exist: {}", path.display())
+            }
+            RenameError::DuplicateSource(path) => {
+                write!(f, "File is renamed more than once: {}", path.display())
+            }
+            RenameError::Conflicts(conflicts) => {
+                let descriptions: Vec<String> = conflicts
+                    .iter()
+                    .map(|conflict| conflict.to_string())
+                    .collect();
+                write!(
+                    f,
+                    "{} rename conflict(s): {}",
+                    conflicts.len(),
+                    descriptions.join("; ")
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for RenameError {}
+
+// RENAME PLAN
+
+#[derive(Debug, Clone, Default)]
+pub struct RenamePlan {
+    operations: Vec<RenameOperation>,
+    create_dirs: Vec<PathBuf>,
+    remove_dirs: Vec<PathBuf>,
+    // Files removed for good, e.g. samples when flattening folders
+    delete_files: Vec<(PathBuf, String)>,
+    // Files that were looked at but stay as they are, only used for previews
+    unchanged: Vec<PathBuf>,
+    skipped: Vec<(PathBuf, String)>,
+    notes: HashMap<PathBuf, Vec<String>>,
+    highlights: HashMap<PathBuf, Vec<(usize, usize)>>,
+    companions: HashMap<PathBuf, PathBuf>,
+}
+
+impl RenamePlan {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // Adds a rename to the plan. Renames that would not change the path are
+    // only listed as unchanged in the preview.
+    pub fn push(&mut self, from: PathBuf, to: PathBuf) {
+        if from != to {
+            self.operations.push(RenameOperation { from, to });
+        } else {
+            self.unchanged.push(from);
+        }
+    }
+
+    // Whether the plan already renames `path` or lists it as unchanged
+    pub fn contains_source(&self, path: &Path) -> bool {
+        self.operations
+            .iter()
+            .any(|operation| operation.from == path)
+            || self.unchanged.iter().any(|unchanged| unchanged == path)
+    }
+
+    // Adds the rename of a file that follows `video`, e.g. its subtitles
+    pub fn push_companion(&mut self, video: &Path, from: PathBuf, to: PathBuf) {
+        self.companions.insert(from.clone(), video.to_path_buf());
+        self.push(from, to);
+    }
+
+    // Lists a file that the operation can not rename, with the reason why
+    pub fn skip(&mut self, path: PathBuf, reason: impl Into<String>) {
+        self.skipped.push((path, reason.into()));
+    }
+
+    // Extra information about a file that is shown in its preview entry
+    pub fn note(&mut self, path: &Path, note: impl Into<String>) {
+        self.notes
+            .entry(path.to_path_buf())
+            .or_default()
+            .push(note.into());
+    }
+
+    // Marks parts of a file name in its preview entry
+    pub fn highlight(&mut self, path: &Path, spans: Vec<(usize, usize)>) {
+        self.highlights
+            .entry(path.to_path_buf())
+            .or_default()
+            .extend(spans);
+    }
+
+    // Merges another plan into this one, e.g. the plan of a subfolder
+    pub fn append(&mut self, other: RenamePlan) {
+        self.operations.extend(other.operations);
+        for dir in other.create_dirs {
+            self.create_dir(dir);
+        }
+        for dir in other.remove_dirs {
+            self.remove_dir(dir);
+        }
+        self.delete_files.extend(other.delete_files);
+        self.unchanged.extend(other.unchanged);
+        self.skipped.extend(other.skipped);
+        for (path, notes) in other.notes {
+            self.notes.entry(path).or_default().extend(notes);
+        }
+        for (path, spans) in other.highlights {
+            self.highlights.entry(path).or_default().extend(spans);
+        }
+        self.companions.extend(other.companions);
+    }
+
+    // Directory that has to exist before the renames run
+    pub fn create_dir(&mut self, dir: PathBuf) {
+        if !self.create_dirs.contains(&dir) {
+            self.create_dirs.push(dir);
+        }
+    }
+
+    // Directory that is removed after the renames ran. Fails the plan if it is not empty.
+    pub fn remove_dir(&mut self, dir: PathBuf) {
+        if !self.remove_dirs.contains(&dir) {
+            self.remove_dirs.push(dir);
+        }
+    }
+
+    // File that is deleted after the renames ran, with the reason shown in the preview.
+    // A target that is deleted can be overwritten by a rename.
+    pub fn delete_file(&mut self, path: PathBuf, reason: impl Into<String>) {
+        if !self
+            .delete_files
+            .iter()
+            .any(|(deleted, _)| *deleted == path)
+        {
+            self.delete_files.push((path, reason.into()));
+        }
+    }
+
+    // Makes every name the plan creates valid for the profile, existing folders stay
+    // as they are. Each change is noted in the preview of the renamed file.
+    pub fn sanitize_targets(&mut self, profile: SanitizeProfile) {
+        // Videos first, so their companions can follow a shortened stem
+        let mut order: Vec<usize> = (0..self.operations.len()).collect();
+        order.sort_by_key(|&index| self.companions.contains_key(&self.operations[index].from));
+
+        let mut video_stems: HashMap<PathBuf, (String, String)> = HashMap::new();
+        let mut targets = vec![PathBuf::new(); self.operations.len()];
+        for index in order {
+            let from = self.operations[index].from.clone();
+            let to = &self.operations[index].to;
+            let mut warnings = Vec::new();
+
+            let parent = to
+                .parent()
+                .map(|parent| sanitize_new_dirs(parent, profile, &mut warnings))
+                .unwrap_or_default();
+            let mut name = to
+                .file_name()
+                .map(|name| name.to_string_lossy().to_string())
+                .unwrap_or_default();
+            if let Some((stem, sanitized_stem)) = self
+                .companions
+                .get(&from)
+                .and_then(|video| video_stems.get(video))
+            {
+                if let Some(suffix) = name.strip_prefix(stem.as_str()) {
+                    name = format!("{}{}", sanitized_stem, suffix);
+                }
+            }
+
+            let (sanitized_name, name_warnings) = sanitize_file_name(&name, profile);
+            warnings.extend(name_warnings);
+            let target = parent.join(&sanitized_name);
+
+            if !self.companions.contains_key(&from) {
+                let stem_of = |path: &Path| {
+                    path.file_stem()
+                        .map(|stem| stem.to_string_lossy().to_string())
+                        .unwrap_or_default()
+                };
+                video_stems.insert(from.clone(), (stem_of(to), stem_of(&target)));
+            }
+            for warning in warnings {
+                self.note(&from, warning);
+            }
+            targets[index] = target;
+        }
+
+        // A name that sanitizes back to the original is not renamed at all
+        let operations = std::mem::take(&mut self.operations);
+        for (operation, target) in operations.into_iter().zip(targets) {
+            self.push(operation.from, target);
+        }
+
+        let mut ignored = Vec::new();
+        self.create_dirs = self
+            .create_dirs
+            .iter()
+            .map(|dir| sanitize_new_dirs(dir, profile, &mut ignored))
+            .collect();
+    }
+
+    // Checks the whole plan against the file system before anything is touched
+    pub fn validate(&self) -> Result<(), RenameError> {
+        let mut sources = HashSet::new();
+
+        for operation in &self.operations {
+            if !operation.from.exists() {
+                return Err(RenameError::SourceMissing(operation.from.clone()));
+            }
+            if !sources.insert(operation.from.clone()) {
+                return Err(RenameError::DuplicateSource(operation.from.clone()));
+            }
+        }
+
+        for (path, _) in &self.delete_files {
+            if !path.is_file() {
+                return Err(RenameError::SourceMissing(path.clone()));
+            }
+            if sources.contains(path) {
+                return Err(RenameError::DuplicateSource(path.clone()));
+            }
+        }
+
+        let conflicts = self.conflicts();
+        if !conflicts.is_empty() {
+            return Err(RenameError::Conflicts(conflicts));
+        }
+
+        for dir in &self.remove_dirs {
+            if !dir.is_dir() {
+                return Err(RenameError::SourceMissing(dir.clone()));
+            }
+        }
+
+        Ok(())
+    }
+
+    // Finds every target that would overwrite or shadow another file.
+    // `fs::rename` silently replaces existing files on Linux, so these must block the plan.
+    pub fn conflicts(&self) -> Vec<RenameConflict> {
+        let mut conflicts = Vec::new();
+        let sources: HashSet<&Path> = self.operations.iter().map(|op| op.from.as_path()).collect();
+        let deleted: HashSet<&Path> = self
+            .delete_files
+            .iter()
+            .map(|(path, _)| path.as_path())
+            .collect();
+
+        // Group the operations by exact target and by case-insensitive target
+        let mut by_target: HashMap<&Path, Vec<&RenameOperation>> = HashMap::new();
+        let mut by_target_key: HashMap<String, Vec<&RenameOperation>> = HashMap::new();
+        for operation in &self.operations {
+            by_target.entry(&operation.to).or_default().push(operation);
+            by_target_key
+                .entry(path_key(&operation.to))
+                .or_default()
+                .push(operation);
+        }
+
+        // Existing directory entries, read once per target directory
+        let mut dir_entries: HashMap<PathBuf, Vec<PathBuf>> = HashMap::new();
+
+        for operation in &self.operations {
+            let target = operation.to.as_path();
+            let same_target = &by_target[&target];
+            if same_target.len() > 1 {
+                // Report each duplicate target once
+                if same_target[0] == operation {
+                    conflicts.push(conflict(ConflictKind::DuplicateTarget, same_target));
+                }
+                continue;
+            }
+
+            let same_key = &by_target_key[&path_key(target)];
+            if same_key.len() > 1 {
+                if same_key[0] == operation {
+                    conflicts.push(conflict(ConflictKind::CaseCollision, same_key));
+                }
+                continue;
+            }
+
+            // On a case-insensitive file system the target of a case-only rename is the
+            // source itself, on a case-sensitive one it can be a different file
+            let is_case_only_rename = path_key(&operation.from) == path_key(target)
+                && is_same_file(&operation.from, target);
+            let is_replaced = deleted.contains(target);
+            if target.exists() && !sources.contains(target) && !is_case_only_rename && !is_replaced
+            {
+                conflicts.push(conflict(ConflictKind::TargetExists, &[operation]));
+                continue;
+            }
+
+            // A file that only differs in case shadows the target on Windows and SMB shares
+            if let Some(parent) = target.parent() {
+                let entries = dir_entries
+                    .entry(parent.to_path_buf())
+                    .or_insert_with(|| list_dir(parent));
+                let is_shadowed = entries.iter().any(|entry| {
+                    entry.as_path() != target
+                        && entry.as_path() != operation.from
+                        && !sources.contains(entry.as_path())
+                        && !deleted.contains(entry.as_path())
+                        && path_key(entry) == path_key(target)
+                });
+                if is_shadowed {
+                    conflicts.push(conflict(ConflictKind::CaseCollision, &[operation]));
+                }
+            }
+        }
+
+        conflicts
+    }
+
+    // Builds one preview entry per file the plan looked at, sorted by path
+    pub fn preview(&self) -> Vec<PreviewEntry> {
+        let conflicts = self.conflicts();
+        let mut entries = Vec::new();
+
+        for operation in &self.operations {
+            let conflict = conflicts
+                .iter()
+                .find(|conflict| conflict.sources.contains(&operation.from));
+            let (status, reason) = match conflict {
+                Some(conflict) => (PreviewStatus::Conflict, Some(conflict.to_string())),
+                None => (PreviewStatus::Changed, None),
+            };
+            entries.push(self.preview_entry(&operation.from, &operation.to, status, reason));
+        }
+        for path in &self.unchanged {
+            entries.push(self.preview_entry(path, path, PreviewStatus::Unchanged, None));
+        }
+        for (path, reason) in &self.delete_files {
+            entries.push(self.preview_entry(
+                path,
+                path,
+                PreviewStatus::Deleted,
+                Some(reason.clone()),
+            ));
+        }
+        for (path, reason) in &self.skipped {
+            entries.push(self.preview_entry(
+                path,
+                path,
+                PreviewStatus::Skipped,
+                Some(reason.clone()),
+            ));
+        }
+
+        // Companion files are listed right under their video
+        entries.sort_by(|a, b| {
+            let group = |entry: &PreviewEntry| {
+                let video = entry.companion_of.as_ref().unwrap_or(&entry.original_path);
+                (video.clone(), entry.companion_of.is_some())
+            };
+            group(a)
+                .cmp(&group(b))
+                .then_with(|| a.original_path.cmp(&b.original_path))
+        });
+        entries
+    }
+
+    fn preview_entry(
+        &self,
+        original: &Path,
+        proposed: &Path,
+        status: PreviewStatus,
+        reason: Option<String>,
+    ) -> PreviewEntry {
+        let file_name = |path: &Path| {
+            path.file_name()
+                .map(|name| name.to_string_lossy().to_string())
+                .unwrap_or_default()
+        };
+
+        PreviewEntry {
+            original_path: original.to_path_buf(),
+            original_name: file_name(original),
+            proposed_name: file_name(proposed),
+            proposed_path: proposed.to_path_buf(),
+            status,
+            reason,
+            notes: self.notes.get(original).cloned().unwrap_or_default(),
+            highlights: self.highlights.get(original).cloned().unwrap_or_default(),
+            companion_of: self.companions.get(original).cloned(),
+        }
+    }
+
+    // Runs the plan as one transaction. Every file is first moved to a temporary
+    // name and then to its target, so chains and swaps (E01 -> E02 -> E03) work in
+    // any order. If a step fails, all completed steps are reverted. Files to delete
+    // are staged as well and only removed once every rename succeeded.
+    pub fn execute(&self) -> RenameReport {
+        let mut report = RenameReport::default();
+        let mut completed_steps = Vec::new();
+        let mut staged_paths = Vec::new();
+
+        // Create missing directories, parents first
+        for dir in &self.create_dirs {
+            if let Err(e) = create_missing_dirs(dir, &mut completed_steps) {
+                return self.fail(dir, e, completed_steps, 0, report);
+            }
+        }
+
+        // Phase 1: move every source out of the way
+        for (started, operation) in self.operations.iter().enumerate() {
+            let staged_path = staging_path(&operation.from);
+            if let Err(e) = fs::rename(&operation.from, &staged_path) {
+                return self.fail(&operation.from, e, completed_steps, started, report);
+            }
+            completed_steps.push(CompletedStep::Moved(
+                operation.from.clone(),
+                staged_path.clone(),
+            ));
+            staged_paths.push(staged_path);
+        }
+
+        let mut staged_deletions = Vec::new();
+        for (path, _) in &self.delete_files {
+            let staged_path = staging_path(path);
+            if let Err(e) = fs::rename(path, &staged_path) {
+                let started = self.operations.len();
+                return self.fail(path, e, completed_steps, started, report);
+            }
+            completed_steps.push(CompletedStep::Moved(path.clone(), staged_path.clone()));
+            staged_deletions.push((path.clone(), staged_path));
+        }
+
+        // Phase 2: move every staged file to its target
+        let started = self.operations.len();
+        for (operation, staged_path) in self.operations.iter().zip(staged_paths) {
+            let result = if operation.to.exists() {
+                Err(io::Error::new(
+                    io::ErrorKind::AlreadyExists,
+                    format!("Target file already exists: {}", operation.to.display()),
+                ))
+            } else {
+                fs::rename(&staged_path, &operation.to)
+            };
+
+            if let Err(e) = result {
+                return self.fail(&operation.from, e, completed_steps, started, report);
+            }
+            completed_steps.push(CompletedStep::Moved(staged_path, operation.to.clone()));
+        }
+
+        // Deleted files can not be restored, so every folder to remove has to hold
+        // nothing but staged deletions and other folders to remove before anything goes
+        let staged: HashSet<&Path> = staged_deletions
+            .iter()
+            .map(|(_, staged_path)| staged_path.as_path())
+            .collect();
+        for dir in &self.remove_dirs {
+            let remaining = list_dir(dir).into_iter().find(|entry| {
+                !staged.contains(entry.as_path()) && !self.remove_dirs.contains(entry)
+            });
+            if let Some(entry) = remaining {
+                let e = io::Error::new(
+                    io::ErrorKind::Other,
+                    format!("Directory is not empty, {} is left", entry.display()),
+                );
+                return self.fail(dir, e, completed_steps, started, report);
+            }
+        }
+
+        // Deleted files can not be restored from here on
+        for (path, staged_path) in staged_deletions {
+            if let Err(e) = fs::remove_file(&staged_path) {
+                return self.fail(&path, e, completed_steps, started, report);
+            }
+            completed_steps.push(CompletedStep::DeletedFile(path));
+        }
+
+        // Remove directories that were emptied by the renames
+        for dir in &self.remove_dirs {
+            if let Err(e) = fs::remove_dir(dir) {
+                return self.fail(dir, e, completed_steps, started, report);
+            }
+            completed_steps.push(CompletedStep::RemovedDir(dir.clone()));
+        }
+
+        for step in completed_steps {
+            match step {
+                CompletedStep::CreatedDir(dir) => report.created_dirs.push(dir),
+                CompletedStep::RemovedDir(dir) => report.removed_dirs.push(dir),
+                CompletedStep::DeletedFile(path) => report.deleted.push(path),
+                CompletedStep::Moved(_, _) => {}
+            }
+        }
+        report.renamed = self.operations.clone();
+        report
+    }
+
+    // Records the failure and reverts every completed step in reverse order
+    fn fail(
+        &self,
+        path: &Path,
+        error: io::Error,
+        completed_steps: Vec<CompletedStep>,
+        started: usize,
+        mut report: RenameReport,
+    ) -> RenameReport {
+        report.failed = Some(RenameFailure {
+            path: path.to_path_buf(),
+            error: error.to_string(),
+        });
+
+        for step in completed_steps.into_iter().rev() {
+            let (result, description) = match &step {
+                CompletedStep::Moved(from, to) => (
+                    fs::rename(to, from),
+                    format!("restore {} from {}", from.display(), to.display()),
+                ),
+                CompletedStep::CreatedDir(dir) => (
+                    fs::remove_dir(dir),
+                    format!("remove directory {}", dir.display()),
+                ),
+                CompletedStep::RemovedDir(dir) => (
+                    fs::create_dir(dir),
+                    format!("recreate directory {}", dir.display()),
+                ),
+                CompletedStep::DeletedFile(path) => (
+                    Err(io::Error::new(
+                        io::ErrorKind::NotFound,
+                        "the file was already deleted",
+                    )),
+                    format!("restore {}", path.display()),
+                ),
+            };
+            if let Err(e) = result {
+                report
+                    .rollback_errors
+                    .push(format!("Failed to {}: {}", description, e));
+            }
+        }
+
+        report.rolled_back = self.operations[..started].to_vec();
+        report
+    }
+}
+
+fn conflict(kind: ConflictKind, operations: &[&RenameOperation]) -> RenameConflict {
+    RenameConflict {
+        kind,
+        target: operations[0].to.clone(),
+        sources: operations.iter().map(|op| op.from.clone()).collect(),
+    }
+}
+
+// Sanitizes the folders of `dir` that do not exist yet, e.g. "Show: Movie" the plan creates
+fn sanitize_new_dirs(dir: &Path, profile: SanitizeProfile, warnings: &mut Vec<String>) -> PathBuf {
+    let existing = dir
+        .ancestors()
+        .find(|ancestor| ancestor.exists())
+        .unwrap_or(Path::new(""));
+    let mut result = existing.to_path_buf();
+
+    if let Ok(missing) = dir.strip_prefix(existing) {
+        for component in missing.components() {
+            let name = component.as_os_str().to_string_lossy();
+            let (sanitized_name, dir_warnings) = sanitize_file_name(&name, profile);
+            warnings.extend(
+                dir_warnings
+                    .into_iter()
+                    .map(|warning| format!("Folder '{}': {}", name, warning)),
+            );
+            result.push(sanitized_name);
+        }
+    }
+
+    result
+}
+
+fn list_dir(dir: &Path) -> Vec<PathBuf> {
+    fs::read_dir(dir)
+        .map(|entries| {
+            entries
+                .filter_map(|entry| entry.ok())
+                .map(|entry| entry.path())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+// Creates `dir` and every missing parent, recording each one that was created
+fn create_missing_dirs(dir: &Path, completed_steps: &mut Vec<CompletedStep>) -> io::Result<()> {
+    let missing: Vec<&Path> = dir.ancestors().take_while(|path| !path.exists()).collect();
+
+    for path in missing.into_iter().rev() {
+        fs::create_dir(path)?;
+        completed_steps.push(CompletedStep::CreatedDir(path.to_path_buf()));
+    }
+
+    Ok(())
+}
+
+// Temporary name next to the source file, used while the plan is running. It does not
+// contain the original name, so long names can not grow past the file system limit.
+fn staging_path(path: &Path) -> PathBuf {
+    path.with_file_name(format!(".sk-{}", uuid::Uuid::new_v4().simple()))
+}
+
+// True if both paths lead to the same file, e.g. "Show.mkv" and "show.mkv" on Windows
+#[cfg(unix)]
+fn is_same_file(a: &Path, b: &Path) -> bool {
+    use std::os::unix::fs::MetadataExt;
+
+    match (fs::metadata(a), fs::metadata(b)) {
+        (Ok(a), Ok(b)) => a.dev() == b.dev() && a.ino() == b.ino(),
+        _ => false,
+    }
+}
+
+#[cfg(not(unix))]
+fn is_same_file(a: &Path, b: &Path) -> bool {
+    match (fs::canonicalize(a), fs::canonicalize(b)) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => false,
+    }
+}
+
+// Case-insensitive key, so a case-only rename on Windows is not taken for a collision
+fn path_key(path: &Path) -> String {
+    path.to_string_lossy().to_lowercase()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::explorer::test_support::TestDir;
+
+    fn plan(renames: &[(&Path, &Path)]) -> RenamePlan {
+        let mut plan = RenamePlan::new();
+        for (from, to) in renames {
+            plan.push(from.to_path_buf(), to.to_path_buf());
+        }
+        plan
+    }
+
+    #[test]
+    fn swaps_two_files() {
+        let dir = TestDir::new();
+        let a = dir.file("E01.mkv");
+        let b = dir.file("E02.mkv");
+
+        let plan = plan(&[(&a, &b), (&b, &a)]);
+        plan.validate().unwrap();
+        let report = plan.execute();
+
+        assert!(report.failed.is_none());
+        assert_eq!(dir.read("E01.mkv"), "E02.mkv");
+        assert_eq!(dir.read("E02.mkv"), "E01.mkv");
+        assert_eq!(dir.names(), ["E01.mkv", "E02.mkv"]);
+    }
+
+    #[test]
+    fn runs_a_chain_in_any_order() {
+        let dir = TestDir::new();
+        let e1 = dir.file("E01.mkv");
+        let e2 = dir.file("E02.mkv");
+        let e3 = dir.join("E03.mkv");
+
+        let plan = plan(&[(&e1, &e2), (&e2, &e3)]);
+        plan.validate().unwrap();
+        assert!(plan.execute().failed.is_none());
+        assert_eq!(dir.read("E02.mkv"), "E01.mkv");
+        assert_eq!(dir.read("E03.mkv"), "E02.mkv");
+        assert_eq!(dir.names(), ["E02.mkv", "E03.mkv"]);
+    }
+
+    #[test]
+    fn rejects_duplicate_targets() {
+        let dir = TestDir::new();
+        let a = dir.file("a.mkv");
+        let b = dir.file("b.mkv");
+        let target = dir.join("c.mkv");
+
+        match plan(&[(&a, &target), (&b, &target)]).validate() {
+            Err(RenameError::Conflicts(conflicts)) => {
+                assert_eq!(conflicts.len(), 1);
+                assert_eq!(conflicts[0].kind, ConflictKind::DuplicateTarget);
+            }
+            other => panic!("unexpected result: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn rejects_existing_targets() {
+        let dir = TestDir::new();
+        let a = dir.file("a.mkv");
+        let b = dir.file("b.mkv");
+
+        match plan(&[(&a, &b)]).validate() {
+            Err(RenameError::Conflicts(conflicts)) => {
+                assert_eq!(conflicts[0].kind, ConflictKind::TargetExists);
+            }
+            other => panic!("unexpected result: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn rejects_case_collisions() {
+        let dir = TestDir::new();
+        let a = dir.file("a.mkv");
+        let b = dir.file("b.mkv");
+
+        match plan(&[(&a, &dir.join("Show.mkv")), (&b, &dir.join("show.mkv"))]).validate() {
+            Err(RenameError::Conflicts(conflicts)) => {
+                assert_eq!(conflicts[0].kind, ConflictKind::CaseCollision);
+            }
+            other => panic!("unexpected result: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn allows_case_only_renames() {
+        let dir = TestDir::new();
+        let a = dir.file("show.mkv");
+
+        let plan = plan(&[(&a, &dir.join("Show.mkv"))]);
+        plan.validate().unwrap();
+        assert!(plan.execute().failed.is_none());
+        assert_eq!(dir.names(), ["Show.mkv"]);
+    }
+
+    #[test]
+    fn rejects_existing_targets_that_only_differ_in_case() {
+        let dir = TestDir::new();
+        let a = dir.file("Show S01E02.mkv");
+        let b = dir.join("show s01e02.mkv");
+        // Both names only exist as separate files on a case-sensitive file system
+        if b.exists() {
+            return;
+        }
+        dir.file("show s01e02.mkv");
+
+        let plan = plan(&[(&a, &b)]);
+        assert_eq!(plan.preview()[0].status, PreviewStatus::Conflict);
+        match plan.validate() {
+            Err(RenameError::Conflicts(conflicts)) => {
+                assert_eq!(conflicts[0].kind, ConflictKind::TargetExists);
+            }
+            other => panic!("unexpected result: {:?}", other),
+        }
+        assert_eq!(dir.read("show s01e02.mkv"), "show s01e02.mkv");
+    }
+
+    #[test]
+    fn rejects_missing_and_duplicate_sources() {
+        let dir = TestDir::new();
+        let a = dir.file("a.mkv");
+        let missing = dir.join("missing.mkv");
+
+        assert!(matches!(
+            plan(&[(&missing, &dir.join("b.mkv"))]).validate(),
+            Err(RenameError::SourceMissing(_))
+        ));
+        assert!(matches!(
+            plan(&[(&a, &dir.join("b.mkv")), (&a, &dir.join("c.mkv"))]).validate(),
+            Err(RenameError::DuplicateSource(_))
+        ));
+    }
+
+    #[test]
+    fn rolls_back_when_a_target_appears() {
+        let dir = TestDir::new();
+        let a = dir.file("a.mkv");
+        let b = dir.file("b.mkv");
+        let plan = plan(&[(&a, &dir.join("x.mkv")), (&b, &dir.join("y.mkv"))]);
+        plan.validate().unwrap();
+
+        // Created after validating, e.g. by another program
+        dir.file_with("y.mkv", "other");
+        let report = plan.execute();
+
+        assert!(report.failed.is_some());
+        assert_eq!(report.rolled_back.len(), 2);
+        assert!(report.rollback_errors.is_empty());
+        assert_eq!(dir.names(), ["a.mkv", "b.mkv", "y.mkv"]);
+        assert_eq!(dir.read("y.mkv"), "other");
+    }
+
+    #[test]
+    fn keeps_deletions_when_a_folder_can_not_be_removed() {
+        let dir = TestDir::new();
+        let video = dir.file("Show/Show.mkv");
+        let sample = dir.file("Show/sample.mkv");
+        dir.file("Show/notes.txt");
+
+        let mut plan = plan(&[(&video, &dir.join("Show.mkv"))]);
+        plan.delete_file(sample, "Sample");
+        plan.remove_dir(dir.join("Show"));
+        plan.validate().unwrap();
+        let report = plan.execute();
+
+        assert!(report.failed.is_some());
+        assert!(report.deleted.is_empty());
+        assert_eq!(dir.read("Show/sample.mkv"), "Show/sample.mkv");
+        assert_eq!(dir.read("Show/Show.mkv"), "Show/Show.mkv");
+        assert_eq!(dir.names(), ["Show"]);
+    }
+
+    #[test]
+    fn deletes_files_and_removes_emptied_folders() {
+        let dir = TestDir::new();
+        let video = dir.file("Show/Show.mkv");
+        let sample = dir.file("Show/sample.mkv");
+
+        let mut plan = plan(&[(&video, &dir.join("Show.mkv"))]);
+        plan.delete_file(sample.clone(), "Sample");
+        plan.remove_dir(dir.join("Show"));
+        plan.validate().unwrap();
+        let report = plan.execute();
+
+        assert!(report.failed.is_none());
+        assert_eq!(report.deleted, [sample]);
+        assert_eq!(report.removed_dirs, [dir.join("Show")]);
+        assert_eq!(dir.names(), ["Show.mkv"]);
+    }
+
+    #[test]
+    fn renames_files_with_long_names() {
+        let dir = TestDir::new();
+        let name = format!("{}.mkv", "a".repeat(240));
+        let from = dir.file(&name);
+
+        let plan = plan(&[(&from, &dir.join("b.mkv"))]);
+        plan.validate().unwrap();
+        assert!(plan.execute().failed.is_none());
+        assert_eq!(dir.names(), ["b.mkv"]);
+    }
+}
@@ -0,0 +1,202 @@
+use once_cell::sync::Lazy;
+use regex::{NoExpand, Regex, RegexBuilder};
+use serde::Deserialize;
+
+// STRUCTS
+
+// How `search_and_replace` matches. Without options it replaces every literal match
+// in the whole file name of video files, like it always did.
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+#[serde(default)]
+pub struct SearchOptions {
+    pub regex: bool, // `$1` and `${name}` in the replacement refer to groups
+    pub case_insensitive: bool,
+    pub first_match_only: bool,
+    pub include_extension: bool,
+    pub all_files: bool, // Not only video files
+}
+
+impl Default for SearchOptions {
+    fn default() -> Self {
+        Self {
+            regex: false,
+            case_insensitive: false,
+            first_match_only: false,
+            include_extension: true,
+            all_files: false,
+        }
+    }
+}
+
+// A checked search pattern with its replacement
+pub struct FileNameSearch {
+    pattern: Regex,
+    replacement: String,
+    options: SearchOptions,
+}
+
+impl FileNameSearch {
+    pub fn new(target: &str, replacement: &str, options: SearchOptions) -> Result<Self, String> {
+        if target.is_empty() {
+            return Err("Target string cannot be empty.".to_string());
+        }
+
+        let source = if options.regex {
+            target.to_string()
+        } else {
+            regex::escape(target)
+        };
+        let pattern = RegexBuilder::new(&source)
+            .case_insensitive(options.case_insensitive)
+            .build()
+            .map_err(|e| format!("Invalid pattern: {}", e))?;
+
+        if options.regex {
+            check_group_references(&pattern, replacement)?;
+        }
+
+        Ok(Self {
+            pattern,
+            replacement: replacement.to_string(),
+            options,
+        })
+    }
+
+    // Whether a file is searched at all
+    pub fn searches(&self, is_video: bool) -> bool {
+        self.options.all_files || is_video
+    }
+
+    // The new file name and the spans of the matches in the old one.
+    // Spans count UTF-16 units, the way the frontend indexes strings.
+    pub fn replace(&self, file_name: &str) -> (String, Vec<(usize, usize)>) {
+        // The extension stays untouched unless it is included
+        let searched_len = match (self.options.include_extension, file_name.rfind('.')) {
+            (false, Some(dot)) if dot > 0 => dot,
+            _ => file_name.len(),
+        };
+        let (searched, extension) = file_name.split_at(searched_len);
+
+        // A limit of 0 replaces every match
+        let (limit, max_spans) = if self.options.first_match_only {
+            (1, 1)
+        } else {
+            (0, usize::MAX)
+        };
+        let utf16_offset = |byte: usize| searched[..byte].encode_utf16().count();
+        let spans = self
+            .pattern
+            .find_iter(searched)
+            .take(max_spans)
+            .map(|found| (utf16_offset(found.start()), utf16_offset(found.end())))
+            .collect();
+
+        let replaced = if self.options.regex {
+            self.pattern
+                .replacen(searched, limit, self.replacement.as_str())
+        } else {
+            self.pattern
+                .replacen(searched, limit, NoExpand(&self.replacement))
+        };
+
+        (format!("{}{}", replaced, extension), spans)
+    }
+}
+
+// "$1", "${name}" or "$$" in a replacement
+static GROUP_REFERENCE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"\$(?:\$|\{([^}]*)\}|([0-9A-Za-z_]+))").unwrap());
+
+// The regex crate replaces unknown groups with nothing, report them instead
+fn check_group_references(pattern: &Regex, replacement: &str) -> Result<(), String> {
+    let group_count = pattern.captures_len() - 1;
+
+    for caps in GROUP_REFERENCE.captures_iter(replacement) {
+        let name = match caps.get(1).or_else(|| caps.get(2)) {
+            Some(name) => name.as_str(),
+            None => continue, // "$$" is a literal dollar sign
+        };
+
+        let exists = match name.parse::<usize>() {
+            Ok(index) => index <= group_count,
+            Err(_) => pattern.capture_names().any(|group| group == Some(name)),
+        };
+        if !exists {
+            let position = caps.get(0).unwrap().start();
+            return Err(match name.parse::<usize>() {
+                Ok(_) => format!(
+                    "Invalid replacement: ${} at position {} refers to a missing group, the pattern has {} group(s)",
+                    name, position, group_count
+                ),
+                Err(_) => format!(
+                    "Invalid replacement: ${{{}}} at position {} refers to a missing group name",
+                    name, position
+                ),
+            });
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn search(target: &str, replacement: &str, options: SearchOptions) -> FileNameSearch {
+        FileNameSearch::new(target, replacement, options).unwrap()
+    }
+
+    #[test]
+    fn replaces_text_literally() {
+        let search = search("a.b", "$1", SearchOptions::default());
+        assert_eq!(
+            search.replace("a.b axb a.b.mkv"),
+            ("$1 axb $1.mkv".to_string(), vec![(0, 3), (8, 11)])
+        );
+    }
+
+    #[test]
+    fn replaces_with_regex_groups() {
+        let options = SearchOptions {
+            regex: true,
+            ..SearchOptions::default()
+        };
+        let search = search(r"S(?P<season>\d+)E(\d+)", "${season}x$2", options);
+        assert_eq!(search.replace("Show S01E02.mkv").0, "Show 01x02.mkv");
+    }
+
+    #[test]
+    fn applies_the_match_options() {
+        let options = SearchOptions {
+            case_insensitive: true,
+            first_match_only: true,
+            include_extension: false,
+            ..SearchOptions::default()
+        };
+        let search = search("MKV", "x", options);
+        assert_eq!(
+            search.replace("mkv mkv.mkv"),
+            ("x mkv.mkv".to_string(), vec![(0, 3)])
+        );
+        assert!(!search.searches(false));
+    }
+
+    #[test]
+    fn counts_spans_in_utf16_units() {
+        let search = search("b", "c", SearchOptions::default());
+        assert_eq!(search.replace("ä😀b").1, [(3, 4)]);
+    }
+
+    #[test]
+    fn rejects_missing_groups() {
+        let options = SearchOptions {
+            regex: true,
+            ..SearchOptions::default()
+        };
+        assert!(FileNameSearch::new(r"(\d+)", "$2", options.clone()).is_err());
+        assert!(FileNameSearch::new(r"(\d+)", "${name}", options.clone()).is_err());
+        assert!(FileNameSearch::new(r"(\d+)", "$$1", options).is_ok());
+        assert!(FileNameSearch::new("", "x", SearchOptions::default()).is_err());
+    }
+}
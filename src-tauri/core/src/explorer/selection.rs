@@ -0,0 +1,167 @@
+use regex::{Regex, RegexBuilder};
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+
+// STRUCTS
+
+// Which files an operation touches. Without options it takes every file in the folder,
+// like it always did. Globs match the file name, `*.mkv` or `*S01E0[1-5]*`.
+#[derive(Debug, Deserialize, Clone, Default, PartialEq)]
+#[serde(default)]
+pub struct SelectionOptions {
+    pub paths: Vec<PathBuf>, // A selected folder selects everything in it
+    pub include: Vec<String>,
+    pub exclude: Vec<String>,
+}
+
+// A checked selection, the globs compiled once
+pub struct FileSelection {
+    paths: Vec<PathBuf>,
+    include: Vec<Regex>,
+    exclude: Vec<Regex>,
+}
+
+impl FileSelection {
+    // Every file is selected
+    pub fn all() -> Self {
+        Self {
+            paths: vec![],
+            include: vec![],
+            exclude: vec![],
+        }
+    }
+
+    pub fn new(options: SelectionOptions) -> Result<Self, String> {
+        Ok(Self {
+            paths: options.paths,
+            include: compile_globs(&options.include)?,
+            exclude: compile_globs(&options.exclude)?,
+        })
+    }
+
+    // Commands take the selection as an optional argument
+    pub fn from_options(options: Option<SelectionOptions>) -> Result<Self, String> {
+        options.map_or(Ok(Self::all()), Self::new)
+    }
+
+    pub fn contains(&self, path: &Path) -> bool {
+        if !self.paths.is_empty() && !path.ancestors().any(|p| self.paths.iter().any(|s| s == p)) {
+            return false;
+        }
+
+        let name = match path.file_name() {
+            Some(name) => name.to_string_lossy(),
+            None => return false,
+        };
+        (self.include.is_empty() || self.include.iter().any(|glob| glob.is_match(&name)))
+            && !self.exclude.iter().any(|glob| glob.is_match(&name))
+    }
+}
+
+// START GLOBS
+
+fn compile_globs(globs: &[String]) -> Result<Vec<Regex>, String> {
+    globs
+        .iter()
+        .map(|glob| glob.trim())
+        .filter(|glob| !glob.is_empty())
+        .map(compile_glob)
+        .collect()
+}
+
+// `*` any text, `?` one character, `[abc]` and `[!abc]` character sets, `{a,b}` alternatives.
+// Case is ignored, as on the file systems most libraries live on.
+fn compile_glob(glob: &str) -> Result<Regex, String> {
+    let mut source = String::from("^");
+    let mut chars = glob.chars().peekable();
+    let mut alternatives = 0;
+
+    while let Some(c) = chars.next() {
+        match c {
+            '*' => source.push_str(".*"),
+            '?' => source.push('.'),
+            '[' => {
+                let mut set = String::new();
+                if chars.peek() == Some(&'!') {
+                    chars.next();
+                    set.push('^');
+                }
+                let mut closed = false;
+                for c in chars.by_ref() {
+                    if c == ']' {
+                        closed = true;
+                        break;
+                    }
+                    if c == '\\' || c == '[' || c == '^' {
+                        set.push('\\');
+                    }
+                    set.push(c);
+                }
+                if !closed {
+                    return Err(format!("Invalid glob \"{}\": missing ]", glob));
+                }
+                source.push('[');
+                source.push_str(&set);
+                source.push(']');
+            }
+            '{' => {
+                alternatives += 1;
+                source.push_str("(?:");
+            }
+            ',' if alternatives > 0 => source.push('|'),
+            '}' if alternatives > 0 => {
+                alternatives -= 1;
+                source.push(')');
+            }
+            _ => source.push_str(&regex::escape(&c.to_string())),
+        }
+    }
+    if alternatives > 0 {
+        return Err(format!("Invalid glob \"{}\": missing }}", glob));
+    }
+    source.push('$');
+
+    RegexBuilder::new(&source)
+        .case_insensitive(true)
+        .build()
+        .map_err(|e| format!("Invalid glob \"{}\": {}", glob, e))
+}
+
+// END GLOBS
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn matches(glob: &str, name: &str) -> bool {
+        compile_glob(glob).unwrap().is_match(name)
+    }
+
+    #[test]
+    fn matches_whole_names() {
+        assert!(matches("*.mkv", "Show.S01E01.MKV"));
+        assert!(!matches("*.mkv", "Show.S01E01.mkv.part"));
+        assert!(!matches("S01", "Show.S01E01.mkv"));
+        assert!(matches("Show.S01E0?.mkv", "Show.S01E05.mkv"));
+        assert!(!matches("Show.S01E0?.mkv", "Show.S01E10.mkv"));
+        assert!(matches("a+b (1).mkv", "A+B (1).mkv"));
+    }
+
+    #[test]
+    fn matches_sets_and_alternatives() {
+        assert!(matches("*E0[1-5]*", "Show.S01E03.mkv"));
+        assert!(!matches("*E0[1-5]*", "Show.S01E07.mkv"));
+        assert!(matches("*E0[!1-5]*", "Show.S01E07.mkv"));
+        assert!(!matches("*E0[!1-5]*", "Show.S01E03.mkv"));
+        assert!(matches("*.{srt,ass}", "Show.S01E01.ass"));
+        assert!(!matches("*.{srt,ass}", "Show.S01E01.mkv"));
+        assert!(matches("a,b.mkv", "a,b.mkv"));
+    }
+
+    #[test]
+    fn rejects_unclosed_globs() {
+        assert!(compile_glob("*E0[1-5").is_err());
+        assert!(compile_glob("*.{srt,ass").is_err());
+        assert!(compile_globs(&[" ".to_string()]).unwrap().is_empty());
+    }
+}
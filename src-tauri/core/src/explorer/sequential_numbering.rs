@@ -0,0 +1,313 @@
+use crate::explorer::filename_policy::replace_path_separators;
+use serde::Deserialize;
+use std::cmp::Ordering;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+// STRUCTS
+
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum FileOrder {
+    Natural,  // "Track 2" before "Track 10"
+    Modified, // Oldest first
+    Size,     // Smallest first
+    Custom,   // The order of `custom_order`
+}
+
+// Numbers files without an episode tag, e.g. "Track 01.mkv" -> "Track 01 - S01E01.mkv"
+#[derive(Debug, Deserialize, Clone)]
+#[serde(default)]
+pub struct SequentialNumbering {
+    pub season: i32, // Used when the folder name has no season
+    pub start: i32,
+    pub step: i32,
+    pub padding: usize, // Digits of the episode number
+    pub order: FileOrder,
+    pub custom_order: Vec<String>, // File names, files not listed follow in natural order
+    pub show_name: Option<String>, // Replaces the old name, "Show - S01E01.mkv"
+}
+
+impl Default for SequentialNumbering {
+    fn default() -> Self {
+        Self {
+            season: 1,
+            start: 1,
+            step: 1,
+            padding: 2,
+            order: FileOrder::Natural,
+            custom_order: vec![],
+            show_name: None,
+        }
+    }
+}
+
+impl SequentialNumbering {
+    pub fn check(&self) -> Result<(), String> {
+        if self.season < 0 || self.start < 0 {
+            return Err("Season and start number cannot be negative.".to_string());
+        }
+        if self.step < 1 {
+            return Err("The step has to be at least 1.".to_string());
+        }
+        if !(1..=4).contains(&self.padding) {
+            return Err("The padding has to be between 1 and 4 digits.".to_string());
+        }
+        Ok(())
+    }
+
+    // Episode number of the file at `index` in the sorted list, None past i32::MAX
+    pub fn episode_at(&self, index: usize) -> Option<i32> {
+        i32::try_from(index)
+            .ok()?
+            .checked_mul(self.step)?
+            .checked_add(self.start)
+    }
+
+    // "Track 01 - S01E05.mkv", or "Show - S01E05.mkv" with a show name
+    pub fn file_name(&self, path: &Path, season: i32, episode: i32) -> String {
+        let stem = path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let base = match self.show_name.as_deref().map(str::trim) {
+            // "AC/DC" must not turn the rename into a move
+            Some(show_name) if !show_name.is_empty() => replace_path_separators(show_name),
+            _ => stem,
+        };
+        let tag = format!(
+            "S{:02}E{:0padding$}",
+            season,
+            episode,
+            padding = self.padding
+        );
+
+        match path.extension() {
+            Some(extension) => format!("{} - {}.{}", base, tag, extension.to_string_lossy()),
+            None => format!("{} - {}", base, tag),
+        }
+    }
+}
+
+// START SORTING
+
+// Sorts the files in place, ties are broken by natural order
+pub fn sort_files(
+    files: &mut [PathBuf],
+    order: FileOrder,
+    custom_order: &[String],
+) -> io::Result<()> {
+    let name_of = |path: &Path| {
+        path.file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default()
+    };
+
+    match order {
+        FileOrder::Natural => {
+            files.sort_by(|a, b| natural_cmp(&name_of(a), &name_of(b)));
+        }
+        FileOrder::Modified | FileOrder::Size => {
+            let mut keyed = Vec::new();
+            for path in files.iter() {
+                let metadata = fs::metadata(path)?;
+                let key = if order == FileOrder::Size {
+                    metadata.len() as u128
+                } else {
+                    metadata
+                        .modified()?
+                        .duration_since(std::time::UNIX_EPOCH)
+                        .map(|duration| duration.as_nanos())
+                        .unwrap_or(0)
+                };
+                keyed.push((key, path.clone()));
+            }
+            keyed.sort_by(|(a_key, a), (b_key, b)| {
+                a_key
+                    .cmp(b_key)
+                    .then_with(|| natural_cmp(&name_of(a), &name_of(b)))
+            });
+            for (slot, (_, path)) in files.iter_mut().zip(keyed) {
+                *slot = path;
+            }
+        }
+        FileOrder::Custom => {
+            let position = |path: &Path| {
+                let name = name_of(path);
+                custom_order
+                    .iter()
+                    .position(|listed| *listed == name)
+                    .unwrap_or(usize::MAX)
+            };
+            files.sort_by(|a, b| {
+                position(a)
+                    .cmp(&position(b))
+                    .then_with(|| natural_cmp(&name_of(a), &name_of(b)))
+            });
+        }
+    }
+
+    Ok(())
+}
+
+// Compares runs of digits by their value and everything else ignoring case,
+// so "VTS_01_2" comes before "VTS_01_10"
+pub fn natural_cmp(a: &str, b: &str) -> Ordering {
+    let (a_chunks, b_chunks) = (chunks(a), chunks(b));
+
+    for (a_chunk, b_chunk) in a_chunks.iter().zip(&b_chunks) {
+        let a_is_number = a_chunk.starts_with(|c: char| c.is_ascii_digit());
+        let b_is_number = b_chunk.starts_with(|c: char| c.is_ascii_digit());
+
+        let ordering = if a_is_number && b_is_number {
+            let a_digits = a_chunk.trim_start_matches('0');
+            let b_digits = b_chunk.trim_start_matches('0');
+            a_digits
+                .len()
+                .cmp(&b_digits.len())
+                .then_with(|| a_digits.cmp(b_digits))
+        } else {
+            a_chunk.to_lowercase().cmp(&b_chunk.to_lowercase())
+        };
+        if ordering != Ordering::Equal {
+            return ordering;
+        }
+    }
+
+    a_chunks.len().cmp(&b_chunks.len()).then_with(|| a.cmp(b))
+}
+
+// "Track 10.mkv" -> ["Track ", "10", ".mkv"]
+fn chunks(text: &str) -> Vec<&str> {
+    let mut chunks = Vec::new();
+    let mut start = 0;
+    let mut previous_is_digit = None;
+
+    for (index, c) in text.char_indices() {
+        let is_digit = c.is_ascii_digit();
+        if previous_is_digit.map_or(false, |previous| previous != is_digit) {
+            chunks.push(&text[start..index]);
+            start = index;
+        }
+        previous_is_digit = Some(is_digit);
+    }
+    if start < text.len() {
+        chunks.push(&text[start..]);
+    }
+
+    chunks
+}
+
+// END SORTING
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::explorer::test_support::TestDir;
+
+    fn names(files: &[PathBuf]) -> Vec<String> {
+        files
+            .iter()
+            .map(|path| path.file_name().unwrap().to_string_lossy().to_string())
+            .collect()
+    }
+
+    #[test]
+    fn compares_numbers_by_value() {
+        let mut names = vec!["VTS_01_10", "vts_01_2", "VTS_01_002b", "VTS_01_1"];
+        names.sort_by(|a, b| natural_cmp(a, b));
+        assert_eq!(names, ["VTS_01_1", "vts_01_2", "VTS_01_002b", "VTS_01_10"]);
+    }
+
+    #[test]
+    fn names_files_with_the_episode_tag() {
+        let numbering = SequentialNumbering {
+            start: 5,
+            step: 2,
+            padding: 3,
+            ..SequentialNumbering::default()
+        };
+        assert_eq!(numbering.episode_at(2), Some(9));
+        assert_eq!(
+            numbering.file_name(Path::new("Track 1.mkv"), 1, 9),
+            "Track 1 - S01E009.mkv"
+        );
+
+        let numbering = SequentialNumbering {
+            show_name: Some(" Show ".to_string()),
+            ..SequentialNumbering::default()
+        };
+        assert_eq!(
+            numbering.file_name(Path::new("Track 1.mkv"), 2, 1),
+            "Show - S02E01.mkv"
+        );
+
+        let numbering = SequentialNumbering {
+            show_name: Some("../AC/DC".to_string()),
+            ..SequentialNumbering::default()
+        };
+        assert_eq!(
+            numbering.file_name(Path::new("Track 1.mkv"), 1, 1),
+            "..-AC-DC - S01E01.mkv"
+        );
+    }
+
+    #[test]
+    fn refuses_episode_numbers_past_the_maximum() {
+        let numbering = SequentialNumbering {
+            step: i32::MAX,
+            ..SequentialNumbering::default()
+        };
+        assert_eq!(numbering.episode_at(0), Some(1));
+        assert_eq!(numbering.episode_at(1), None);
+        assert_eq!(numbering.episode_at(2), None);
+    }
+
+    #[test]
+    fn checks_the_options() {
+        let check = |numbering: SequentialNumbering| numbering.check().is_ok();
+        assert!(check(SequentialNumbering::default()));
+        assert!(!check(SequentialNumbering {
+            step: 0,
+            ..SequentialNumbering::default()
+        }));
+        assert!(!check(SequentialNumbering {
+            padding: 5,
+            ..SequentialNumbering::default()
+        }));
+        assert!(!check(SequentialNumbering {
+            start: -1,
+            ..SequentialNumbering::default()
+        }));
+    }
+
+    #[test]
+    fn sorts_files_by_size_and_custom_order() {
+        let dir = TestDir::new();
+        let mut files = vec![
+            dir.file_with("Track 10.mkv", "a"),
+            dir.file_with("Track 2.mkv", "aaa"),
+            dir.file_with("Track 1.mkv", "aa"),
+        ];
+
+        sort_files(&mut files, FileOrder::Natural, &[]).unwrap();
+        assert_eq!(
+            names(&files),
+            ["Track 1.mkv", "Track 2.mkv", "Track 10.mkv"]
+        );
+
+        sort_files(&mut files, FileOrder::Size, &[]).unwrap();
+        assert_eq!(
+            names(&files),
+            ["Track 10.mkv", "Track 1.mkv", "Track 2.mkv"]
+        );
+
+        let custom_order = vec!["Track 2.mkv".to_string()];
+        sort_files(&mut files, FileOrder::Custom, &custom_order).unwrap();
+        assert_eq!(
+            names(&files),
+            ["Track 2.mkv", "Track 1.mkv", "Track 10.mkv"]
+        );
+    }
+}
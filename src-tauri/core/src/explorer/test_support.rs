@@ -0,0 +1,51 @@
+use std::fs;
+use std::path::PathBuf;
+
+// A folder below the system temp folder, removed again when the test ends
+pub struct TestDir {
+    path: PathBuf,
+}
+
+impl TestDir {
+    pub fn new() -> Self {
+        let path = std::env::temp_dir().join(format!("sk-test-{}", uuid::Uuid::new_v4().simple()));
+        fs::create_dir_all(&path).unwrap();
+        Self { path }
+    }
+
+    pub fn join(&self, name: &str) -> PathBuf {
+        self.path.join(name)
+    }
+
+    // Creates the file and its folders, the content is the name unless given
+    pub fn file(&self, name: &str) -> PathBuf {
+        self.file_with(name, name)
+    }
+
+    pub fn file_with(&self, name: &str, content: &str) -> PathBuf {
+        let path = self.path.join(name);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(&path, content).unwrap();
+        path
+    }
+
+    pub fn read(&self, name: &str) -> String {
+        fs::read_to_string(self.path.join(name)).unwrap()
+    }
+
+    // File names in the folder, sorted
+    pub fn names(&self) -> Vec<String> {
+        let mut names: Vec<String> = fs::read_dir(&self.path)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().to_string_lossy().to_string())
+            .collect();
+        names.sort();
+        names
+    }
+}
+
+impl Drop for TestDir {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.path);
+    }
+}
@@ -8,7 +8,7 @@ export default function ErrorMessage({ message }: { message: string }) {
                 <div className='flex items-center'>
                     <BsExclamationCircleFill className="h-5 w-5 align-sub inline mr-2" />
                 </div>
-                <div className='flex flex-grow'>
+                <div className='flex flex-grow whitespace-pre-wrap'>
                     {message}
                 </div>
             </div>
@@ -0,0 +1,83 @@
+"use client";
+
+import { useEffect, useRef, useState } from 'react';
+import { emit, listen } from '@tauri-apps/api/event';
+import { FileSelection } from '@/services/tauriService';
+
+type FileSelectionInputProps = {
+    id: string;
+    onChange: (selection: FileSelection) => void;
+};
+
+// Several globs are separated by ";", e.g. "*.mkv; *.mp4"
+function splitGlobs(value: string): string[] {
+    return value.split(';').map((glob) => glob.trim()).filter((glob) => glob !== '');
+}
+
+// Limits an operation to the files checked in the explorer and to names matching the globs
+export default function FileSelectionInput({ id, onChange }: FileSelectionInputProps) {
+    const [paths, setPaths] = useState<string[]>([]);
+    const [include, setInclude] = useState<string>('');
+    const [exclude, setExclude] = useState<string>('');
+    const lastSelection = useRef<string>('{}');
+
+    useEffect(() => {
+        const unlisten = listen<string[]>('file-selection-changed', (event) => {
+            setPaths(event.payload);
+        });
+        // The explorer answers with the files checked before this tool was opened
+        unlisten.then(() => emit('file-selection-requested'));
+
+        return () => {
+            unlisten.then((fn) => fn());
+        };
+    }, []);
+
+    useEffect(() => {
+        const selection: FileSelection = {};
+        if (paths.length > 0) selection.paths = paths;
+        if (splitGlobs(include).length > 0) selection.include = splitGlobs(include);
+        if (splitGlobs(exclude).length > 0) selection.exclude = splitGlobs(exclude);
+
+        // Only a real change builds the preview again
+        const serialized = JSON.stringify(selection);
+        if (serialized !== lastSelection.current) {
+            lastSelection.current = serialized;
+            onChange(selection);
+        }
+    }, [paths, include, exclude]);
+
+    return (
+        <div className="flex flex-col gap-2">
+            <div className="flex flex-row items-center gap-2">
+                <label htmlFor={`${id}-include`} className="w-20">Nur</label>
+                <input
+                    id={`${id}-include`}
+                    type="text"
+                    value={include}
+                    onChange={(e) => setInclude(e.target.value)}
+                    placeholder="z.B. *S01E0[1-5]*; *.mkv"
+                    title="Nur Dateien, deren Name passt. * beliebiger Text, ? ein Zeichen, mehrere mit ; trennen"
+                    className="border rounded px-2 py-1 flex-grow"
+                />
+            </div>
+            <div className="flex flex-row items-center gap-2">
+                <label htmlFor={`${id}-exclude`} className="w-20">Außer</label>
+                <input
+                    id={`${id}-exclude`}
+                    type="text"
+                    value={exclude}
+                    onChange={(e) => setExclude(e.target.value)}
+                    placeholder="z.B. *sample*"
+                    title="Dateien, deren Name passt, bleiben unverändert"
+                    className="border rounded px-2 py-1 flex-grow"
+                />
+            </div>
+            {paths.length > 0 && (
+                <span className="text-sm">
+                    {paths.length === 1 ? '1 Eintrag' : `${paths.length} Einträge`} im Dateiexplorer ausgewählt
+                </span>
+            )}
+        </div>
+    );
+}
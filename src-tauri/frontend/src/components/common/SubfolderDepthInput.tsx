@@ -0,0 +1,24 @@
+type SubfolderDepthInputProps = {
+    id: string;
+    value: number;
+    onChange: (value: number) => void;
+};
+
+// How many levels of subfolders (e.g. "Season 01") an operation includes, 0 is only the current folder
+export default function SubfolderDepthInput({ id, value, onChange }: SubfolderDepthInputProps) {
+    return (
+        <div className="flex flex-row items-center gap-2">
+            <label htmlFor={id}>Unterordner</label>
+            <input
+                id={id}
+                type="number"
+                min={0}
+                max={5}
+                value={value}
+                onChange={(e) => onChange(Math.max(0, parseInt(e.target.value) || 0))}
+                title="0 = nur der aktuelle Ordner, 1 = auch Staffelordner darin"
+                className="border rounded px-2 py-1 w-20"
+            />
+        </div>
+    );
+}
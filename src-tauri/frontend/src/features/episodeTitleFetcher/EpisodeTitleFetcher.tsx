@@ -50,18 +50,29 @@ export default function EpisodeTitleFetcher({ seasons, setSeasons, selectedSeaso
         }
         // Fetch from TheTVDB
         fetchedSeasons = await fetchTVDBShowDetails(tvdbApiKey, animeId, animeName, year);
+        // Only a known ID lets the main window fetch the episodes again
+        if (animeId !== null) {
+          fetchedShowDetails = {
+            id: animeId,
+            name: animeName ?? '',
+            premiered_year: year !== null ? String(year) : null,
+            episodes_by_season: fetchedSeasons,
+            provider: 'tvdb',
+            tvdb_api_key: tvdbApiKey,
+          };
+        }
       } else if (apiOption == "JIKA") {
         // Fetch from Jikan
         const fetchedSeasonsDetails = await fetchJikanShowDetails(animeId, animeName, year);
 
-        fetchedShowDetails = fetchedSeasonsDetails;
+        fetchedShowDetails = { ...fetchedSeasonsDetails, provider: 'jikan' };
 
         fetchedSeasons = fetchedSeasonsDetails.episodes_by_season;
       } else {
         // Fetch from TVmaze
         const fetchedSeasonsDetails: SeasonedEpisodesDetails = await fetchTVMAZEShowDetails(animeId, animeName, year);
 
-        fetchedShowDetails = fetchedSeasonsDetails;
+        fetchedShowDetails = { ...fetchedSeasonsDetails, provider: 'tvmaze' };
 
         fetchedSeasons = fetchedSeasonsDetails.episodes_by_season
       }
@@ -18,6 +18,7 @@ interface EpisodeTitleFetcherListProps {
 export function EpisodeTitleFetcherList({ seasons, selectedSeason, setSelectedSeason, setError, showDetails }: EpisodeTitleFetcherListProps) {
     const [copySuccess, setCopySuccess] = useState('');  // For displaying the copy success message
     const [sendSuccess, setsendSuccess] = useState('');
+    const [applySuccess, setApplySuccess] = useState('');
     const handleCopy = () => {
         const selectedTitles = seasons.find((season) => season.season === selectedSeason)?.titles.join('\n');
         if (selectedTitles) {
@@ -37,7 +38,7 @@ export function EpisodeTitleFetcherList({ seasons, selectedSeason, setSelectedSe
     const handleSendFetchedEpisodeTitles = async () => {
         try {
             const selectedTitles = seasons.find((season) => season.season === selectedSeason)?.titles;
-            await emit('send_episodes', { episodeTitles: selectedTitles });
+            await emit('send_episodes', { season: selectedSeason, episodeTitles: selectedTitles });
 
             setsendSuccess('Titel gesendet!');
             setTimeout(() => {
@@ -49,6 +50,29 @@ export function EpisodeTitleFetcherList({ seasons, selectedSeason, setSelectedSe
         }
     }
 
+    // Sends all seasons at once. With a provider the main window can also fetch them by ID
+    // and match them to the files itself.
+    const handleSendShow = async () => {
+        try {
+            await emit('send_show', {
+                provider: showDetails?.provider ?? null,
+                showId: showDetails?.id ?? null,
+                tvdbApiKey: showDetails?.tvdb_api_key ?? null,
+                name: showDetails?.name ?? null,
+                year: showDetails?.premiered_year ?? null,
+                seasons,
+            });
+
+            setApplySuccess('Show gesendet!');
+            setTimeout(() => {
+                setApplySuccess('');
+            }, 2000);
+        } catch (err) {
+            console.error(err);
+            setError(String(err));
+        }
+    }
+
     return (
         <GlassCard fullHeight title='Episodenliste' image='/styling/backsplash/orange.jpg'>
             <div className='p-2 flex flex-col w-full h-full overflow-x-hidden gap-4'>
@@ -94,6 +118,8 @@ export function EpisodeTitleFetcherList({ seasons, selectedSeason, setSelectedSe
                             <AnimatedButton text={sendSuccess ? sendSuccess : 'Übernehmen'} onClick={handleSendFetchedEpisodeTitles} image='/styling/buttons/button-blue.jpg' />
                             {/* Copy Episodes Button */}
                             <AnimatedButton text={copySuccess ? copySuccess : 'Kopieren'} onClick={handleCopy} image='/styling/buttons/button-purple.jpg' />
+                            {/* Send all seasons */}
+                            <AnimatedButton text={applySuccess ? applySuccess : 'Alle Staffeln übernehmen'} onClick={handleSendShow} image='/styling/buttons/button-blue.jpg' />
                         </div>
                     </>
                 )}
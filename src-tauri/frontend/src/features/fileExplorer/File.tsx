@@ -5,10 +5,12 @@ type FileProps = {
     index: number;
     file: FileInfo;
     inactive?: boolean;
+    selected?: boolean;
+    onSelect?: (path: string) => void; // Limits the operations to the checked files and folders
     onClickFunction: (path: string) => void;
 };
 
-export default function File({ index, file, inactive, onClickFunction }: FileProps) {
+export default function File({ index, file, inactive, selected, onSelect, onClickFunction }: FileProps) {
     return (
         <>
             <li
@@ -21,6 +23,16 @@ export default function File({ index, file, inactive, onClickFunction }: FilePro
                 }}
                 onClick={() => { file.is_dir && onClickFunction(file.path) }}
             >
+                {onSelect && (
+                    <input
+                        type="checkbox"
+                        checked={selected ?? false}
+                        onChange={() => onSelect(file.path)}
+                        onClick={(e) => e.stopPropagation()}
+                        title="Nur ausgewählte Dateien bearbeiten"
+                        className="mr-2 cursor-pointer"
+                    />
+                )}
                 <span className="flex items-center">
                     {selectFolderIcon(file)}
                 </span>
@@ -1,6 +1,6 @@
 "use client";
 
-import { useEffect, useState } from 'react';
+import { useEffect, useRef, useState } from 'react';
 import {
     listFilesInCurrentDirectory,
     changeDirectory,
@@ -11,12 +11,15 @@ import {
 import Controls from './explorerControls/Controls';
 import File from './File';
 import BreadCrumbs from './BreadCrumbs';
-import { listen } from '@tauri-apps/api/event';
+import { emit, listen } from '@tauri-apps/api/event';
 import GlassCard from '@/components/layout/GlassCard';
 
 export default function FileExplorer() {
     const [files, setFiles] = useState<FileInfo[]>([]);
     const [hierarchy, setHierarchy] = useState<DirectoryHierarchy[]>([]);
+    // Files and folders the operations are limited to, none means all of them
+    const [selectedPaths, setSelectedPaths] = useState<string[]>([]);
+    const selectedPathsRef = useRef<string[]>([]);
 
     useEffect(() => {
         loadInitialData();
@@ -29,14 +32,36 @@ export default function FileExplorer() {
 
         setFiles(initialFiles);
         setHierarchy(hierarchy);
+        // Renamed or moved files drop out of the selection
+        setSelectedPaths((paths) => {
+            const kept = paths.filter((path) => initialFiles.some((file) => file.path === path));
+            return kept.length === paths.length ? paths : kept;
+        });
     }
 
+    // The operations hear about every change, a newly opened one asks for the current selection
+    useEffect(() => {
+        selectedPathsRef.current = selectedPaths;
+        emit('file-selection-changed', selectedPaths);
+    }, [selectedPaths]);
+
+    useEffect(() => {
+        const unlisten = listen('file-selection-requested', () => {
+            emit('file-selection-changed', selectedPathsRef.current);
+        });
+
+        return () => {
+            unlisten.then((fn) => fn());
+        };
+    }, []);
+
     useEffect(() => {
         const unlisten = listen<string>('trigger-reload', async (event) => {
             loadInitialData();
         });
 
         const unlisten2 = listen<string>('directory-changed', async (event) => {
+            setSelectedPaths([]);
             loadInitialData();
         });
 
@@ -53,8 +78,15 @@ export default function FileExplorer() {
 
         setFiles(newFiles);
         setHierarchy(newHierarchy);
+        setSelectedPaths([]);
     };
 
+    function handleSelect(path: string) {
+        setSelectedPaths((paths) =>
+            paths.includes(path) ? paths.filter((selected) => selected !== path) : [...paths, path]
+        );
+    }
+
     return (
         <GlassCard fullHeight title='Dateiexplorer' image='/styling/backsplash/blue2.jpg'>
             <div className="flex items-center p-2 bg-white bg-opacity-30">
@@ -63,7 +95,14 @@ export default function FileExplorer() {
 
             <ul className="flex-col w-full max-h-full overflow-x-hidden flex-grow text-md">
                 {files.map((file, index) => (
-                    <File key={index} index={index} file={file} onClickFunction={handleDirectoryClick} />
+                    <File
+                        key={index}
+                        index={index}
+                        file={file}
+                        selected={selectedPaths.includes(file.path)}
+                        onSelect={handleSelect}
+                        onClickFunction={handleDirectoryClick}
+                    />
                 ))}
             </ul >
             <Controls />
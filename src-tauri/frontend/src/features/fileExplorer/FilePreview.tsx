@@ -5,16 +5,53 @@ import {
     listFilesInCurrentDirectory,
     FileInfo,
     getCurrentPath,
+    PreviewEntry,
 } from '../../services/tauriService';
 import File from './File';
+import PreviewFile from './PreviewFile';
 import { listen } from '@tauri-apps/api/event';
 import React from 'react';
-import { BsDeviceHddFill } from 'react-icons/bs';
+import { BsDeviceHddFill, BsFolderFill } from 'react-icons/bs';
 import GlassCard from '@/components/layout/GlassCard';
 
+// Folder of a path, works with both / and \ separators
+function parentFolder(path: string): string {
+    return path.replace(/[\\/][^\\/]*$/, '');
+}
+
+// Folder relative to the current directory, '' for the directory itself
+function relativeFolder(folder: string, root: string): string {
+    return folder.startsWith(root) ? folder.slice(root.length).replace(/^[\\/]/, '') : folder;
+}
+
+type TreeRow = { folder: string, depth: number } | { entry: PreviewEntry, depth: number };
+
+// Moves into other folders are shown as the tree they create, one row per folder.
+// Sorting is stable, so companions stay right under their video.
+function treeRows(entries: PreviewEntry[], root: string): TreeRow[] {
+    const sorted = [...entries].sort((a, b) => parentFolder(a.proposed_path).localeCompare(parentFolder(b.proposed_path)));
+    const rows: TreeRow[] = [];
+    let previous: string[] = [];
+    sorted.forEach((entry) => {
+        const folder = relativeFolder(parentFolder(entry.proposed_path), root);
+        const segments = folder ? folder.split(/[\\/]/) : [];
+        let common = 0;
+        while (common < segments.length && common < previous.length && segments[common] === previous[common]) {
+            common++;
+        }
+        for (let depth = common; depth < segments.length; depth++) {
+            rows.push({ folder: segments[depth], depth });
+        }
+        rows.push({ entry, depth: segments.length });
+        previous = segments;
+    });
+    return rows;
+}
+
 export default function FilePreview() {
     const [files, setFiles] = useState<FileInfo[]>([]);
     const [path, setPath] = useState<string>('');
+    const [previewEntries, setPreviewEntries] = useState<PreviewEntry[] | null>(null);
 
     useEffect(() => {
         async function loadInitialData() {
@@ -35,29 +72,20 @@ export default function FilePreview() {
 
             setFiles(files);
             setPath(newPath);
+            setPreviewEntries(null);
         });
 
         const unlistenTriggerReload = listen<string>('trigger-reload', async (event) => {
             const files = await listFilesInCurrentDirectory();
 
             setFiles(files);
+            setPreviewEntries(null);
         });
 
-        const unlistenTriggerPreview = listen<{ new_file_names: string[] }>('trigger-preview', async (event) => {
-            const previewFiles = event.payload.new_file_names;
-
-            // console.log("Previewing Files:", previewFiles);
+        const unlistenTriggerPreview = listen<{ plan_id: string, entries: PreviewEntry[] }>('trigger-preview', async (event) => {
             console.log("Previewing Files");
 
-            // Map previewFiles to FileInfo[]
-            const fileInfos: FileInfo[] = previewFiles.map((fileName) => ({
-                path: path, // Use the current path
-                is_dir: false,
-                is_video: true,
-                name: fileName,
-            }));
-
-            setFiles(fileInfos);
+            setPreviewEntries(event.payload.entries);
         });
 
         return () => {
@@ -74,11 +102,38 @@ export default function FilePreview() {
                 {path}
             </span>
             <ul className="flex-col w-full max-h-full overflow-x-hidden flex-grow text-md">
-                {files.map((file, index) => (
-                    <React.Fragment key={index}>{file.is_video &&
-                        <File index={index} file={file} onClickFunction={() => { }} />
-                    }</React.Fragment>
-                ))}
+                {previewEntries && previewEntries.some((entry) => parentFolder(entry.proposed_path) !== parentFolder(entry.original_path))
+                    ? treeRows(previewEntries, path).map((row, index) => 'entry' in row
+                        ? <PreviewFile key={index} index={index} entry={row.entry} depth={row.depth} />
+                        : (
+                            <li key={index} className="p-2 bg-white bg-opacity-20 break-all glass-card-border-top" style={{ paddingLeft: `${0.5 + row.depth}rem` }}>
+                                <BsFolderFill className="align-text-top h-5 w-5 text-dir inline mr-1" />
+                                {row.folder}
+                            </li>
+                        ))
+                    : previewEntries
+                    ? previewEntries.map((entry, index) => {
+                        // Entries are sorted by path, so a recursive preview gets one header per folder
+                        const folder = parentFolder(entry.original_path);
+                        const showFolder = previewEntries.some((other) => parentFolder(other.original_path) !== folder)
+                            && (index === 0 || parentFolder(previewEntries[index - 1].original_path) !== folder);
+                        return (
+                            <React.Fragment key={index}>
+                                {showFolder && (
+                                    <li className="p-2 bg-white bg-opacity-20 break-all glass-card-border-top">
+                                        <BsFolderFill className="align-text-top h-5 w-5 text-dir inline mr-1" />
+                                        {relativeFolder(folder, path) || '.'}
+                                    </li>
+                                )}
+                                <PreviewFile index={index} entry={entry} />
+                            </React.Fragment>
+                        );
+                    })
+                    : files.map((file, index) => (
+                        <React.Fragment key={index}>{file.is_video &&
+                            <File index={index} file={file} onClickFunction={() => { }} />
+                        }</React.Fragment>
+                    ))}
             </ul >
         </GlassCard>
     );
@@ -0,0 +1,70 @@
+import { BsFileEarmarkPlayFill, BsFileEarmarkTextFill, BsExclamationTriangleFill, BsArrowRight, BsTrashFill } from "react-icons/bs";
+import { PreviewEntry } from '@/services/tauriService';
+import React from 'react';
+
+type PreviewFileProps = {
+    index: number;
+    entry: PreviewEntry;
+    depth?: number; // Indentation in a folder tree
+};
+
+// The name with its highlighted spans, e.g. the matches of a search
+function highlightedName(name: string, highlights: [number, number][]) {
+    const parts: React.ReactNode[] = [];
+    let position = 0;
+    [...highlights].sort((a, b) => a[0] - b[0]).forEach(([start, end], index) => {
+        if (start < position || end <= start) {
+            return;
+        }
+        parts.push(name.slice(position, start));
+        parts.push(<mark key={index} className="bg-yellow-300 bg-opacity-60 rounded-sm">{name.slice(start, end)}</mark>);
+        position = end;
+    });
+    parts.push(name.slice(position));
+    return parts;
+}
+
+export default function PreviewFile({ index, entry, depth = 0 }: PreviewFileProps) {
+    const isConflict = entry.status === 'conflict';
+    const isDeleted = entry.status === 'deleted';
+    const isInactive = entry.status === 'skipped' || entry.status === 'unchanged';
+    const isCompanion = entry.companion_of !== null;
+
+    return (
+        <li
+            key={index}
+            className={`py-1 flex flex-col break-all glass-card-border-top
+                ${isCompanion ? ' pl-8 text-sm' : ' pl-2'}${isConflict ? ' text-error' : ''}${isInactive ? ' text-inactive' : ''}`}
+            style={{
+                backgroundColor: index % 2 === 0 ? 'rgba(255, 255, 255, 0)' : 'rgba(255, 255, 255, 0.1)',
+                marginLeft: `${depth}rem`,
+            }}
+            title={entry.original_name}
+        >
+            <span className="flex">
+                <span className="flex items-center">
+                    {isConflict
+                        ? <BsExclamationTriangleFill className="align-text-top h-5 w-5 inline mr-2" />
+                        : isDeleted
+                            ? <BsTrashFill className="align-text-top h-5 w-5 text-error inline mr-2" />
+                        : isCompanion
+                            ? <BsFileEarmarkTextFill className="align-text-top h-4 w-4 inline mr-2" />
+                            : <BsFileEarmarkPlayFill className="align-text-top h-5 w-5 text-media inline mr-2" />}
+                </span>
+                <span className={isDeleted ? 'line-through text-inactive' : ''}>{entry.proposed_name}</span>
+            </span>
+            {entry.status !== 'unchanged' && entry.status !== 'skipped' && !isDeleted && (
+                <span className="flex text-sm text-inactive pl-7">
+                    <BsArrowRight className="h-4 w-4 inline mr-1 rotate-180" />
+                    <span>{highlightedName(entry.original_name, entry.highlights)}</span>
+                </span>
+            )}
+            {entry.reason && (
+                <span className="flex text-sm pl-7">{entry.reason}</span>
+            )}
+            {entry.notes.map((note, noteIndex) => (
+                <span key={noteIndex} className="flex text-sm text-inactive pl-7">{note}</span>
+            ))}
+        </li>
+    );
+}
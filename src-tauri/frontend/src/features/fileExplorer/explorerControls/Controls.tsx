@@ -14,6 +14,8 @@ import ControlsButtonSelectPath from './ControlsButtonSelectPath';
 import ControlsButtonExplorer from './ControlsButtonExplorer';
 import ControlsButtonTerminal from './ControlsButtonTerminal';
 import ControlsButtonRefresh from './ControlsButtonRefresh';
+import ControlsButtonUndo from './ControlsButtonUndo';
+import ControlsButtonRedo from './ControlsButtonRedo';
 import ControlsButtonPrint from './ControlsButtonPrint';
 import ControlsListPrint from './ControlsListPrint';
 import LoadingScreen from '@/components/common/LoadingScreen';
@@ -71,6 +73,8 @@ export default function Controls() {
                 <ControlsButtonExplorer />
                 <ControlsButtonTerminal />
                 <ControlsButtonRefresh />
+                <ControlsButtonUndo />
+                <ControlsButtonRedo />
                 <ControlsButtonPrint toggleListPrint={handleListPrint} showPrint={showPrint} />
             </div>
         </>
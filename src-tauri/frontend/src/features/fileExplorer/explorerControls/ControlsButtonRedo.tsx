@@ -0,0 +1,27 @@
+import { redoOperation } from "@/services/tauriService";
+import { Tooltip } from "@nextui-org/tooltip";
+import { message } from "@tauri-apps/api/dialog";
+import { BsArrowClockwise } from "react-icons/bs";
+
+export default function ControlsButtonRedo() {
+
+    const handleRedo = async () => {
+        try {
+            await redoOperation();
+        } catch (err) {
+            console.error("Failed to redo operation:", err);
+            await message(String(err), { title: 'Wiederholen', type: 'error' });
+        }
+    };
+
+    return (
+        <Tooltip content="Wiederholen" placement="top" className='bg-white px-2 rounded border border-gray-100'>
+            <div
+                className="h-6 w-6 text-controls-100 inline cursor-pointer hover:text-controls-200 active:text-controls-300"
+                onClick={handleRedo}
+            >
+                <BsArrowClockwise />
+            </div>
+        </Tooltip>
+    );
+}
@@ -0,0 +1,27 @@
+import { undoLastOperation } from "@/services/tauriService";
+import { Tooltip } from "@nextui-org/tooltip";
+import { message } from "@tauri-apps/api/dialog";
+import { BsArrowCounterclockwise } from "react-icons/bs";
+
+export default function ControlsButtonUndo() {
+
+    const handleUndo = async () => {
+        try {
+            await undoLastOperation();
+        } catch (err) {
+            console.error("Failed to undo operation:", err);
+            await message(String(err), { title: 'Rückgängig', type: 'error' });
+        }
+    };
+
+    return (
+        <Tooltip content="Rückgängig" placement="top" className='bg-white px-2 rounded border border-gray-100'>
+            <div
+                className="h-6 w-6 text-controls-100 inline cursor-pointer hover:text-controls-200 active:text-controls-300"
+                onClick={handleUndo}
+            >
+                <BsArrowCounterclockwise />
+            </div>
+        </Tooltip>
+    );
+}
@@ -0,0 +1,153 @@
+"use client";
+
+import React, { useEffect, useState } from 'react';
+import { listen } from '@tauri-apps/api/event';
+import { ask } from '@tauri-apps/api/dialog';
+import { caancelFilePrinter, deleteDuplicateFiles, DuplicateProgress, DuplicateReport, findDuplicateFiles } from '@/services/tauriService';
+import { AnimatedButton } from '@/components/ui/AnimatedButton';
+import GlassCard from '@/components/layout/GlassCard';
+import ErrorMessage from '@/components/common/ErrorMessage';
+
+const PHASES: Record<DuplicateProgress['phase'], string> = {
+    scanning: 'Dateien werden gesucht',
+    partial_hash: 'Dateianfänge werden verglichen',
+    full_hash: 'Dateien werden vollständig verglichen',
+};
+
+function formatSize(size: number): string {
+    if (size >= 1_073_741_824) {
+        return `${(size / 1_073_741_824).toFixed(2)} GB`;
+    } else if (size >= 1_048_576) {
+        return `${(size / 1_048_576).toFixed(2)} MB`;
+    }
+    return `${(size / 1_024).toFixed(2)} KB`;
+}
+
+function progressText(progress: DuplicateProgress): string {
+    if (progress.phase === 'scanning') {
+        return `${PHASES.scanning}: ${progress.done}`;
+    }
+    const percent = progress.total > 0 ? Math.floor(progress.done / progress.total * 100) : 0;
+    return `${PHASES[progress.phase]}: ${percent}%`;
+}
+
+export default function DuplicateFinder() {
+    const [report, setReport] = useState<DuplicateReport | null>(null);
+    const [progress, setProgress] = useState<DuplicateProgress | null>(null);
+    const [isSearching, setIsSearching] = useState<boolean>(false);
+    const [isDeleting, setIsDeleting] = useState<boolean>(false); // The files are hashed again first, which can take a while
+    const [selected, setSelected] = useState<string[]>([]);
+    const [error, setError] = useState<string | null>(null);
+
+    useEffect(() => {
+        const unlisten = listen<DuplicateProgress>('duplicate_progress', (event) => {
+            setProgress(event.payload);
+        });
+
+        return () => {
+            unlisten.then((fn) => fn());
+        };
+    }, []);
+
+    function handleSearch() {
+        setError(null);
+        setReport(null);
+        setSelected([]);
+        setProgress(null);
+        setIsSearching(true);
+        findDuplicateFiles()
+            .then((newReport) => setReport(newReport))
+            .catch((err) => {
+                console.error("Failed to find duplicates:", err);
+                setError(String(err));
+            })
+            .finally(() => {
+                setIsSearching(false);
+                setProgress(null);
+            });
+    }
+
+    function handleCancel() {
+        caancelFilePrinter().catch((err) => console.error("Failed to cancel:", err));
+    }
+
+    // Marks every file of every set but the first one
+    function selectAllButFirst() {
+        setSelected(report ? report.sets.flatMap((set) => set.paths.slice(1)) : []);
+    }
+
+    function togglePath(path: string) {
+        setSelected(selected.includes(path) ? selected.filter((other) => other !== path) : [...selected, path]);
+    }
+
+    async function handleDelete() {
+        setError(null);
+        if (selected.length === 0) {
+            setError("Keine Dateien ausgewählt.");
+            return;
+        }
+        const confirmed = await ask(`${selected.length} Dateien endgültig löschen? Das kann nicht rückgängig gemacht werden.`, { title: 'Duplikate löschen', type: 'warning' });
+        if (!confirmed) {
+            return;
+        }
+        setIsDeleting(true);
+        deleteDuplicateFiles(selected)
+            .then((newReport) => {
+                setReport(newReport);
+                setSelected([]);
+            })
+            .catch((err) => {
+                console.error("Failed to delete duplicates:", err);
+                setError("Failed to delete duplicates: " + err);
+            })
+            .finally(() => setIsDeleting(false));
+    }
+
+    return (
+        <GlassCard title='Duplikate finden' image='/styling/backsplash/gray.jpg'>
+            <div className="flex flex-col gap-2 p-2">
+                {isSearching && progress && <span className="text-sm">{progressText(progress)}</span>}
+                {report && (
+                    <span className="text-sm">
+                        {report.scanned_files} Dateien durchsucht, {report.sets.length} Gruppen mit Duplikaten, {formatSize(report.wasted)} verschwendet
+                    </span>
+                )}
+                {report && report.skipped.length > 0 && (
+                    <details className="text-sm text-error">
+                        <summary>{report.skipped.length} Dateien oder Ordner konnten nicht gelesen werden und wurden nicht verglichen</summary>
+                        <ul className="font-mono break-all">
+                            {report.skipped.map((skipped, index) => <li key={index}>{skipped}</li>)}
+                        </ul>
+                    </details>
+                )}
+                {report && report.sets.length > 0 && (
+                    <>
+                        <button onClick={selectAllButFirst} className="border rounded px-2 py-1 self-start">Alle außer der ersten Kopie auswählen</button>
+                        <ul className="flex flex-col gap-2 font-mono text-sm">
+                            {report.sets.map((set) => (
+                                <li key={set.hash} className="flex flex-col glass-card-border-top pt-1">
+                                    <span className="text-inactive">{set.paths.length} × {formatSize(set.size)}, {formatSize(set.wasted)} verschwendet</span>
+                                    {set.paths.map((path) => (
+                                        <label key={path} className="flex flex-row items-center gap-2 break-all">
+                                            <input type="checkbox" checked={selected.includes(path)} onChange={() => togglePath(path)} />
+                                            <span className={selected.includes(path) ? 'line-through' : ''}>{path}</span>
+                                        </label>
+                                    ))}
+                                </li>
+                            ))}
+                        </ul>
+                    </>
+                )}
+                {error && <ErrorMessage message={error}></ErrorMessage>}
+                <div className='flex flex-row w-full justify-center gap-2'>
+                    {isSearching || isDeleting
+                        ? <AnimatedButton text="Abbrechen" onClick={handleCancel} image='/styling/buttons/button-purple.jpg' />
+                        : <AnimatedButton text="Suchen" onClick={handleSearch} image='/styling/buttons/button-purple.jpg' />}
+                    {report && report.sets.length > 0 && !isSearching && !isDeleting && (
+                        <AnimatedButton text="Ausgewählte löschen" onClick={handleDelete} image='/styling/buttons/button-purple.jpg' />
+                    )}
+                </div>
+            </div>
+        </GlassCard>
+    );
+}
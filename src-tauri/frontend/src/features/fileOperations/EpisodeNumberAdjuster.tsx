@@ -1,14 +1,19 @@
 "use client";
 
 import React, { useEffect, useState } from 'react';
-import { adjustEpisodeNumbers, adjustEpisodeNumbersPreview, triggerRefresh } from '@/services/tauriService'; // Adjust import paths as necessary
+import { adjustEpisodeNumbersPreview, applyRenamePlan, FileSelection, renameReportError, triggerRefresh } from '@/services/tauriService'; // Adjust import paths as necessary
 import { AnimatedButton } from '@/components/ui/AnimatedButton';
 import GlassCard from '@/components/layout/GlassCard';
 import ErrorMessage from '@/components/common/ErrorMessage';
+import SubfolderDepthInput from '@/components/common/SubfolderDepthInput';
+import FileSelectionInput from '@/components/common/FileSelectionInput';
 
 export default function EpisodeNumberAdjuster() {
     const [adjustmentValue, setAdjustmentValue] = useState<number | null>(null);
+    const [subfolderDepth, setSubfolderDepth] = useState<number>(0);
+    const [selection, setSelection] = useState<FileSelection>({});
     const [error, setError] = useState<string | null>(null);
+    const [planId, setPlanId] = useState<string | null>(null);
 
     function handleAdjust() {
         setError(null);
@@ -17,9 +22,20 @@ export default function EpisodeNumberAdjuster() {
             return;
         }
 
+        if (!planId) {
+            setError("Keine Vorschau vorhanden.");
+            return;
+        }
+
         setError(null);
-        adjustEpisodeNumbers(adjustmentValue)
-            .then(() => {
+        setPlanId(null);
+        applyRenamePlan(planId)
+            .then((report) => {
+                const reportError = renameReportError(report);
+                if (reportError) {
+                    setError(reportError);
+                    return;
+                }
                 console.log("Episode numbers adjusted successfully");
                 setAdjustmentValue(null);
             })
@@ -31,9 +47,11 @@ export default function EpisodeNumberAdjuster() {
 
     useEffect(() => {
         function handlePreview() {
+            setPlanId(null);
             !adjustmentValue ? triggerRefresh() :
-                adjustEpisodeNumbersPreview(adjustmentValue)
-                    .then(() => {
+                adjustEpisodeNumbersPreview(adjustmentValue, subfolderDepth, selection)
+                    .then((newPlanId) => {
+                        setPlanId(newPlanId);
                         console.log("Preview successfull: Number Adjuster");
                     })
                     .catch((err) => {
@@ -42,7 +60,7 @@ export default function EpisodeNumberAdjuster() {
                     });
         };
         handlePreview()
-    }, [adjustmentValue]);
+    }, [adjustmentValue, subfolderDepth, selection]);
 
     return (
         <GlassCard title='Episodennummer anpassen' image='/styling/backsplash/gray.jpg'>
@@ -54,6 +72,8 @@ export default function EpisodeNumberAdjuster() {
                     placeholder="Gib eine Zahl ein (z.B. 1 oder -1)"
                     className="border rounded px-2 py-1"
                 />
+                <SubfolderDepthInput id="episode-adjuster-depth" value={subfolderDepth} onChange={setSubfolderDepth} />
+                <FileSelectionInput id="episode-adjuster-selection" onChange={setSelection} />
                 {error && <ErrorMessage message={error}></ErrorMessage>}
                 <div className='flex flex-row w-full gap-2 justify-center'>
                     <AnimatedButton text="Anpassen" onClick={handleAdjust} image='/styling/buttons/button-purple.jpg' />
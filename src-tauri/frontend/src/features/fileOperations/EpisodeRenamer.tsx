@@ -1,20 +1,28 @@
 "use client";
 
 import React, { useEffect, useState } from 'react';
-import { addTitlesToEpisodes, addTitlesToEpisodesPreview, getCurrentEpisodeNames, openEpisodeTitleWindow, focusMainWindow } from '@/services/tauriService'; // Adjust the import path as necessary
+import { applyRenamePlan, addTitlesToEpisodesPreview, fetchAndApplyTitlesPreview, FileSelection, getCurrentEpisodeNames, getCurrentPath, openEpisodeTitleWindow, focusMainWindow, MetadataProvider, renameReportError } from '@/services/tauriService'; // Adjust the import path as necessary
 import { listen } from '@tauri-apps/api/event'; // Import the event listener
 import { AnimatedButton } from '@/components/ui/AnimatedButton';
 import GlassCard from '@/components/layout/GlassCard';
 import ErrorMessage from '@/components/common/ErrorMessage';
+import SubfolderDepthInput from '@/components/common/SubfolderDepthInput';
+import FileSelectionInput from '@/components/common/FileSelectionInput';
 
 export default function EpisodeRenamer() {
     const [episodeTitles, setEpisodeTitles] = useState<string>('');
+    const [season, setSeason] = useState<number>(1);
+    const [titleSeparator, setTitleSeparator] = useState<string>(' + ');
+    const [subfolderDepth, setSubfolderDepth] = useState<number>(0);
+    const [selection, setSelection] = useState<FileSelection>({});
     const [error, setError] = useState<string | null>(null);
+    const [planId, setPlanId] = useState<string | null>(null);
 
     useEffect(() => {
         // Listen for the 'fetched_episodes' event emitted from the backend
-        const unlisten = listen<{ episodeTitles: string[] }>('send_episodes', async (event) => {
+        const unlisten = listen<{ season: number, episodeTitles: string[] }>('send_episodes', async (event) => {
             const episodeTitles = event.payload.episodeTitles
+            setSeason(event.payload.season);
 
             // Check if episodeTitles is an array before joining
             if (Array.isArray(episodeTitles)) {
@@ -35,12 +43,40 @@ export default function EpisodeRenamer() {
         };
     }, []);
 
+    useEffect(() => {
+        // The fetcher window sent a whole show, the backend fetches it again and matches it to the files
+        const unlisten = listen<{ provider: MetadataProvider | null, showId: number | null, tvdbApiKey: string | null }>('send_show', async (event) => {
+            const { provider, showId, tvdbApiKey } = event.payload;
+            if (!provider || showId === null) {
+                return;
+            }
+            setError(null);
+            focusMainWindow();
+            try {
+                const directory = await getCurrentPath();
+                setPlanId(await fetchAndApplyTitlesPreview(provider, showId, tvdbApiKey, directory, titleSeparator, subfolderDepth, selection));
+                console.log("Preview successfull: Episode Renamer (provider)");
+            } catch (err) {
+                setPlanId(null);
+                console.error("Preview failed: Episode Renamer (provider):", err);
+                setError(String(err));
+            }
+        });
+
+        return () => {
+            unlisten.then((dispose) => dispose());
+        };
+    }, [titleSeparator, subfolderDepth, selection]);
+
     // Function to fetch and set current episode titles
     async function fetchCurrentEpisodes() {
         setError(null);
         try {
-            const currentTitles = await getCurrentEpisodeNames();
-            setEpisodeTitles(currentTitles.join('\n')); // Pre-fill text area with the episode titles
+            const currentSeasons = await getCurrentEpisodeNames(titleSeparator);
+            if (currentSeasons.length > 0) {
+                setSeason(currentSeasons[0].season);
+                setEpisodeTitles(currentSeasons[0].titles.join('\n')); // Pre-fill text area with the episode titles
+            }
         } catch (err) {
             console.error("Failed to load current episode names:", err);
             setError("Failed to load current episode names.");
@@ -68,10 +104,15 @@ export default function EpisodeRenamer() {
     // Function to handle renaming episodes
     function handleRename() {
         setError(null);
-        const titles = episodeTitles.split('\n').map(title => title.trim());
-        addTitlesToEpisodes(titles)
-            .then(() => {
-                console.log("Files renamed successfully");
+        if (!planId) {
+            setError("Keine Vorschau vorhanden.");
+            return;
+        }
+        setPlanId(null);
+        applyRenamePlan(planId)
+            .then((report) => {
+                const reportError = renameReportError(report);
+                reportError ? setError(reportError) : console.log("Files renamed successfully");
             })
             .catch((err) => {
                 console.error("Failed to rename files:", err);
@@ -82,28 +123,53 @@ export default function EpisodeRenamer() {
     // Function to handle previewing new file names
     useEffect(() => {
         function handlePreview() {
+            // Line n holds the title of episode n of the selected season
             const titles = episodeTitles.split('\n').map(title => title.trim());
-            addTitlesToEpisodesPreview(titles)
-                .then(() => {
+            addTitlesToEpisodesPreview([{ season, start_episode: 1, end_episode: titles.length, titles }], titleSeparator, subfolderDepth, selection)
+                .then((newPlanId) => {
+                    setPlanId(newPlanId);
                     console.log("Preview successfull: Episode Renamer");
                 })
                 .catch((err) => {
+                    setPlanId(null);
                     console.error("Preview failed: Episode Renamer:", err);
                 });
         }
 
         episodeTitles && handlePreview();
-    }, [episodeTitles]);
+    }, [episodeTitles, season, titleSeparator, subfolderDepth, selection]);
 
     return (
         <GlassCard fullHeight title='Episoden Umbenennen' image='/styling/backsplash/green.jpg'>
             <div className="flex flex-col gap-2 p-2 h-full">
+                <div className="flex flex-row items-center gap-2">
+                    <label htmlFor="episode-renamer-season">Staffel</label>
+                    <input
+                        id="episode-renamer-season"
+                        type="number"
+                        min={0}
+                        value={season}
+                        onChange={(e) => setSeason(parseInt(e.target.value) || 0)}
+                        className="border rounded px-2 py-1 w-20"
+                    />
+                    <label htmlFor="episode-renamer-separator">Trenner</label>
+                    <input
+                        id="episode-renamer-separator"
+                        type="text"
+                        value={titleSeparator}
+                        onChange={(e) => setTitleSeparator(e.target.value)}
+                        title="Verbindet die Titel von Mehrfachepisoden"
+                        className="border rounded px-2 py-1 w-20 font-mono"
+                    />
+                </div>
+                <SubfolderDepthInput id="episode-renamer-depth" value={subfolderDepth} onChange={setSubfolderDepth} />
+                <FileSelectionInput id="episode-renamer-selection" onChange={setSelection} />
                 <div className="flex flex-row flex-grow">
                     <textarea
                         style={{ whiteSpace: 'pre', overflowY: 'auto', }}
                         value={episodeTitles}
                         onChange={(e) => setEpisodeTitles(e.target.value)}
-                        placeholder="Jede Zeile entspricht einer Episode"
+                        placeholder="Zeile 1 entspricht Episode 1 der Staffel"
                         className="border rounded px-2 py-1 font-mono w-full"
                     />
                 </div>
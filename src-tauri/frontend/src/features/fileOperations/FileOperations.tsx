@@ -3,12 +3,23 @@
 import React, { useEffect, useState } from 'react';
 import FileRenamer from './FileRenamer';
 import EpisodeNumberAdjuster from './EpisodeNumberAdjuster';
+import SeasonAdjuster from './SeasonAdjuster';
+import TemplateRenamer from './TemplateRenamer';
 import EpisodeRenamer from './EpisodeRenamer';
 import GlassCard from '@/components/layout/GlassCard';
 import ImageButtonSwitch from '@/components/ui/ImageButtonSwitch';
-import { triggerRefresh } from '@/services/tauriService';
+import { getSanitizeProfile, SanitizeProfile, setSanitizeProfile, triggerRefresh } from '@/services/tauriService';
 import InfoMessage from '@/components/common/InfoMessage';
 import FolderOrganizer from './FolderOrganizer';
+import NumberingConverter from './NumberingConverter';
+import ReleaseCleaner from './ReleaseCleaner';
+import NameTransformer from './NameTransformer';
+import SanitizeProfileSelect from './SanitizeProfileSelect';
+import SequentialNumberer from './SequentialNumberer';
+import ShowOrganizer from './ShowOrganizer';
+import FolderFlattener from './FolderFlattener';
+import MovieOrganizer from './MovieOrganizer';
+import DuplicateFinder from './DuplicateFinder';
 
 interface FileOperationsOptions {
     id: string;
@@ -19,9 +30,22 @@ interface FileOperationsOptions {
 }
 
 export default function FileOperations() {
-    type ToolOption = 'SR' | 'ADJ' | 'ER' | 'FO';  // Define the three possible options
+    type ToolOption = 'SR' | 'TR' | 'RC' | 'ADJ' | 'SEA' | 'ABS' | 'NUM' | 'ER' | 'TPL' | 'FO' | 'PLX' | 'MOV' | 'FLT' | 'DUP';  // Define the possible options
     const [options, setOptions] = useState<FileOperationsOptions[]>();
     const [toolOption, setToolOption] = useState<ToolOption>('SR');  // Initialize with one of the options
+    const [sanitizeProfile, setSanitizeProfileState] = useState<SanitizeProfile | null>(null);
+
+    useEffect(() => {
+        getSanitizeProfile()
+            .then(setSanitizeProfileState)
+            .catch((err) => console.error("Failed to load sanitize profile:", err));
+    }, []);
+
+    function handleProfileChange(profile: SanitizeProfile) {
+        setSanitizeProfile(profile)
+            .then(() => setSanitizeProfileState(profile))
+            .catch((err) => console.error("Failed to save sanitize profile:", err));
+    }
 
     useState(() => {
         setOptions([
@@ -32,6 +56,20 @@ export default function FileOperations() {
                 image: '/styling/buttons/glasses.jpg',
                 option: <FileRenamer />
             },
+            {
+                id: 'TR',
+                title: 'Schreibweise anpassen',
+                description: 'Ändere Groß- und Kleinschreibung, normalisiere Unicode, wandle in ASCII um oder ersetze Punkte und Unterstriche durch Leerzeichen.',
+                image: '/styling/buttons/glasses.jpg',
+                option: <NameTransformer />
+            },
+            {
+                id: 'RC',
+                title: 'Release-Namen bereinigen',
+                description: 'Zerlege Namen wie "[Gruppe] Show.S01E03.1080p.WEB-DL.x264-GRUPPE [ABCD1234]" und baue sie aus den gewählten Teilen neu auf.',
+                image: '/styling/buttons/glasses.jpg',
+                option: <ReleaseCleaner />
+            },
             {
                 id: 'ADJ',
                 title: 'Episodennummer anpassen',
@@ -39,6 +77,27 @@ export default function FileOperations() {
                 image: '/styling/buttons/numbers.jpg',
                 option: <EpisodeNumberAdjuster />
             },
+            {
+                id: 'SEA',
+                title: 'Staffelnummer anpassen',
+                description: 'Verschiebe alle Staffeln oder einen Episodenbereich in eine andere Staffel (z.B. S02E13-S02E24 zu S03E01-S03E12).',
+                image: '/styling/buttons/numbers.jpg',
+                option: <SeasonAdjuster />
+            },
+            {
+                id: 'ABS',
+                title: 'Absolute Nummerierung',
+                description: 'Wandle absolute Episodennummern (Show - 37) mit den geladenen Staffeln in SxxEyy um und zurück.',
+                image: '/styling/buttons/numbers.jpg',
+                option: <NumberingConverter />
+            },
+            {
+                id: 'NUM',
+                title: 'Dateien durchnummerieren',
+                description: 'Gib Dateien ohne Episodennummer (z.B. "Track 01.mkv" oder "VTS_01_1.mkv") der Reihe nach eine SxxEyy Nummer.',
+                image: '/styling/buttons/numbers.jpg',
+                option: <SequentialNumberer />
+            },
             {
                 id: 'ER',
                 title: 'Episoden umbenennen',
@@ -46,12 +105,47 @@ export default function FileOperations() {
                 image: '/styling/buttons/rename.jpg',
                 option: <EpisodeRenamer />
             },
+            {
+                id: 'TPL',
+                title: 'Mit Vorlage umbenennen',
+                description: 'Benenne Episoden nach einer Vorlage wie "{show} - S{season:02}E{episode:02} - {title}.{ext}".',
+                image: '/styling/buttons/rename.jpg',
+                option: <TemplateRenamer />
+            },
             {
                 id: 'FO',
                 title: 'In Ordner Verschieben',
                 description: 'Verschiebe Dateien in Ordner. Oder ziehe Dateien aus Ordnern.',
                 image: '/styling/buttons/package.jpg',
                 option: <FolderOrganizer />
+            },
+            {
+                id: 'PLX',
+                title: 'Serienstruktur',
+                description: 'Verschiebe Episoden nach "Serie (Jahr)/Season 01/Serie - S01E01 - Titel" für Plex und Jellyfin. Specials landen in "Season 00". Sende eine Serie aus dem Titelfenster um Titel und Jahr zu übernehmen.',
+                image: '/styling/buttons/package.jpg',
+                option: <ShowOrganizer />
+            },
+            {
+                id: 'MOV',
+                title: 'Filmstruktur',
+                description: 'Verschiebe Filme samt Untertiteln nach "Titel (Jahr)/Titel (Jahr).mkv". Editionen wie Director\'s Cut werden als {edition-Director\'s Cut} angehängt. Mit einem TMDB API-Schlüssel werden Titel und Jahr abgeglichen.',
+                image: '/styling/buttons/package.jpg',
+                option: <MovieOrganizer />
+            },
+            {
+                id: 'FLT',
+                title: 'Ordner auflösen',
+                description: 'Ziehe Videos aus Unterordnern und entferne Ordner, die danach leer sind. Lege fest, was mit Untertiteln, Samples und sonstigen Dateien passiert und wie Namenskonflikte gelöst werden.',
+                image: '/styling/buttons/package.jpg',
+                option: <FolderFlattener />
+            },
+            {
+                id: 'DUP',
+                title: 'Duplikate finden',
+                description: 'Finde Dateien mit gleichem Inhalt im aktuellen Ordner und allen Unterordnern. Gelöscht wird erst, wenn du Dateien auswählst und das Löschen bestätigst.',
+                image: '/styling/buttons/package.jpg',
+                option: <DuplicateFinder />
             }
         ]);
     });
@@ -76,12 +170,16 @@ export default function FileOperations() {
                             />
                         ))}
                     </div>
+                    {sanitizeProfile && <SanitizeProfileSelect value={sanitizeProfile} onChange={handleProfileChange} />}
                     <GlassCard>
                         <InfoMessage message={options?.find((option) => option.id === toolOption)?.description} />
                     </GlassCard>
                 </div>
             </GlassCard>
-            {toolOption && options?.find((option) => option.id === toolOption)?.option}
+            {/* A new profile remounts the tool, so its preview is built again */}
+            <React.Fragment key={sanitizeProfile ?? ''}>
+                {toolOption && options?.find((option) => option.id === toolOption)?.option}
+            </React.Fragment>
         </>
     );
 }
@@ -1,26 +1,47 @@
 "use client";
 
 import {
-    searchAndReplace,
+    applyRenamePlan,
+    FileSelection,
     searchAndReplacePreview,
+    SearchOptions,
+    renameReportError,
     triggerRefresh,
 } from '@/services/tauriService';
 import React, { useEffect, useState } from 'react';
 import { AnimatedButton } from '@/components/ui/AnimatedButton';
 import GlassCard from '@/components/layout/GlassCard';
 import ErrorMessage from '@/components/common/ErrorMessage';
+import SubfolderDepthInput from '@/components/common/SubfolderDepthInput';
+import FileSelectionInput from '@/components/common/FileSelectionInput';
 
 // Placeholder for your components
 export default function FileRenamer() {
     const [searchString, setSearchString] = useState('');
     const [replaceString, setReplaceString] = useState('');
+    const [subfolderDepth, setSubfolderDepth] = useState<number>(0);
+    const [selection, setSelection] = useState<FileSelection>({});
+    const [options, setOptions] = useState<SearchOptions>({
+        regex: false,
+        case_insensitive: false,
+        first_match_only: false,
+        include_extension: true,
+        all_files: false,
+    });
     const [error, setError] = useState<string | null>(null);
+    const [planId, setPlanId] = useState<string | null>(null);
 
     function handleRename() {
         setError(null);
-        searchAndReplace(searchString, replaceString)
-            .then(() => {
-                console.log("Files renamed successfully");
+        if (!planId) {
+            setError("Keine Vorschau vorhanden.");
+            return;
+        }
+        setPlanId(null);
+        applyRenamePlan(planId)
+            .then((report) => {
+                const reportError = renameReportError(report);
+                reportError ? setError(reportError) : console.log("Files renamed successfully");
             })
             .catch((err) => {
                 console.error("Failed to rename files:", err);
@@ -30,16 +51,34 @@ export default function FileRenamer() {
 
     useEffect(() => {
         function handlePreview() {
-            searchAndReplacePreview(searchString, replaceString)
-                .then(() => {
+            setError(null);
+            searchAndReplacePreview(searchString, replaceString, options, subfolderDepth, selection)
+                .then((newPlanId) => {
+                    setPlanId(newPlanId);
                     console.log("Preview successfull: Search and Replace");
                 })
                 .catch((err) => {
+                    setPlanId(null);
                     console.error("Preview failed: Search and Replace:", err);
+                    setError(String(err)); // e.g. an invalid pattern
+                    triggerRefresh();
                 });
         };
         searchString ? handlePreview() : triggerRefresh();
-    }, [searchString, replaceString]);
+    }, [searchString, replaceString, options, subfolderDepth, selection]);
+
+    function optionCheckbox(label: string, key: keyof SearchOptions) {
+        return (
+            <label className="flex flex-row items-center gap-1">
+                <input
+                    type="checkbox"
+                    checked={options[key]}
+                    onChange={(e) => setOptions({ ...options, [key]: e.target.checked })}
+                />
+                {label}
+            </label>
+        );
+    }
 
     return (
         <GlassCard title='Titel umbenennen' image='/styling/backsplash/white.jpg'>
@@ -48,16 +87,25 @@ export default function FileRenamer() {
                     type="text"
                     value={searchString}
                     onChange={(e) => setSearchString(e.target.value)}
-                    placeholder="Suchen"
-                    className="border rounded px-2 py-1"
+                    placeholder={options.regex ? "Suchen (Regex, z.B. S(\\d+)E(\\d+))" : "Suchen"}
+                    className={`border rounded px-2 py-1${options.regex ? ' font-mono' : ''}`}
                 />
                 <input
                     type="text"
                     value={replaceString}
                     onChange={(e) => setReplaceString(e.target.value)}
-                    placeholder="Ersetzen"
-                    className="border rounded px-2 py-1"
+                    placeholder={options.regex ? "Ersetzen ($1, ${name})" : "Ersetzen"}
+                    className={`border rounded px-2 py-1${options.regex ? ' font-mono' : ''}`}
                 />
+                <div className="flex flex-row flex-wrap gap-4">
+                    {optionCheckbox('Regex', 'regex')}
+                    {optionCheckbox('Groß-/Kleinschreibung ignorieren', 'case_insensitive')}
+                    {optionCheckbox('Nur erster Treffer', 'first_match_only')}
+                    {optionCheckbox('Mit Dateiendung', 'include_extension')}
+                    {optionCheckbox('Alle Dateien', 'all_files')}
+                </div>
+                <SubfolderDepthInput id="file-renamer-depth" value={subfolderDepth} onChange={setSubfolderDepth} />
+                <FileSelectionInput id="file-renamer-selection" onChange={setSelection} />
                 {error && <ErrorMessage message={error}></ErrorMessage>}
                 <div className='flex flex-row w-full justify-center'>
                     <AnimatedButton text="Umbenennen" onClick={handleRename} image='/styling/buttons/button-purple.jpg' />
@@ -0,0 +1,127 @@
+"use client";
+
+import React, { useEffect, useState } from 'react';
+import { applyRenamePlan, ConflictPolicy, FileRule, FileSelection, flattenDirectoriesPreview, FlattenOptions, renameReportError, triggerRefresh } from '@/services/tauriService';
+import { AnimatedButton } from '@/components/ui/AnimatedButton';
+import GlassCard from '@/components/layout/GlassCard';
+import ErrorMessage from '@/components/common/ErrorMessage';
+import FileSelectionInput from '@/components/common/FileSelectionInput';
+
+const RULES: { rule: FileRule, label: string }[] = [
+    { rule: 'bring', label: 'Mitnehmen' },
+    { rule: 'delete', label: 'Löschen' },
+    { rule: 'leave', label: 'Liegen lassen' },
+];
+
+const RULE_FIELDS: { field: 'sidecars' | 'samples' | 'junk', label: string }[] = [
+    { field: 'sidecars', label: 'Begleitdateien' },
+    { field: 'samples', label: 'Samples' },
+    { field: 'junk', label: 'Sonstige' },
+];
+
+const CONFLICTS: { conflict: ConflictPolicy, label: string }[] = [
+    { conflict: 'skip', label: 'Überspringen' },
+    { conflict: 'suffix', label: 'Nummer anhängen' },
+    { conflict: 'overwrite', label: 'Überschreiben' },
+];
+
+export default function FolderFlattener() {
+    const [options, setOptions] = useState<FlattenOptions>({
+        depth: 1,
+        sidecars: 'bring',
+        samples: 'leave',
+        junk: 'leave',
+        conflict: 'skip',
+    });
+    const [error, setError] = useState<string | null>(null);
+    const [selection, setSelection] = useState<FileSelection>({});
+    const [planId, setPlanId] = useState<string | null>(null);
+
+    function handleFlatten() {
+        setError(null);
+        if (!planId) {
+            setError("Keine Vorschau vorhanden.");
+            return;
+        }
+        setPlanId(null);
+        applyRenamePlan(planId)
+            .then((report) => {
+                const reportError = renameReportError(report);
+                reportError ? setError(reportError) : console.log("Folders flattened successfully");
+            })
+            .catch((err) => {
+                console.error("Failed to flatten folders:", err);
+                setError("Failed to flatten folders: " + err);
+            });
+    }
+
+    useEffect(() => {
+        function handlePreview() {
+            setPlanId(null);
+            setError(null);
+            flattenDirectoriesPreview(options, selection)
+                .then((newPlanId) => {
+                    setPlanId(newPlanId);
+                    console.log("Preview successfull: Folder Flattener");
+                })
+                .catch((err) => {
+                    console.error("Preview failed: Folder Flattener:", err);
+                    setError(String(err));
+                    triggerRefresh();
+                });
+        };
+
+        handlePreview();
+    }, [options, selection]);
+
+    return (
+        <GlassCard title='Ordner auflösen' image='/styling/backsplash/gray.jpg'>
+            <div className="flex flex-col gap-2 p-2">
+                <div className="flex flex-row items-center gap-2">
+                    <label htmlFor="flatten-depth">Tiefe</label>
+                    <input
+                        id="flatten-depth"
+                        type="number"
+                        min={1}
+                        value={options.depth}
+                        onChange={(e) => setOptions({ ...options, depth: Math.max(1, parseInt(e.target.value) || 1) })}
+                        className="border rounded px-2 py-1 w-20"
+                    />
+                </div>
+                {RULE_FIELDS.map(({ field, label }) => (
+                    <div key={field} className="flex flex-row items-center gap-2">
+                        <label htmlFor={`flatten-${field}`} className="w-32">{label}</label>
+                        <select
+                            id={`flatten-${field}`}
+                            value={options[field]}
+                            onChange={(e) => setOptions({ ...options, [field]: e.target.value as FileRule })}
+                            className="border rounded px-2 py-1"
+                        >
+                            {RULES.map(({ rule, label }) => (
+                                <option key={rule} value={rule}>{label}</option>
+                            ))}
+                        </select>
+                    </div>
+                ))}
+                <div className="flex flex-row items-center gap-2">
+                    <label htmlFor="flatten-conflict" className="w-32">Konflikte</label>
+                    <select
+                        id="flatten-conflict"
+                        value={options.conflict}
+                        onChange={(e) => setOptions({ ...options, conflict: e.target.value as ConflictPolicy })}
+                        className="border rounded px-2 py-1"
+                    >
+                        {CONFLICTS.map(({ conflict, label }) => (
+                            <option key={conflict} value={conflict}>{label}</option>
+                        ))}
+                    </select>
+                </div>
+                <FileSelectionInput id="folder-flattener-selection" onChange={setSelection} />
+                {error && <ErrorMessage message={error}></ErrorMessage>}
+                <div className='flex flex-row w-full justify-center'>
+                    <AnimatedButton text="Auflösen" onClick={handleFlatten} image='/styling/buttons/button-purple.jpg' />
+                </div>
+            </div>
+        </GlassCard>
+    );
+}
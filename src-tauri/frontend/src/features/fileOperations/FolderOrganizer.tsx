@@ -1,18 +1,21 @@
 import { useState } from "react";
 import GlassCard from "@/components/layout/GlassCard";
-import { pullFilesFromFolders, putFilesInFolders } from "@/services/tauriService";
+import { FileSelection, pullFilesFromFolders, putFilesInFolders, renameReportError } from "@/services/tauriService";
 import { AnimatedButton } from "@/components/ui/AnimatedButton";
 import ErrorMessage from "@/components/common/ErrorMessage";
+import FileSelectionInput from "@/components/common/FileSelectionInput";
 
 export default function FolderOrganizer() {
 
     const [error, setError] = useState<string | null>(null);
+    const [selection, setSelection] = useState<FileSelection>({});
 
     function handlePutFilesInFolders() {
         setError(null);
-        putFilesInFolders()
-            .then(() => {
-                console.log("Files moved successfully");
+        putFilesInFolders(selection)
+            .then((report) => {
+                const reportError = renameReportError(report);
+                reportError ? setError(reportError) : console.log("Files moved successfully");
             })
             .catch((err) => {
                 console.error("Failed to move files:", err);
@@ -22,9 +25,10 @@ export default function FolderOrganizer() {
 
     function handlePullFilesFromFolders() {
         setError(null);
-        pullFilesFromFolders()
-            .then(() => {
-                console.log("Files pulled successfully");
+        pullFilesFromFolders(selection)
+            .then((report) => {
+                const reportError = renameReportError(report);
+                reportError ? setError(reportError) : console.log("Files pulled successfully");
             })
             .catch((err) => {
                 console.error("Failed to pull files:", err);
@@ -35,7 +39,7 @@ export default function FolderOrganizer() {
     return (
         <GlassCard className='' title='Dateien Organisieren' image='/styling/backsplash/green.jpg'>
             <div className="flex flex-col gap-2 p-2">
-
+                <FileSelectionInput id="folder-organizer-selection" onChange={setSelection} />
                 {error && <ErrorMessage message={error}></ErrorMessage>}
                 <div className='flex flex-row w-full gap-2 justify-center'>
                     <AnimatedButton text="In Ordner packen" onClick={() => handlePutFilesInFolders()} image='/styling/buttons/button-blue.jpg' />
@@ -0,0 +1,86 @@
+"use client";
+
+import React, { useEffect, useState } from 'react';
+import { applyRenamePlan, FileSelection, organizeMoviesPreview, renameReportError, triggerRefresh } from '@/services/tauriService';
+import { AnimatedButton } from '@/components/ui/AnimatedButton';
+import GlassCard from '@/components/layout/GlassCard';
+import ErrorMessage from '@/components/common/ErrorMessage';
+import SubfolderDepthInput from '@/components/common/SubfolderDepthInput';
+import FileSelectionInput from '@/components/common/FileSelectionInput';
+
+export default function MovieOrganizer() {
+    const [tmdbApiKey, setTmdbApiKey] = useState<string>('');
+    const [confirmWithTmdb, setConfirmWithTmdb] = useState<boolean>(false);
+    const [subfolderDepth, setSubfolderDepth] = useState<number>(0);
+    const [selection, setSelection] = useState<FileSelection>({});
+    const [error, setError] = useState<string | null>(null);
+    const [planId, setPlanId] = useState<string | null>(null);
+
+    function handleMove() {
+        setError(null);
+        if (!planId) {
+            setError("Keine Vorschau vorhanden.");
+            return;
+        }
+        setPlanId(null);
+        applyRenamePlan(planId)
+            .then((report) => {
+                const reportError = renameReportError(report);
+                reportError ? setError(reportError) : console.log("Movies organized successfully");
+            })
+            .catch((err) => {
+                console.error("Failed to organize movies:", err);
+                setError("Failed to organize movies: " + err);
+            });
+    }
+
+    useEffect(() => {
+        function handlePreview() {
+            setPlanId(null);
+            setError(null);
+            // TMDB is only asked once the key is entered and the box is checked
+            const apiKey = confirmWithTmdb && tmdbApiKey.trim() ? tmdbApiKey.trim() : null;
+            organizeMoviesPreview(apiKey, subfolderDepth, selection)
+                .then((newPlanId) => {
+                    setPlanId(newPlanId);
+                    console.log("Preview successfull: Movie Organizer");
+                })
+                .catch((err) => {
+                    console.error("Preview failed: Movie Organizer:", err);
+                    setError(String(err));
+                    triggerRefresh();
+                });
+        };
+
+        handlePreview();
+    }, [confirmWithTmdb, tmdbApiKey, subfolderDepth, selection]);
+
+    return (
+        <GlassCard title='Filme einsortieren' image='/styling/backsplash/gray.jpg'>
+            <div className="flex flex-col gap-2 p-2">
+                <input
+                    type="password"
+                    value={tmdbApiKey}
+                    onChange={(e) => setTmdbApiKey(e.target.value)}
+                    placeholder="TMDB API-Schlüssel (optional)"
+                    className="border rounded px-2 py-1"
+                />
+                <div className="flex flex-row items-center gap-2">
+                    <input
+                        id="movie-organizer-tmdb"
+                        type="checkbox"
+                        checked={confirmWithTmdb}
+                        onChange={(e) => setConfirmWithTmdb(e.target.checked)}
+                    />
+                    <label htmlFor="movie-organizer-tmdb">Titel und Jahr mit TMDB abgleichen</label>
+                </div>
+                <SubfolderDepthInput id="movie-organizer-depth" value={subfolderDepth} onChange={setSubfolderDepth} />
+                <FileSelectionInput id="movie-organizer-selection" onChange={setSelection} />
+                {error && <ErrorMessage message={error}></ErrorMessage>}
+                <div className='flex flex-row w-full justify-center'>
+                    <AnimatedButton text="Verschieben" onClick={handleMove} image='/styling/buttons/button-purple.jpg' />
+                </div>
+            </div>
+        </GlassCard>
+    );
+}
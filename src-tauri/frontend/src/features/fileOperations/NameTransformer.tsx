@@ -0,0 +1,98 @@
+"use client";
+
+import React, { useEffect, useState } from 'react';
+import { applyRenamePlan, FileSelection, NameTransform, renameReportError, transformFileNamesPreview, triggerRefresh } from '@/services/tauriService';
+import { AnimatedButton } from '@/components/ui/AnimatedButton';
+import GlassCard from '@/components/layout/GlassCard';
+import ErrorMessage from '@/components/common/ErrorMessage';
+import SubfolderDepthInput from '@/components/common/SubfolderDepthInput';
+import FileSelectionInput from '@/components/common/FileSelectionInput';
+
+// In the order they are applied
+const TRANSFORMS: { transform: NameTransform, label: string }[] = [
+    { transform: 'separators_to_spaces', label: 'Punkte/Unterstriche zu Leerzeichen' },
+    { transform: 'nfc', label: 'Unicode NFC' },
+    { transform: 'nfkc', label: 'Unicode NFKC' },
+    { transform: 'ascii', label: 'Nur ASCII' },
+    { transform: 'lower_case', label: 'klein' },
+    { transform: 'upper_case', label: 'GROSS' },
+    { transform: 'title_case', label: 'Title Case' },
+];
+
+export default function NameTransformer() {
+    const [selected, setSelected] = useState<NameTransform[]>(['separators_to_spaces', 'title_case']);
+    const [subfolderDepth, setSubfolderDepth] = useState<number>(0);
+    const [selection, setSelection] = useState<FileSelection>({});
+    const [error, setError] = useState<string | null>(null);
+    const [planId, setPlanId] = useState<string | null>(null);
+
+    function toggleTransform(transform: NameTransform) {
+        setSelected((current) => current.includes(transform)
+            ? current.filter((selectedTransform) => selectedTransform !== transform)
+            : [...current, transform]);
+    }
+
+    function handleRename() {
+        setError(null);
+        if (!planId) {
+            setError("Keine Vorschau vorhanden.");
+            return;
+        }
+        setPlanId(null);
+        applyRenamePlan(planId)
+            .then((report) => {
+                const reportError = renameReportError(report);
+                reportError ? setError(reportError) : console.log("File names transformed successfully");
+            })
+            .catch((err) => {
+                console.error("Failed to transform file names:", err);
+                setError("Failed to transform file names: " + err);
+            });
+    }
+
+    useEffect(() => {
+        function handlePreview() {
+            setPlanId(null);
+            setError(null);
+            // Keep the order of the list, not the order of clicking
+            const transforms = TRANSFORMS.map(({ transform }) => transform).filter((transform) => selected.includes(transform));
+            transformFileNamesPreview(transforms, subfolderDepth, selection)
+                .then((newPlanId) => {
+                    setPlanId(newPlanId);
+                    console.log("Preview successfull: Name Transformer");
+                })
+                .catch((err) => {
+                    console.error("Preview failed: Name Transformer:", err);
+                    setError(String(err));
+                    triggerRefresh();
+                });
+        };
+
+        selected.length > 0 ? handlePreview() : triggerRefresh();
+    }, [selected, subfolderDepth, selection]);
+
+    return (
+        <GlassCard title='Schreibweise anpassen' image='/styling/backsplash/white.jpg'>
+            <div className="flex flex-col gap-2 p-2">
+                <div className="flex flex-row flex-wrap gap-4">
+                    {TRANSFORMS.map(({ transform, label }) => (
+                        <label key={transform} className="flex flex-row items-center gap-1">
+                            <input
+                                type="checkbox"
+                                checked={selected.includes(transform)}
+                                onChange={() => toggleTransform(transform)}
+                            />
+                            {label}
+                        </label>
+                    ))}
+                </div>
+                <SubfolderDepthInput id="name-transformer-depth" value={subfolderDepth} onChange={setSubfolderDepth} />
+                <FileSelectionInput id="name-transformer-selection" onChange={setSelection} />
+                {error && <ErrorMessage message={error}></ErrorMessage>}
+                <div className='flex flex-row w-full justify-center'>
+                    <AnimatedButton text="Umbenennen" onClick={handleRename} image='/styling/buttons/button-purple.jpg' />
+                </div>
+            </div>
+        </GlassCard>
+    );
+}
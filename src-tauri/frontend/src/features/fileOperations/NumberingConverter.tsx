@@ -0,0 +1,95 @@
+"use client";
+
+import React, { useEffect, useState } from 'react';
+import { applyRenamePlan, convertAbsoluteNumberingPreview, FileSelection, focusMainWindow, NumberingConversion, openEpisodeTitleWindow, renameReportError, SeasonedEpisodes, triggerRefresh } from '@/services/tauriService';
+import { listen } from '@tauri-apps/api/event';
+import { AnimatedButton } from '@/components/ui/AnimatedButton';
+import GlassCard from '@/components/layout/GlassCard';
+import ErrorMessage from '@/components/common/ErrorMessage';
+import SubfolderDepthInput from '@/components/common/SubfolderDepthInput';
+import FileSelectionInput from '@/components/common/FileSelectionInput';
+
+export default function NumberingConverter() {
+    const [seasons, setSeasons] = useState<SeasonedEpisodes[]>([]);
+    const [conversion, setConversion] = useState<NumberingConversion>('to_seasonal');
+    const [subfolderDepth, setSubfolderDepth] = useState<number>(0);
+    const [selection, setSelection] = useState<FileSelection>({});
+    const [error, setError] = useState<string | null>(null);
+    const [planId, setPlanId] = useState<string | null>(null);
+
+    useEffect(() => {
+        // The season boundaries come from the episode title window
+        const unlisten = listen<{ seasons: SeasonedEpisodes[] }>('send_show', async (event) => {
+            setSeasons(event.payload.seasons);
+            focusMainWindow();
+        });
+
+        return () => {
+            unlisten.then((dispose) => dispose());
+        };
+    }, []);
+
+    function handleConvert() {
+        setError(null);
+        if (!planId) {
+            setError("Keine Vorschau vorhanden.");
+            return;
+        }
+        setPlanId(null);
+        applyRenamePlan(planId)
+            .then((report) => {
+                const reportError = renameReportError(report);
+                reportError ? setError(reportError) : console.log("Episode numbering converted successfully");
+            })
+            .catch((err) => {
+                console.error("Failed to convert episode numbering:", err);
+                setError("Failed to convert episode numbering: " + err);
+            });
+    }
+
+    useEffect(() => {
+        function handlePreview() {
+            setPlanId(null);
+            setError(null);
+            convertAbsoluteNumberingPreview(seasons, conversion, subfolderDepth, selection)
+                .then((newPlanId) => {
+                    setPlanId(newPlanId);
+                    console.log("Preview successfull: Numbering Converter");
+                })
+                .catch((err) => {
+                    console.error("Preview failed: Numbering Converter:", err);
+                    setError(String(err));
+                    triggerRefresh();
+                });
+        };
+
+        seasons.length > 0 ? handlePreview() : triggerRefresh();
+    }, [seasons, conversion, subfolderDepth, selection]);
+
+    return (
+        <GlassCard title='Absolute Nummerierung' image='/styling/backsplash/gray.jpg'>
+            <div className="flex flex-col gap-2 p-2">
+                <select
+                    value={conversion}
+                    onChange={(e) => setConversion(e.target.value as NumberingConversion)}
+                    className="border rounded px-2 py-1"
+                >
+                    <option value="to_seasonal">Absolut zu Staffeln (Show - 37 zu S02E13)</option>
+                    <option value="to_absolute">Staffeln zu absolut (S02E13 zu Show - 37)</option>
+                </select>
+                <SubfolderDepthInput id="numbering-converter-depth" value={subfolderDepth} onChange={setSubfolderDepth} />
+                <FileSelectionInput id="numbering-converter-selection" onChange={setSelection} />
+                <span className="text-sm text-inactive">
+                    {seasons.length > 0
+                        ? seasons.filter((season) => season.season > 0).map((season) => `S${String(season.season).padStart(2, '0')}: ${season.titles.length} Episoden`).join(', ')
+                        : 'Lade die Staffeln und drücke "Alle Staffeln übernehmen".'}
+                </span>
+                {error && <ErrorMessage message={error}></ErrorMessage>}
+                <div className='flex flex-row w-full gap-2 justify-center'>
+                    <AnimatedButton text="Staffeln laden" onClick={openEpisodeTitleWindow} image='/styling/buttons/button-blue.jpg' />
+                    <AnimatedButton text="Umwandeln" onClick={handleConvert} image='/styling/buttons/button-purple.jpg' />
+                </div>
+            </div>
+        </GlassCard>
+    );
+}
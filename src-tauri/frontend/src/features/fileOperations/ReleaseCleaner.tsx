@@ -0,0 +1,138 @@
+"use client";
+
+import React, { useEffect, useState } from 'react';
+import { applyRenamePlan, cleanReleaseNamesPreview, CleanupRules, FileSelection, getCleanupRules, ReleasePart, renameReportError, triggerRefresh, updateCleanupRules } from '@/services/tauriService';
+import { AnimatedButton } from '@/components/ui/AnimatedButton';
+import GlassCard from '@/components/layout/GlassCard';
+import ErrorMessage from '@/components/common/ErrorMessage';
+import SubfolderDepthInput from '@/components/common/SubfolderDepthInput';
+import FileSelectionInput from '@/components/common/FileSelectionInput';
+
+// Show title and episode are always kept
+const RELEASE_PARTS: { part: ReleasePart, label: string }[] = [
+    { part: 'episode_title', label: 'Episodentitel' },
+    { part: 'resolution', label: 'Auflösung' },
+    { part: 'source', label: 'Quelle' },
+    { part: 'codec', label: 'Codec' },
+    { part: 'group', label: 'Gruppe' },
+    { part: 'crc', label: 'CRC' },
+];
+
+export default function ReleaseCleaner() {
+    const [rules, setRules] = useState<CleanupRules | null>(null);
+    const [removeWords, setRemoveWords] = useState<string>('');
+    const [subfolderDepth, setSubfolderDepth] = useState<number>(0);
+    const [selection, setSelection] = useState<FileSelection>({});
+    const [error, setError] = useState<string | null>(null);
+    const [saved, setSaved] = useState<string>('');
+    const [planId, setPlanId] = useState<string | null>(null);
+
+    useEffect(() => {
+        getCleanupRules()
+            .then((savedRules) => {
+                setRules(savedRules);
+                setRemoveWords(savedRules.remove_words.join(', '));
+            })
+            .catch((err) => setError(String(err)));
+    }, []);
+
+    function togglePart(part: ReleasePart) {
+        if (!rules) {
+            return;
+        }
+        const keep = rules.keep.includes(part)
+            ? rules.keep.filter((kept) => kept !== part)
+            : [...rules.keep, part];
+        setRules({ ...rules, keep });
+    }
+
+    function handleSave() {
+        if (!rules) {
+            return;
+        }
+        updateCleanupRules(rules)
+            .then(() => {
+                setSaved('Gespeichert!');
+                setTimeout(() => setSaved(''), 2000);
+            })
+            .catch((err) => setError(String(err)));
+    }
+
+    function handleRename() {
+        setError(null);
+        if (!planId) {
+            setError("Keine Vorschau vorhanden.");
+            return;
+        }
+        setPlanId(null);
+        applyRenamePlan(planId)
+            .then((report) => {
+                const reportError = renameReportError(report);
+                reportError ? setError(reportError) : console.log("Release names cleaned successfully");
+            })
+            .catch((err) => {
+                console.error("Failed to clean release names:", err);
+                setError("Failed to clean release names: " + err);
+            });
+    }
+
+    // Keep the word list in the rules, separated by commas
+    useEffect(() => {
+        setRules((current) => current && {
+            ...current,
+            remove_words: removeWords.split(',').map((word) => word.trim()).filter((word) => word),
+        });
+    }, [removeWords]);
+
+    useEffect(() => {
+        function handlePreview() {
+            setPlanId(null);
+            setError(null);
+            cleanReleaseNamesPreview(rules, subfolderDepth, selection)
+                .then((newPlanId) => {
+                    setPlanId(newPlanId);
+                    console.log("Preview successfull: Release Cleaner");
+                })
+                .catch((err) => {
+                    console.error("Preview failed: Release Cleaner:", err);
+                    setError(String(err));
+                    triggerRefresh();
+                });
+        };
+
+        rules && handlePreview();
+    }, [rules, subfolderDepth, selection]);
+
+    return (
+        <GlassCard title='Release-Namen bereinigen' image='/styling/backsplash/white.jpg'>
+            <div className="flex flex-col gap-2 p-2">
+                <div className="flex flex-row flex-wrap gap-4">
+                    {RELEASE_PARTS.map(({ part, label }) => (
+                        <label key={part} className="flex flex-row items-center gap-1">
+                            <input
+                                type="checkbox"
+                                checked={rules?.keep.includes(part) ?? false}
+                                onChange={() => togglePart(part)}
+                            />
+                            {label}
+                        </label>
+                    ))}
+                </div>
+                <input
+                    type="text"
+                    value={removeWords}
+                    onChange={(e) => setRemoveWords(e.target.value)}
+                    placeholder="Wörter entfernen (z.B. Dual, Audio, Uncensored)"
+                    className="border rounded px-2 py-1"
+                />
+                <SubfolderDepthInput id="release-cleaner-depth" value={subfolderDepth} onChange={setSubfolderDepth} />
+                <FileSelectionInput id="release-cleaner-selection" onChange={setSelection} />
+                {error && <ErrorMessage message={error}></ErrorMessage>}
+                <div className='flex flex-row w-full gap-2 justify-center'>
+                    <AnimatedButton text={saved ? saved : "Regeln speichern"} onClick={handleSave} image='/styling/buttons/button-blue.jpg' />
+                    <AnimatedButton text="Umbenennen" onClick={handleRename} image='/styling/buttons/button-purple.jpg' />
+                </div>
+            </div>
+        </GlassCard>
+    );
+}
@@ -0,0 +1,33 @@
+import { SanitizeProfile } from '@/services/tauriService';
+
+type SanitizeProfileSelectProps = {
+    value: SanitizeProfile;
+    onChange: (profile: SanitizeProfile) => void;
+};
+
+const PROFILES: { profile: SanitizeProfile, label: string }[] = [
+    { profile: 'windows', label: 'Windows (NTFS)' },
+    { profile: 'exfat', label: 'exFAT' },
+    { profile: 'smb', label: 'Netzwerkfreigabe (SMB)' },
+    { profile: 'posix', label: 'Linux / macOS' },
+];
+
+// The file system the renamed files have to work on, e.g. the drive they are copied to
+export default function SanitizeProfileSelect({ value, onChange }: SanitizeProfileSelectProps) {
+    return (
+        <div className="flex flex-row items-center gap-2">
+            <label htmlFor="sanitize-profile">Zieldateisystem</label>
+            <select
+                id="sanitize-profile"
+                value={value}
+                onChange={(e) => onChange(e.target.value as SanitizeProfile)}
+                title="Ungültige Zeichen, reservierte Namen und zu lange Namen werden in der Vorschau angezeigt und angepasst"
+                className="border rounded px-2 py-1"
+            >
+                {PROFILES.map(({ profile, label }) => (
+                    <option key={profile} value={profile}>{label}</option>
+                ))}
+            </select>
+        </div>
+    );
+}
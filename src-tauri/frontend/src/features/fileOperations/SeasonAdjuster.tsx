@@ -0,0 +1,130 @@
+"use client";
+
+import React, { useEffect, useState } from 'react';
+import { adjustSeasonNumbersPreview, applyRenamePlan, EpisodeRangeRemap, FileSelection, remapEpisodeRangePreview, renameReportError, triggerRefresh } from '@/services/tauriService';
+import { AnimatedButton } from '@/components/ui/AnimatedButton';
+import GlassCard from '@/components/layout/GlassCard';
+import ErrorMessage from '@/components/common/ErrorMessage';
+import SubfolderDepthInput from '@/components/common/SubfolderDepthInput';
+import FileSelectionInput from '@/components/common/FileSelectionInput';
+
+type SeasonMode = 'shift' | 'range';
+
+export default function SeasonAdjuster() {
+    const [mode, setMode] = useState<SeasonMode>('shift');
+    const [adjustmentValue, setAdjustmentValue] = useState<number | null>(null);
+    const [remap, setRemap] = useState<EpisodeRangeRemap>({
+        season: 2,
+        first_episode: 13,
+        last_episode: 24,
+        target_season: 3,
+        target_first_episode: 1,
+    });
+    const [subfolderDepth, setSubfolderDepth] = useState<number>(0);
+    const [error, setError] = useState<string | null>(null);
+    const [selection, setSelection] = useState<FileSelection>({});
+    const [planId, setPlanId] = useState<string | null>(null);
+
+    function handleApply() {
+        setError(null);
+        if (!planId) {
+            setError("Keine Vorschau vorhanden.");
+            return;
+        }
+
+        setPlanId(null);
+        applyRenamePlan(planId)
+            .then((report) => {
+                const reportError = renameReportError(report);
+                if (reportError) {
+                    setError(reportError);
+                    return;
+                }
+                console.log("Season numbers adjusted successfully");
+                setAdjustmentValue(null);
+            })
+            .catch((err) => {
+                console.error("Failed to adjust season numbers:", err);
+                setError("Failed to adjust season numbers: " + err);
+            });
+    }
+
+    useEffect(() => {
+        function handlePreview() {
+            setPlanId(null);
+            setError(null);
+            const preview = mode === 'shift'
+                ? (adjustmentValue ? adjustSeasonNumbersPreview(adjustmentValue, subfolderDepth, selection) : null)
+                : remapEpisodeRangePreview(remap, subfolderDepth, selection);
+
+            if (!preview) {
+                triggerRefresh();
+                return;
+            }
+            preview
+                .then((newPlanId) => {
+                    setPlanId(newPlanId);
+                    console.log("Preview successfull: Season Adjuster");
+                })
+                .catch((err) => {
+                    console.error("Preview failed: Season Adjuster:", err);
+                    setError(String(err));
+                    triggerRefresh();
+                });
+        };
+        handlePreview()
+    }, [mode, adjustmentValue, remap, subfolderDepth, selection]);
+
+    function remapInput(label: string, key: keyof EpisodeRangeRemap) {
+        return (
+            <label className="flex flex-col text-sm">
+                {label}
+                <input
+                    type="number"
+                    min={0}
+                    value={remap[key]}
+                    onChange={(e) => setRemap({ ...remap, [key]: Number(e.target.value) })}
+                    className="border rounded px-2 py-1 w-20"
+                />
+            </label>
+        );
+    }
+
+    return (
+        <GlassCard title='Staffelnummer anpassen' image='/styling/backsplash/gray.jpg'>
+            <div className="flex flex-col gap-2 p-2">
+                <select
+                    value={mode}
+                    onChange={(e) => setMode(e.target.value as SeasonMode)}
+                    className="border rounded px-2 py-1"
+                >
+                    <option value="shift">Alle Staffeln verschieben</option>
+                    <option value="range">Episoden in andere Staffel verschieben</option>
+                </select>
+                {mode === 'shift' ? (
+                    <input
+                        type="number"
+                        value={adjustmentValue !== null ? adjustmentValue : ''}
+                        onChange={(e) => setAdjustmentValue(Number(e.target.value))}
+                        placeholder="Gib eine Zahl ein (z.B. 1 oder -1)"
+                        className="border rounded px-2 py-1"
+                    />
+                ) : (
+                    <div className="flex flex-row flex-wrap gap-2 items-end">
+                        {remapInput('Staffel', 'season')}
+                        {remapInput('Von Episode', 'first_episode')}
+                        {remapInput('Bis Episode', 'last_episode')}
+                        {remapInput('Neue Staffel', 'target_season')}
+                        {remapInput('Ab Episode', 'target_first_episode')}
+                    </div>
+                )}
+                <SubfolderDepthInput id="season-adjuster-depth" value={subfolderDepth} onChange={setSubfolderDepth} />
+                <FileSelectionInput id="season-adjuster-selection" onChange={setSelection} />
+                {error && <ErrorMessage message={error}></ErrorMessage>}
+                <div className='flex flex-row w-full gap-2 justify-center'>
+                    <AnimatedButton text="Anpassen" onClick={handleApply} image='/styling/buttons/button-purple.jpg' />
+                </div>
+            </div>
+        </GlassCard>
+    );
+}
@@ -0,0 +1,155 @@
+"use client";
+
+import React, { useEffect, useState } from 'react';
+import { applyRenamePlan, FileOrder, FileSelection, listFilesInCurrentDirectory, numberFilesPreview, renameReportError, SequentialNumbering, triggerRefresh } from '@/services/tauriService';
+import { AnimatedButton } from '@/components/ui/AnimatedButton';
+import GlassCard from '@/components/layout/GlassCard';
+import ErrorMessage from '@/components/common/ErrorMessage';
+import SubfolderDepthInput from '@/components/common/SubfolderDepthInput';
+import FileSelectionInput from '@/components/common/FileSelectionInput';
+
+const ORDERS: { order: FileOrder, label: string }[] = [
+    { order: 'natural', label: 'Natürlich (Track 2 vor Track 10)' },
+    { order: 'modified', label: 'Änderungsdatum' },
+    { order: 'size', label: 'Größe' },
+    { order: 'custom', label: 'Eigene Reihenfolge' },
+];
+
+const NUMBER_FIELDS: { field: 'season' | 'start' | 'step' | 'padding', label: string, min: number }[] = [
+    { field: 'season', label: 'Staffel', min: 0 },
+    { field: 'start', label: 'Start', min: 0 },
+    { field: 'step', label: 'Schritt', min: 1 },
+    { field: 'padding', label: 'Stellen', min: 1 },
+];
+
+export default function SequentialNumberer() {
+    const [numbering, setNumbering] = useState<SequentialNumbering>({
+        season: 1,
+        start: 1,
+        step: 1,
+        padding: 2,
+        order: 'natural',
+        custom_order: [],
+        show_name: null,
+    });
+    const [subfolderDepth, setSubfolderDepth] = useState<number>(0);
+    const [selection, setSelection] = useState<FileSelection>({});
+    const [error, setError] = useState<string | null>(null);
+    const [planId, setPlanId] = useState<string | null>(null);
+
+    // The video files of the current folder are the starting point of a custom order
+    useEffect(() => {
+        if (numbering.order !== 'custom' || numbering.custom_order.length > 0) {
+            return;
+        }
+        listFilesInCurrentDirectory()
+            .then((files) => {
+                const names = files.filter((file) => file.is_video).map((file) => file.name);
+                names.sort((a, b) => a.localeCompare(b, undefined, { numeric: true, sensitivity: 'base' }));
+                setNumbering((current) => ({ ...current, custom_order: names }));
+            })
+            .catch((err) => setError(String(err)));
+    }, [numbering.order, numbering.custom_order.length]);
+
+    function moveFile(index: number, offset: number) {
+        const target = index + offset;
+        if (target < 0 || target >= numbering.custom_order.length) {
+            return;
+        }
+        const customOrder = [...numbering.custom_order];
+        [customOrder[index], customOrder[target]] = [customOrder[target], customOrder[index]];
+        setNumbering({ ...numbering, custom_order: customOrder });
+    }
+
+    function handleRename() {
+        setError(null);
+        if (!planId) {
+            setError("Keine Vorschau vorhanden.");
+            return;
+        }
+        setPlanId(null);
+        applyRenamePlan(planId)
+            .then((report) => {
+                const reportError = renameReportError(report);
+                reportError ? setError(reportError) : console.log("Files numbered successfully");
+            })
+            .catch((err) => {
+                console.error("Failed to number files:", err);
+                setError("Failed to number files: " + err);
+            });
+    }
+
+    useEffect(() => {
+        function handlePreview() {
+            setPlanId(null);
+            setError(null);
+            numberFilesPreview(numbering, subfolderDepth, selection)
+                .then((newPlanId) => {
+                    setPlanId(newPlanId);
+                    console.log("Preview successfull: Sequential Numberer");
+                })
+                .catch((err) => {
+                    console.error("Preview failed: Sequential Numberer:", err);
+                    setError(String(err));
+                    triggerRefresh();
+                });
+        };
+
+        handlePreview();
+    }, [numbering, subfolderDepth, selection]);
+
+    return (
+        <GlassCard title='Dateien durchnummerieren' image='/styling/backsplash/gray.jpg'>
+            <div className="flex flex-col gap-2 p-2">
+                <div className="flex flex-row flex-wrap gap-4">
+                    {NUMBER_FIELDS.map(({ field, label, min }) => (
+                        <div key={field} className="flex flex-row items-center gap-2">
+                            <label htmlFor={`sequential-${field}`}>{label}</label>
+                            <input
+                                id={`sequential-${field}`}
+                                type="number"
+                                min={min}
+                                value={numbering[field]}
+                                onChange={(e) => setNumbering({ ...numbering, [field]: Math.max(min, parseInt(e.target.value) || 0) })}
+                                className="border rounded px-2 py-1 w-20"
+                            />
+                        </div>
+                    ))}
+                </div>
+                <input
+                    type="text"
+                    value={numbering.show_name ?? ''}
+                    onChange={(e) => setNumbering({ ...numbering, show_name: e.target.value || null })}
+                    placeholder="Serienname (leer lassen um den alten Namen zu behalten)"
+                    className="border rounded px-2 py-1"
+                />
+                <select
+                    value={numbering.order}
+                    onChange={(e) => setNumbering({ ...numbering, order: e.target.value as FileOrder })}
+                    className="border rounded px-2 py-1"
+                >
+                    {ORDERS.map(({ order, label }) => (
+                        <option key={order} value={order}>{label}</option>
+                    ))}
+                </select>
+                {numbering.order === 'custom' && (
+                    <ul className="flex flex-col gap-1 font-mono text-sm">
+                        {numbering.custom_order.map((name, index) => (
+                            <li key={name} className="flex flex-row items-center gap-2">
+                                <button onClick={() => moveFile(index, -1)} disabled={index === 0} className="border rounded px-1">▲</button>
+                                <button onClick={() => moveFile(index, 1)} disabled={index === numbering.custom_order.length - 1} className="border rounded px-1">▼</button>
+                                <span>{name}</span>
+                            </li>
+                        ))}
+                    </ul>
+                )}
+                <SubfolderDepthInput id="sequential-numberer-depth" value={subfolderDepth} onChange={setSubfolderDepth} />
+                <FileSelectionInput id="sequential-numberer-selection" onChange={setSelection} />
+                {error && <ErrorMessage message={error}></ErrorMessage>}
+                <div className='flex flex-row w-full justify-center'>
+                    <AnimatedButton text="Nummerieren" onClick={handleRename} image='/styling/buttons/button-purple.jpg' />
+                </div>
+            </div>
+        </GlassCard>
+    );
+}
@@ -0,0 +1,110 @@
+"use client";
+
+import React, { useEffect, useState } from 'react';
+import { listen } from '@tauri-apps/api/event';
+import { applyRenamePlan, FileSelection, organizeIntoShowFoldersPreview, renameReportError, SeasonedEpisodes, SeasonedEpisodesDetails, triggerRefresh } from '@/services/tauriService';
+import { AnimatedButton } from '@/components/ui/AnimatedButton';
+import GlassCard from '@/components/layout/GlassCard';
+import ErrorMessage from '@/components/common/ErrorMessage';
+import SubfolderDepthInput from '@/components/common/SubfolderDepthInput';
+import FileSelectionInput from '@/components/common/FileSelectionInput';
+
+export default function ShowOrganizer() {
+    const [showName, setShowName] = useState<string>('');
+    const [year, setYear] = useState<string>('');
+    const [showId, setShowId] = useState<number>(0);
+    const [seasons, setSeasons] = useState<SeasonedEpisodes[]>([]);
+    const [subfolderDepth, setSubfolderDepth] = useState<number>(0);
+    const [selection, setSelection] = useState<FileSelection>({});
+    const [error, setError] = useState<string | null>(null);
+    const [planId, setPlanId] = useState<string | null>(null);
+
+    // A show sent from the episode title window brings its name, year, titles and seasons
+    useEffect(() => {
+        const unlisten = listen<{ showId: number | null, name: string | null, year: string | null, seasons: SeasonedEpisodes[] }>('send_show', (event) => {
+            setShowId(event.payload.showId ?? 0);
+            setShowName(event.payload.name ?? '');
+            setYear(event.payload.year ?? '');
+            setSeasons(event.payload.seasons);
+        });
+
+        return () => {
+            unlisten.then((fn) => fn());
+        };
+    }, []);
+
+    function handleMove() {
+        setError(null);
+        if (!planId) {
+            setError("Keine Vorschau vorhanden.");
+            return;
+        }
+        setPlanId(null);
+        applyRenamePlan(planId)
+            .then((report) => {
+                const reportError = renameReportError(report);
+                reportError ? setError(reportError) : console.log("Files organized successfully");
+            })
+            .catch((err) => {
+                console.error("Failed to organize files:", err);
+                setError("Failed to organize files: " + err);
+            });
+    }
+
+    useEffect(() => {
+        function handlePreview() {
+            setPlanId(null);
+            setError(null);
+            // Without a name the show name is taken from the file names
+            const showDetails: SeasonedEpisodesDetails | null = showName.trim()
+                ? { id: showId, name: showName.trim(), premiered_year: year.trim() || null, episodes_by_season: seasons }
+                : null;
+            organizeIntoShowFoldersPreview(showDetails, subfolderDepth, selection)
+                .then((newPlanId) => {
+                    setPlanId(newPlanId);
+                    console.log("Preview successfull: Show Organizer");
+                })
+                .catch((err) => {
+                    console.error("Preview failed: Show Organizer:", err);
+                    setError(String(err));
+                    triggerRefresh();
+                });
+        };
+
+        handlePreview();
+    }, [showName, year, showId, seasons, subfolderDepth, selection]);
+
+    return (
+        <GlassCard title='Serienstruktur' image='/styling/backsplash/green.jpg'>
+            <div className="flex flex-col gap-2 p-2">
+                <div className="flex flex-row gap-2">
+                    <input
+                        type="text"
+                        value={showName}
+                        onChange={(e) => setShowName(e.target.value)}
+                        placeholder="Serienname (leer lassen um ihn aus den Dateinamen zu lesen)"
+                        className="border rounded px-2 py-1 flex-grow"
+                    />
+                    <input
+                        type="text"
+                        value={year}
+                        onChange={(e) => setYear(e.target.value)}
+                        placeholder="Jahr"
+                        className="border rounded px-2 py-1 w-24"
+                    />
+                </div>
+                {seasons.length > 0 && (
+                    <span className="text-sm text-inactive">
+                        {seasons.length} Staffel(n) geladen, Titel und absolute Nummern werden übernommen.
+                    </span>
+                )}
+                <SubfolderDepthInput id="show-organizer-depth" value={subfolderDepth} onChange={setSubfolderDepth} />
+                <FileSelectionInput id="show-organizer-selection" onChange={setSelection} />
+                {error && <ErrorMessage message={error}></ErrorMessage>}
+                <div className='flex flex-row w-full justify-center'>
+                    <AnimatedButton text="Verschieben" onClick={handleMove} image='/styling/buttons/button-purple.jpg' />
+                </div>
+            </div>
+        </GlassCard>
+    );
+}
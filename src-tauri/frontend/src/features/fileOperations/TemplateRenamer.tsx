@@ -0,0 +1,94 @@
+"use client";
+
+import React, { useEffect, useState } from 'react';
+import { applyRenamePlan, FileSelection, listFilesInCurrentDirectory, renameReportError, renameWithTemplatePreview, TemplateSample, triggerRefresh, validateFilenameTemplate } from '@/services/tauriService';
+import { AnimatedButton } from '@/components/ui/AnimatedButton';
+import GlassCard from '@/components/layout/GlassCard';
+import ErrorMessage from '@/components/common/ErrorMessage';
+import SubfolderDepthInput from '@/components/common/SubfolderDepthInput';
+import FileSelectionInput from '@/components/common/FileSelectionInput';
+
+const DEFAULT_TEMPLATE = '{show} - S{season:02}E{episode:02} - {title|"Episode " episode}.{ext}';
+
+export default function TemplateRenamer() {
+    const [template, setTemplate] = useState<string>(DEFAULT_TEMPLATE);
+    const [samples, setSamples] = useState<TemplateSample[]>([]);
+    const [error, setError] = useState<string | null>(null);
+    const [subfolderDepth, setSubfolderDepth] = useState<number>(0);
+    const [selection, setSelection] = useState<FileSelection>({});
+    const [planId, setPlanId] = useState<string | null>(null);
+
+    function handleRename() {
+        setError(null);
+        if (!planId) {
+            setError("Keine Vorschau vorhanden.");
+            return;
+        }
+        setPlanId(null);
+        applyRenamePlan(planId)
+            .then((report) => {
+                const reportError = renameReportError(report);
+                reportError ? setError(reportError) : console.log("Files renamed successfully");
+            })
+            .catch((err) => {
+                console.error("Failed to rename files:", err);
+                setError("Failed to rename files: " + err);
+            });
+    }
+
+    useEffect(() => {
+        async function handlePreview() {
+            setPlanId(null);
+            setError(null);
+            try {
+                // Check the template against the first video files before previewing all of them
+                const files = await listFilesInCurrentDirectory();
+                const sampleNames = files.filter((file) => file.is_video).slice(0, 3).map((file) => file.name);
+                setSamples(await validateFilenameTemplate(template, sampleNames, null));
+
+                setPlanId(await renameWithTemplatePreview(template, null, undefined, subfolderDepth, selection));
+                console.log("Preview successfull: Template Renamer");
+            } catch (err) {
+                console.error("Preview failed: Template Renamer:", err);
+                setSamples([]);
+                setError(String(err));
+                triggerRefresh();
+            }
+        }
+
+        template ? handlePreview() : triggerRefresh();
+    }, [template, subfolderDepth, selection]);
+
+    return (
+        <GlassCard title='Mit Vorlage umbenennen' image='/styling/backsplash/white.jpg'>
+            <div className="flex flex-col gap-2 p-2">
+                <input
+                    type="text"
+                    value={template}
+                    onChange={(e) => setTemplate(e.target.value)}
+                    placeholder={DEFAULT_TEMPLATE}
+                    className="border rounded px-2 py-1 font-mono"
+                />
+                <span className="text-sm text-inactive">
+                    Variablen: show, year, season, episode, episode_end, absolute, air_date, title, group, resolution, original, ext.
+                    Format mit {'{episode:02}'} oder {'{title:upper}'}, Ersatzwerte mit {'{title|"Episode " episode}'}. Ohne {'{ext}'} bleibt die Dateiendung erhalten.
+                </span>
+                {samples.length > 0 && (
+                    <ul className="text-sm font-mono">
+                        {samples.map((sample, index) => (
+                            <li key={index} className={sample.error ? 'text-error' : ''}>
+                                {sample.rendered_name ?? sample.error}
+                            </li>
+                        ))}
+                    </ul>
+                )}
+                <SubfolderDepthInput id="template-renamer-depth" value={subfolderDepth} onChange={setSubfolderDepth} />
+                <FileSelectionInput id="template-renamer-selection" onChange={setSelection} />
+                {error && <ErrorMessage message={error}></ErrorMessage>}
+                <div className='flex flex-row w-full justify-center'>
+                    <AnimatedButton text="Umbenennen" onClick={handleRename} image='/styling/buttons/button-purple.jpg' />
+                </div>
+            </div>
+        </GlassCard>
+    );
+}
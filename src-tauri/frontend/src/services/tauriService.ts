@@ -61,42 +61,188 @@ export type DriveInfo = {
     is_offline: boolean;
 };
 
+// Rename Reports
+
+export interface RenameOperation {
+    from: string;
+    to: string;
+}
+
+export interface RenameReport {
+    renamed: RenameOperation[];
+    created_dirs: string[];
+    removed_dirs: string[];
+    deleted: string[];
+    failed: { path: string; error: string } | null;
+    rolled_back: RenameOperation[];
+    rollback_errors: string[];
+    warnings: string[]; // E.g. the journal could not be written, the files are renamed anyway
+}
+
+export interface PreviewEntry {
+    original_path: string;
+    original_name: string;
+    proposed_name: string;
+    proposed_path: string; // In another folder when the file is moved
+    status: 'changed' | 'unchanged' | 'skipped' | 'conflict' | 'deleted'; // Deleted files can not be restored by undo
+    reason: string | null;
+    notes: string[];
+    highlights: [number, number][]; // Spans of original_name, e.g. search matches
+    companion_of: string | null;
+}
+
+// Returns an error message if the rename failed and was rolled back, or finished with warnings
+export const renameReportError = (report: RenameReport): string | null => {
+    if (!report.failed) {
+        return report.warnings.length > 0
+            ? 'Files were renamed, but: ' + report.warnings.join('; ')
+            : null;
+    }
+    let message = `Failed to rename ${report.failed.path}: ${report.failed.error}. ${report.rolled_back.length} file(s) were restored.`;
+    if (report.rollback_errors.length > 0) {
+        message += ' Restoring failed for: ' + report.rollback_errors.join('; ');
+    }
+    return message;
+};
+
+// Apply a previewed rename plan, the preview commands return its ID
+
+export const applyRenamePlan = async (planId: string): Promise<RenameReport> => {
+    return invoke('apply_rename_plan', { planId });
+};
+
+// Sanitize Profile
+
+// The file system every rename makes its names valid for
+export type SanitizeProfile = 'posix' | 'windows' | 'exfat' | 'smb';
+
+export const getSanitizeProfile = async (): Promise<SanitizeProfile> => {
+    return invoke('get_sanitize_profile');
+};
+
+export const setSanitizeProfile = async (profile: SanitizeProfile): Promise<void> => {
+    return invoke('set_sanitize_profile', { profile });
+};
+
+// File Selection
+
+// Which files an operation touches, every file of the folder without it.
+// paths: files or folders picked in the explorer. include / exclude: globs like "*S01E0[1-5]*"
+export interface FileSelection {
+    paths?: string[];
+    include?: string[];
+    exclude?: string[];
+}
+
+// Undo / Redo
+
+export const undoLastOperation = async (): Promise<RenameReport> => {
+    return invoke('undo_last_operation');
+};
+
+export const redoOperation = async (): Promise<RenameReport> => {
+    return invoke('redo_operation');
+};
+
 // Get Current Episode Names
 
-export const getCurrentEpisodeNames = async (): Promise<string[]> => {
-    return invoke('get_current_episode_names');
+export const getCurrentEpisodeNames = async (titleSeparator?: string): Promise<SeasonedEpisodes[]> => {
+    return invoke('get_current_episode_names', { titleSeparator });
 };
 
 // Adjust Episode Numbers
 
-export const adjustEpisodeNumbers = async (adjustmentValue: number): Promise<void> => {
-    return invoke('adjust_episode_numbers', { adjustmentValue });
+// subfolderDepth: how many levels of subfolders are processed as well, 0 or undefined for only the current folder
+export const adjustEpisodeNumbersPreview = async (adjustmentValue: number, subfolderDepth?: number, selection?: FileSelection): Promise<string> => {
+    return invoke('adjust_episode_numbers_preview', { adjustmentValue, subfolderDepth, selection });
 };
 
-export const adjustEpisodeNumbersPreview = async (adjustmentValue: number): Promise<void> => {
-    return invoke('adjust_episode_numbers_preview', { adjustmentValue });
+// Adjust Season Numbers
+
+export const adjustSeasonNumbersPreview = async (adjustmentValue: number, subfolderDepth?: number, selection?: FileSelection): Promise<string> => {
+    return invoke('adjust_season_numbers_preview', { adjustmentValue, subfolderDepth, selection });
+};
+
+export interface EpisodeRangeRemap {
+    season: number;
+    first_episode: number;
+    last_episode: number;
+    target_season: number;
+    target_first_episode: number;
+}
+
+export const remapEpisodeRangePreview = async (remap: EpisodeRangeRemap, subfolderDepth?: number, selection?: FileSelection): Promise<string> => {
+    return invoke('remap_episode_range_preview', { remap, subfolderDepth, selection });
 };
 
 // Rename Files ( search and replace )
 
-export const searchAndReplace = async (targetStr: string, replacementStr: string): Promise<void> => {
-    return invoke('search_and_replace', { targetStr: targetStr, replacementStr: replacementStr });
+// With regex, $1 and ${name} in the replacement refer to groups of the pattern
+export interface SearchOptions {
+    regex: boolean;
+    case_insensitive: boolean;
+    first_match_only: boolean;
+    include_extension: boolean;
+    all_files: boolean;
+}
+
+export const searchAndReplacePreview = async (targetStr: string, replacementStr: string, options?: SearchOptions, subfolderDepth?: number, selection?: FileSelection): Promise<string> => {
+    return invoke('search_and_replace_preview', { targetStr: targetStr, replacementStr: replacementStr, options, subfolderDepth, selection });
+};
+
+// Transform File Names
+
+// Applied in the given order
+export type NameTransform = 'title_case' | 'lower_case' | 'upper_case' | 'nfc' | 'nfkc' | 'ascii' | 'separators_to_spaces';
+
+export const transformFileNamesPreview = async (transforms: NameTransform[], subfolderDepth?: number, selection?: FileSelection): Promise<string> => {
+    return invoke('transform_file_names_preview', { transforms, subfolderDepth, selection });
+};
+
+// Clean Release Names
+
+export type ReleasePart = 'episode_title' | 'resolution' | 'source' | 'codec' | 'group' | 'crc';
+
+export interface CleanupRules {
+    keep: ReleasePart[];
+    remove_words: string[];
+}
+
+export const getCleanupRules = async (): Promise<CleanupRules> => {
+    return invoke('get_cleanup_rules');
 };
 
-export const searchAndReplacePreview = async (targetStr: string, replacementStr: string): Promise<String[]> => {
-    return invoke('search_and_replace_preview', { targetStr: targetStr, replacementStr: replacementStr });
+export const updateCleanupRules = async (rules: CleanupRules): Promise<void> => {
+    return invoke('update_cleanup_rules', { rules });
+};
+
+// Without rules the saved rules are used
+export const cleanReleaseNamesPreview = async (rules: CleanupRules | null, subfolderDepth?: number, selection?: FileSelection): Promise<string> => {
+    return invoke('clean_release_names_preview', { rules, subfolderDepth, selection });
 };
 
 // Append Titles to Episodes
 
-export const addTitlesToEpisodes = async (episodeTitles: string[]): Promise<void> => {
-    return invoke('add_titles_to_episodes', { episodeTitles });
+export const addTitlesToEpisodesPreview = async (episodesBySeason: SeasonedEpisodes[], titleSeparator?: string, subfolderDepth?: number, selection?: FileSelection): Promise<string> => {
+    return invoke('add_titles_to_episodes_preview', { episodesBySeason, titleSeparator, subfolderDepth, selection });
 };
 
-export const addTitlesToEpisodesPreview = async (episodeTitles: string[]): Promise<String[]> => {
-    return invoke('add_titles_to_episodes_preview', { episodeTitles });
+
+// Rename With Template
+
+export interface TemplateSample {
+    original_name: string;
+    rendered_name: string | null;
+    error: string | null;
+}
+
+export const renameWithTemplatePreview = async (template: string, showDetails: SeasonedEpisodesDetails | null, titleSeparator?: string, subfolderDepth?: number, selection?: FileSelection): Promise<string> => {
+    return invoke('rename_with_template_preview', { template, showDetails, titleSeparator, subfolderDepth, selection });
 };
 
+export const validateFilenameTemplate = async (template: string, sampleNames: string[], showDetails: SeasonedEpisodesDetails | null, titleSeparator?: string): Promise<TemplateSample[]> => {
+    return invoke('validate_filename_template', { template, sampleNames, showDetails, titleSeparator });
+};
 
 export const openEpisodeTitleWindow = async (): Promise<void> => {
     try {
@@ -117,11 +263,15 @@ export const fetchTVDBShowDetails = async (tvdbApiKey: string, animeId: number |
     return invoke('fetch_tvdb_episode_titles_grouped_by_season', { tvdbApiKey, animeId, animeName, year });
 };
 
+export type MetadataProvider = 'tvmaze' | 'jikan' | 'tvdb';
+
 export interface SeasonedEpisodesDetails {
     id: number,
     name: String,
-    premiered_year?: number,
-    episodes_by_season: SeasonedEpisodes[]
+    premiered_year?: string | null,
+    episodes_by_season: SeasonedEpisodes[],
+    provider?: MetadataProvider, // Set by the frontend, allows fetching the titles again by ID
+    tvdb_api_key?: string | null, // Set with the TheTVDB provider, which needs a key
 };
 
 export const fetchJikanShowDetails = async (animeId: number | null, animeName: string | null, year: number | null): Promise<SeasonedEpisodesDetails> => {
@@ -132,6 +282,18 @@ export const fetchTVMAZEShowDetails = async (animeId: number | null, animeName:
     return invoke('fetch_tvmaze_show_details', { animeId, animeName, year });
 };
 
+// Fetches the episodes of a show and previews their titles for the files in directory, returns the plan ID
+export const fetchAndApplyTitlesPreview = async (provider: MetadataProvider, showId: number, tvdbApiKey: string | null, directory: string, titleSeparator?: string, subfolderDepth?: number, selection?: FileSelection): Promise<string> => {
+    return invoke('fetch_and_apply_titles_preview', { provider, showId, tvdbApiKey, directory, titleSeparator, subfolderDepth, selection });
+};
+
+// Absolute (Show - 37) and seasonal (Show - S02E13) episode numbering
+export type NumberingConversion = 'to_seasonal' | 'to_absolute';
+
+export const convertAbsoluteNumberingPreview = async (episodesBySeason: SeasonedEpisodes[], conversion: NumberingConversion, subfolderDepth?: number, selection?: FileSelection): Promise<string> => {
+    return invoke('convert_absolute_numbering_preview', { episodesBySeason, conversion, subfolderDepth, selection });
+};
+
 export const focusMainWindow = async (): Promise<void> => {
     console.log("Focusing main window");
     return invoke('focus_main_window');
@@ -142,6 +304,25 @@ export const triggerRefresh = async (): Promise<void> => {
     return invoke('trigger_refresh');
 };
 
+// Number Untagged Files
+
+export type FileOrder = 'natural' | 'modified' | 'size' | 'custom';
+
+// Gives files without an episode tag a running SxxEyy, custom_order lists file names
+export interface SequentialNumbering {
+    season: number;
+    start: number;
+    step: number;
+    padding: number;
+    order: FileOrder;
+    custom_order: string[];
+    show_name: string | null;
+}
+
+export const numberFilesPreview = async (numbering: SequentialNumbering, subfolderDepth?: number, selection?: FileSelection): Promise<string> => {
+    return invoke('number_files_preview', { numbering, subfolderDepth, selection });
+};
+
 // QR Code Generator
 
 export interface QrCodeResponse {
@@ -164,12 +345,38 @@ export const saveQrCodePng = async (filePath: string, qrString: string): Promise
 
 // File Organizer
 
-export const putFilesInFolders = async (): Promise<void> => {
-    return invoke('organize_videos_into_directories');
+export const putFilesInFolders = async (selection?: FileSelection): Promise<RenameReport> => {
+    return invoke('organize_videos_into_directories', { selection });
+};
+
+// Show Name (Year)/Season 01/Show Name - S01E01 - Title.ext below the current folder
+export const organizeIntoShowFoldersPreview = async (showDetails: SeasonedEpisodesDetails | null, subfolderDepth?: number, selection?: FileSelection): Promise<string> => {
+    return invoke('organize_into_show_folders_preview', { showDetails, subfolderDepth, selection });
 };
 
-export const pullFilesFromFolders = async (): Promise<String[]> => {
-    return invoke('flatten_single_file_directories');
+// Title (Year)/Title (Year) {edition-Director's Cut}.ext, a TMDB API key confirms title and year
+export const organizeMoviesPreview = async (tmdbApiKey: string | null, subfolderDepth?: number, selection?: FileSelection): Promise<string> => {
+    return invoke('organize_movies_preview', { tmdbApiKey, subfolderDepth, selection });
+};
+
+export const pullFilesFromFolders = async (selection?: FileSelection): Promise<RenameReport> => {
+    return invoke('flatten_single_file_directories', { selection });
+};
+
+// What happens to files that are no videos, and to names that are already taken
+export type FileRule = 'bring' | 'delete' | 'leave';
+export type ConflictPolicy = 'skip' | 'suffix' | 'overwrite';
+
+export interface FlattenOptions {
+    depth: number; // Levels of subfolders, at least 1
+    sidecars: FileRule;
+    samples: FileRule;
+    junk: FileRule;
+    conflict: ConflictPolicy;
+}
+
+export const flattenDirectoriesPreview = async (options: FlattenOptions, selection?: FileSelection): Promise<string> => {
+    return invoke('flatten_directories_preview', { options, selection });
 };
 
 // Printer: Load Files
@@ -186,6 +393,40 @@ export const caancelFilePrinter = async (): Promise<void> => {
     return invoke('cancel_file_printer');
 };
 
+// Duplicate Finder
+
+export interface DuplicateSet {
+    size: number;
+    hash: string;
+    paths: string[];
+    wasted: number; // Bytes of every copy but one
+}
+
+export interface DuplicateReport {
+    root: string;
+    scanned_files: number;
+    sets: DuplicateSet[];
+    wasted: number;
+    skipped: string[]; // Files and folders that could not be read, so they were not compared
+}
+
+// Sent as "duplicate_progress" while searching, the full hash counts bytes instead of files
+export interface DuplicateProgress {
+    phase: 'scanning' | 'partial_hash' | 'full_hash';
+    done: number;
+    total: number;
+}
+
+// Only searches, cancel it like the printer with caancelFilePrinter
+export const findDuplicateFiles = async (): Promise<DuplicateReport> => {
+    return invoke('find_duplicate_files');
+};
+
+// Deletes files of the last search, every set keeps at least one copy
+export const deleteDuplicateFiles = async (paths: string[]): Promise<DuplicateReport> => {
+    return invoke('delete_duplicate_files', { paths });
+};
+
 export const saveFileToFolder = async (folderPath: string, fileInfoList: FileInfo[]): Promise<void> => {
     return invoke('save_file_to_folder', { folderPath, fileInfoList });
 };
\ No newline at end of file
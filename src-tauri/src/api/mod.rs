@@ -1,4 +1,3 @@
 pub mod anime_episodes_jikan;
 pub mod anime_episodes_thetvdb;
 pub mod anime_episodes_tvmaze;
-pub mod models;
\ No newline at end of file
@@ -1,7 +1,8 @@
 use dirs;
-use serde::{Deserialize, Serialize};
+pub use script_kiddie_core::explorer::media_files::{is_video_file, FileInfo};
+use serde::Serialize;
 use std::fs;
-use std::path::{Path, PathBuf};
+use std::path::PathBuf;
 use std::process::Command;
 use std::sync::{Arc, Mutex};
 use tauri::api::dialog::blocking::FileDialogBuilder;
@@ -9,14 +10,6 @@ use tauri::command;
 use tauri::AppHandle;
 use tauri::Manager;
 
-#[derive(Debug, Serialize, Clone, Deserialize)]
-pub struct FileInfo {
-    pub(crate) path: String,
-    pub(crate) is_dir: bool,
-    pub(crate) is_video: bool,
-    pub(crate) name: String,
-}
-
 #[derive(Debug)]
 pub struct FileExplorer {
     current_path: PathBuf,
@@ -206,17 +199,6 @@ impl FileExplorer {
     }
 }
 
-pub fn is_video_file(path: &Path) -> bool {
-    if let Some(extension) = path.extension() {
-        match extension.to_str().unwrap_or("").to_lowercase().as_str() {
-            "mp4" | "mkv" | "avi" | "mov" | "wmv" | "flv" | "webm" | "m4v" => true,
-            _ => false,
-        }
-    } else {
-        false
-    }
-}
-
 #[command]
 pub fn list_files_in_current_directory(
     state: tauri::State<'_, Arc<Mutex<FileExplorer>>>,
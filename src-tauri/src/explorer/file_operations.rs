@@ -1,616 +1,685 @@
-use crate::explorer::file_explorer::{is_video_file, FileExplorer}; // Import necessary items
-use regex::Regex;
-use sanitize_filename::sanitize;
+use crate::explorer::file_explorer::FileExplorer; // Import necessary items
+use crate::explorer::plan_store::{DirectorySnapshot, PlanStore, StoredPlan};
+use script_kiddie_core::api::metadata_provider::MetadataProvider;
+use script_kiddie_core::api::models::{SeasonedEpisodes, ShowDetailsWithEpisodes};
+use script_kiddie_core::explorer::absolute_numbering::{NumberingConversion, SeasonBoundaries};
+use script_kiddie_core::explorer::filename_policy::{
+    load_sanitize_profile, sanitize_file_name, save_sanitize_profile, SanitizeProfile,
+};
+use script_kiddie_core::explorer::filename_template::FilenameTemplate;
+use script_kiddie_core::explorer::flatten::{flatten_plan, FlattenOptions};
+use script_kiddie_core::explorer::folders::collect_folders;
+use script_kiddie_core::explorer::journal::record_operation;
+use script_kiddie_core::explorer::name_transform::NameTransform;
+use script_kiddie_core::explorer::operations::{
+    add_titles_to_episodes_plan, adjust_episode_numbers_plan, adjust_season_numbers_plan,
+    apply_plan, check_episode_adjustment, check_episode_range_remap, check_season_adjustment,
+    clean_release_names_plan, confirm_movies, convert_absolute_numbering_plan,
+    current_episode_names, episode_template_values, episode_titles_by_number,
+    flatten_single_file_directories_plan, folders_to_process, movie_files, number_files_plan,
+    organize_into_show_folders_plan, organize_movies_plan, organize_videos_plan, plan_for_folders,
+    provider_titles_plan, remap_episode_range_plan, rename_with_template_plan,
+    search_and_replace_plan, transform_file_names_plan, EpisodeRangeRemap, DEFAULT_TITLE_SEPARATOR,
+};
+use script_kiddie_core::explorer::release_name::{
+    load_cleanup_rules, save_cleanup_rules, CleanupRules, ReleaseNameParser,
+};
+use script_kiddie_core::explorer::rename_plan::{PreviewEntry, RenamePlan, RenameReport};
+use script_kiddie_core::explorer::search_replace::{FileNameSearch, SearchOptions};
+use script_kiddie_core::explorer::selection::{FileSelection, SelectionOptions};
+use script_kiddie_core::explorer::sequential_numbering::SequentialNumbering;
 use serde::Serialize;
-use std::ffi::OsStr;
-use std::fs;
-use std::io;
-use std::path::{Path, PathBuf};
+use std::path::PathBuf;
 use std::sync::{Arc, Mutex};
 use tauri::{command, State, Window};
 
 // STRUCTS
 #[derive(Serialize, Clone)]
 struct PreviewPayload {
-    new_file_names: Vec<String>,
+    plan_id: String,
+    entries: Vec<PreviewEntry>,
 }
 
-#[derive(Debug)]
-pub enum RenameError {
-    IoError(io::Error),
-    InvalidFilename,
+// A sample name checked against a template, with its result or why it failed
+#[derive(Serialize, Clone, Debug)]
+pub struct TemplateSample {
+    pub original_name: String,
+    pub rendered_name: Option<String>,
+    pub error: Option<String>,
 }
 
-impl From<io::Error> for RenameError {
-    fn from(error: io::Error) -> Self {
-        RenameError::IoError(error)
-    }
-}
+// START SANITIZE PROFILE
 
-impl std::fmt::Display for RenameError {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self {
-            RenameError::IoError(e) => write!(f, "IO Error: {}", e),
-            RenameError::InvalidFilename => write!(f, "Invalid Filename"),
-        }
-    }
+#[command]
+pub fn get_sanitize_profile() -> Result<SanitizeProfile, String> {
+    load_sanitize_profile()
 }
 
-impl std::error::Error for RenameError {}
-
-// START GET EPISODE TITLES
-
+// Every rename after this makes its names valid for the profile
 #[command]
-pub fn get_current_episode_names(
-    state: State<'_, Arc<Mutex<FileExplorer>>>,
-) -> Result<Vec<String>, String> {
-    let explorer = state.lock().unwrap();
-    let current_path = PathBuf::from(explorer.get_current_path());
-
-    let entries =
-        fs::read_dir(&current_path).map_err(|e| format!("Failed to read directory: {}", e))?;
-    let pattern = Regex::new(r"(S\d{2,3}E\d{2,3})").unwrap(); // Pattern to match SXXEXX or SXXEXXX
-    let mut episode_names = Vec::new();
-
-    for entry in entries {
-        let entry = entry.map_err(|e| format!("Failed to read directory entry: {}", e))?;
-        let path = entry.path();
-
-        if path.is_file() && is_video_file(&path) {
-            if let Some(file_name) = path.file_name().and_then(OsStr::to_str) {
-                if let Some(caps) = pattern.captures(file_name) {
-                    // Extract the title after the episode number if it exists
-                    let episode_title_with_ext = file_name
-                        .splitn(2, &caps[0]) // Split based on "SXXEXX"
-                        .nth(1) // Take the part after "SXXEXX"
-                        .unwrap_or("")
-                        .trim() // Trim leading/trailing whitespace
-                        .trim_start_matches("- "); // Remove leading " - " if present
-
-                    // Remove file extension
-                    let episode_title = episode_title_with_ext
-                        .split('.')
-                        .next() // Get the part before the extension
-                        .unwrap_or("");
-
-                    episode_names.push(episode_title.to_string());
-                }
-            }
-        }
-    }
-
-    Ok(episode_names)
+pub fn set_sanitize_profile(profile: SanitizeProfile) -> Result<(), String> {
+    save_sanitize_profile(profile)
 }
 
-// END GET EPISODE TITLES
+// END SANITIZE PROFILE
 
-// START RENAME EPISODES WITH TITLES
+// START APPLY PREVIEWED PLANS
 
+// Applies exactly the plan a preview command returned the ID for
 #[command]
-pub async fn add_titles_to_episodes(
-    state: State<'_, Arc<Mutex<FileExplorer>>>,
-    episode_titles: Vec<String>, // List of episode titles from the frontend
-    window: Window,              // To emit events
-) -> Result<(), String> {
-    // Generate new file names
-    let new_file_names =
-        add_titles_to_episodes_generate_file_titles(state.clone(), episode_titles.clone())
-            .await
-            .map_err(|e| format!("Failed to generate new file names: {:?}", e))?;
+pub fn apply_rename_plan(
+    plan_store: State<'_, Arc<Mutex<PlanStore>>>,
+    plan_id: String,
+    window: Window, // To emit events
+) -> Result<RenameReport, String> {
+    let stored_plan = plan_store
+        .lock()
+        .unwrap()
+        .take(&plan_id)
+        .ok_or("The preview has expired. Please preview again.")?;
+
+    stored_plan.ensure_directory_unchanged()?;
+    stored_plan
+        .plan
+        .validate()
+        .map_err(|e| format!("Failed to rename files: {}", e))?;
+
+    let mut report = stored_plan.plan.execute();
+    // The files are already renamed, so a journal error must not hide the report
+    if let Err(e) = record_operation(&stored_plan.label, &report) {
+        report
+            .warnings
+            .push(format!("The changes can not be undone: {}", e));
+    }
 
-    // Get the current path from FileExplorer
-    let current_path = {
-        let explorer = state.lock().unwrap();
-        PathBuf::from(explorer.get_current_path())
-    };
+    // Emit an event when renaming is done, the directory changed either way
+    window
+        .emit("trigger-reload", "Files renamed successfully")
+        .map_err(|e| format!("Failed to emit event: {:?}", e))?;
 
-    // Rename media files
-    add_titles_to_episodes_rename_media_files(&current_path, &new_file_names)
-        .await
-        .map_err(|e| format!("Failed to rename files: {:?}", e))?;
+    Ok(report)
+}
 
-    // Emit an event when renaming is successful
+// Keeps the plan for `apply_rename_plan` and sends its preview to the frontend
+fn store_and_emit_preview(
+    plan_store: &State<'_, Arc<Mutex<PlanStore>>>,
+    window: &Window,
+    label: &str,
+    directory: PathBuf,
+    snapshot: DirectorySnapshot,
+    mut plan: RenamePlan,
+) -> Result<String, String> {
+    // Every preview shows the names as they end up on the chosen file system
+    plan.sanitize_targets(load_sanitize_profile()?);
+    let entries = plan.preview();
+    let plan_id = plan_store.lock().unwrap().insert(StoredPlan {
+        label: label.to_string(),
+        directory,
+        snapshot,
+        plan,
+    });
+
+    // Emit an event with the preview entries
     window
         .emit(
-            "trigger-reload",
-            "Files renamed with episode titles successfully",
+            "trigger-preview",
+            PreviewPayload {
+                plan_id: plan_id.clone(),
+                entries,
+            },
         )
-        .unwrap();
+        .map_err(|e| format!("Failed to emit event: {:?}", e))?;
 
-    Ok(())
+    Ok(plan_id)
 }
 
+// END APPLY PREVIEWED PLANS
+
+// START GET EPISODE TITLES
+
+// Returns the titles found in the file names, grouped by season. Each season's
+// titles are indexed by episode number, so episodes without a file stay empty.
+// The title of a multi-episode file is split by the separator when possible.
 #[command]
-pub async fn add_titles_to_episodes_generate_file_titles(
+pub fn get_current_episode_names(
     state: State<'_, Arc<Mutex<FileExplorer>>>,
-    episode_titles: Vec<String>, // Use Vec<String> to pass the episode titles from the frontend
-) -> Result<Vec<String>, String> {
+    title_separator: Option<String>,
+) -> Result<Vec<SeasonedEpisodes>, String> {
     let explorer = state.lock().unwrap();
     let current_path = PathBuf::from(explorer.get_current_path());
+    let title_separator = title_separator.unwrap_or_else(|| DEFAULT_TITLE_SEPARATOR.to_string());
 
-    let entries =
-        fs::read_dir(&current_path).map_err(|e| format!("Failed to read directory: {:?}", e))?;
-    let pattern = Regex::new(r"(S\d{2,3}E\d{2,3})").unwrap(); // Pattern to match SXXEXX or SXXEXXX
-    let mut episode_idx = 0; // Track episode title index
-    let mut new_file_names = Vec::new();
-
-    for entry in entries {
-        let entry = entry.map_err(|e| format!("Failed to read entry: {:?}", e))?;
-        let path = entry.path();
-
-        if path.is_file() && is_video_file(&path) {
-            if let Some(file_name) = path.file_name().and_then(OsStr::to_str) {
-                if let Some(new_file_name) =
-                    create_new_file_name(file_name, &pattern, &episode_titles, &mut episode_idx)
-                {
-                    new_file_names.push(new_file_name);
-                }
-            }
-        }
-    }
-
-    Ok(new_file_names)
+    current_episode_names(&current_path, &title_separator)
 }
 
+// END GET EPISODE TITLES
+
+// START RENAME EPISODES WITH TITLES
+
 #[command]
 pub async fn add_titles_to_episodes_preview(
     state: State<'_, Arc<Mutex<FileExplorer>>>,
-    episode_titles: Vec<String>, // List of episode titles from the frontend
-    window: Window,              // To emit events
-) -> Result<(), String> {
-    // Generate new file names
-    let new_file_names =
-        add_titles_to_episodes_generate_file_titles(state.clone(), episode_titles.clone())
-            .await
-            .map_err(|e| format!("Failed to generate new file names: {:?}", e))?;
-
+    plan_store: State<'_, Arc<Mutex<PlanStore>>>,
+    episodes_by_season: Vec<SeasonedEpisodes>, // Episode titles from the frontend
+    title_separator: Option<String>,           // Joins the titles of multi-episode files
+    subfolder_depth: Option<usize>,            // Also renames files in season subfolders
+    selection: Option<SelectionOptions>,       // Only these files, every file without it
+    window: Window,                            // To emit events
+) -> Result<String, String> {
+    let selection = FileSelection::from_options(selection)?;
     // Get the current path from FileExplorer
     let current_path = {
         let explorer = state.lock().unwrap();
         PathBuf::from(explorer.get_current_path())
     };
+    let folders = folders_to_process(&current_path, subfolder_depth)?;
+
+    // Remember the directory contents the preview is based on
+    let snapshot = DirectorySnapshot::capture_all(&folders)?;
+
+    let title_separator = title_separator.unwrap_or_else(|| DEFAULT_TITLE_SEPARATOR.to_string());
+    let plan = plan_for_folders(&folders, |folder| {
+        add_titles_to_episodes_plan(folder, &episodes_by_season, &title_separator, &selection)
+    })
+    .map_err(|e| format!("Failed to build rename plan: {:?}", e))?;
+
+    store_and_emit_preview(
+        &plan_store,
+        &window,
+        "Add episode titles",
+        current_path,
+        snapshot,
+        plan,
+    )
+}
 
-    // Get the file extensions from the current directory
-    let entries =
-        fs::read_dir(&current_path).map_err(|e| format!("Failed to read directory: {:?}", e))?;
-    let mut file_extensions = Vec::new();
+// END RENAME EPISODES WITH TITLES
 
-    for entry in entries {
-        let entry = entry.map_err(|e| format!("Failed to read entry: {:?}", e))?;
-        let path = entry.path();
+// START FETCH AND APPLY TITLES
 
-        if path.is_file() && is_video_file(&path) {
-            if let Some(extension) = path.extension().and_then(OsStr::to_str) {
-                file_extensions.push(extension.to_string());
-            }
-        }
+// Fetches the episodes of a show and previews them as titles of the files in `directory`,
+// so the frontend doesn't have to pass the titles around
+#[command]
+#[allow(clippy::too_many_arguments)] // Tauri passes the arguments by name
+pub async fn fetch_and_apply_titles_preview(
+    plan_store: State<'_, Arc<Mutex<PlanStore>>>,
+    provider: MetadataProvider,
+    show_id: i32,
+    tvdb_api_key: Option<String>, // Only needed for TheTVDB
+    directory: String,
+    title_separator: Option<String>, // Joins the titles of multi-episode files
+    subfolder_depth: Option<usize>,  // Also renames files in season subfolders
+    selection: Option<SelectionOptions>, // Only these files, every file without it
+    window: Window,                  // To emit events
+) -> Result<String, String> {
+    let selection = FileSelection::from_options(selection)?;
+    let directory = PathBuf::from(directory);
+    if !directory.is_dir() {
+        return Err(format!("{} is not a directory", directory.display()));
     }
 
-    // Append the extensions to the new file names
-    let mut final_file_names = Vec::new();
-    for (new_file_name, extension) in new_file_names.iter().zip(file_extensions.iter()) {
-        let final_file_name = format!("{}.{}", new_file_name, extension);
-        final_file_names.push(final_file_name);
+    let episodes = provider
+        .fetch_episodes(show_id, tvdb_api_key.as_deref())
+        .await?;
+    if episodes.is_empty() {
+        return Err(format!(
+            "{} has no episodes for show {}",
+            provider.name(),
+            show_id
+        ));
     }
 
-    // Emit an event with the preview file names
-    window
-        .emit(
-            "trigger-preview",
-            PreviewPayload {
-                new_file_names: final_file_names.clone(),
-            },
-        )
-        .unwrap();
+    let folders = folders_to_process(&directory, subfolder_depth)?;
+
+    // Remember the directory contents the preview is based on
+    let snapshot = DirectorySnapshot::capture_all(&folders)?;
+
+    let title_separator = title_separator.unwrap_or_else(|| DEFAULT_TITLE_SEPARATOR.to_string());
+    let plan = plan_for_folders(&folders, |folder| {
+        provider_titles_plan(folder, provider, &episodes, &title_separator, &selection)
+    })
+    .map_err(|e| format!("Failed to build rename plan: {}", e))?;
 
-    Ok(())
+    store_and_emit_preview(
+        &plan_store,
+        &window,
+        &format!("Add titles from {}", provider.name()),
+        directory,
+        snapshot,
+        plan,
+    )
 }
 
-pub async fn add_titles_to_episodes_rename_media_files(
-    directory: &Path,
-    new_file_names: &[String], // Slice of new file names
-) -> Result<(), io::Error> {
-    let entries = fs::read_dir(directory)?;
-    let mut file_idx = 0; // Track file index
-
-    for entry in entries {
-        let entry = entry?;
-        let path = entry.path();
-
-        if path.is_file() && is_video_file(&path) {
-            if let Some(_file_name) = path.file_name().and_then(OsStr::to_str) {
-                if file_idx < new_file_names.len() {
-                    let new_file_name = &new_file_names[file_idx];
-                    file_idx += 1;
-
-                    // Split the filename and extension
-                    let extension = path.extension().and_then(OsStr::to_str).unwrap_or("");
-                    let sanitized_base_name = sanitize(new_file_name); // Sanitize the base file name
-
-                    // Construct the new filename by attaching the extension back
-                    let final_file_name = format!("{}.{}", sanitized_base_name, extension);
-
-                    let new_path = path.with_file_name(final_file_name);
-                    fs::rename(&path, new_path)?;
-                }
-            }
-        }
-    }
+// END FETCH AND APPLY TITLES
+
+// START RENAME WITH TEMPLATE
 
-    Ok(())
+#[command]
+#[allow(clippy::too_many_arguments)] // Tauri passes the arguments by name
+pub fn rename_with_template_preview(
+    state: State<'_, Arc<Mutex<FileExplorer>>>,
+    plan_store: State<'_, Arc<Mutex<PlanStore>>>,
+    template: String,
+    show_details: Option<ShowDetailsWithEpisodes>,
+    title_separator: Option<String>,
+    subfolder_depth: Option<usize>, // Also renames files in subfolders
+    selection: Option<SelectionOptions>, // Only these files, every file without it
+    window: Window,                 // To emit events
+) -> Result<String, String> {
+    let selection = FileSelection::from_options(selection)?;
+    let explorer = state.lock().unwrap();
+    let current_path = PathBuf::from(explorer.get_current_path());
+    let folders = folders_to_process(&current_path, subfolder_depth)?;
+
+    // Remember the directory contents the preview is based on
+    let snapshot = DirectorySnapshot::capture_all(&folders)?;
+
+    let template = FilenameTemplate::parse(&template).map_err(|e| e.to_string())?;
+    let title_separator = title_separator.unwrap_or_else(|| DEFAULT_TITLE_SEPARATOR.to_string());
+    let plan = plan_for_folders(&folders, |folder| {
+        rename_with_template_plan(
+            folder,
+            &template,
+            show_details.as_ref(),
+            &title_separator,
+            &selection,
+        )
+    })
+    .map_err(|e| format!("Failed to build rename plan: {:?}", e))?;
+
+    store_and_emit_preview(
+        &plan_store,
+        &window,
+        "Rename with template",
+        current_path,
+        snapshot,
+        plan,
+    )
 }
 
-fn create_new_file_name(
-    file_name: &str,
-    pattern: &Regex,
-    episode_titles: &[String],
-    episode_idx: &mut usize,
-) -> Option<String> {
-    // Check if the file name matches the episode pattern
-    if let Some(caps) = pattern.captures(file_name) {
-        let episode_counter = &caps[1]; // e.g., "S01E02" or "S01E003"
-
-        // Remove everything after the episode counter
-        let base_name = file_name.split(episode_counter).next()?.to_string() + episode_counter;
-
-        // If there are more titles, append the corresponding title
-        if *episode_idx < episode_titles.len() {
-            let title = &episode_titles[*episode_idx];
-            *episode_idx += 1;
-
-            // If the title is empty or just spaces, return the base name (i.e., remove the old title)
-            if title.trim().is_empty() {
-                return Some(base_name);
+// Renders the template for every sample name without touching any file
+#[command]
+pub fn validate_filename_template(
+    template: String,
+    sample_names: Vec<String>,
+    show_details: Option<ShowDetailsWithEpisodes>,
+    title_separator: Option<String>,
+) -> Result<Vec<TemplateSample>, String> {
+    let template = FilenameTemplate::parse(&template).map_err(|e| e.to_string())?;
+    let title_separator = title_separator.unwrap_or_else(|| DEFAULT_TITLE_SEPARATOR.to_string());
+    let parser = ReleaseNameParser::new();
+    let episode_titles = show_details
+        .as_ref()
+        .map(|details| episode_titles_by_number(&details.episodes_by_season))
+        .unwrap_or_default();
+
+    let profile = load_sanitize_profile()?;
+    let samples = sample_names
+        .into_iter()
+        .map(|original_name| {
+            let values = episode_template_values(
+                &original_name,
+                &parser,
+                show_details.as_ref(),
+                &episode_titles,
+                &title_separator,
+                None,
+            );
+            match template.render_file_name(&values) {
+                Ok(rendered_name) => TemplateSample {
+                    original_name,
+                    rendered_name: Some(sanitize_file_name(&rendered_name, profile).0),
+                    error: None,
+                },
+                Err(error) => TemplateSample {
+                    original_name,
+                    rendered_name: None,
+                    error: Some(error),
+                },
             }
+        })
+        .collect();
 
-            let new_file_name = format!("{} - {}", base_name, title);
-            return Some(new_file_name);
-        } else {
-            return Some(base_name); // If no more titles, just return the base name
-        }
-    }
-    None
+    Ok(samples)
 }
 
-// END RENAME EPISODES WITH TITLES
+// END RENAME WITH TEMPLATE
 
 // START SEARCH AND REPLACE FILE TITLES
 
 #[command]
-pub fn search_and_replace(
+#[allow(clippy::too_many_arguments)] // Tauri passes the arguments by name
+pub fn search_and_replace_preview(
     state: State<'_, Arc<Mutex<FileExplorer>>>,
+    plan_store: State<'_, Arc<Mutex<PlanStore>>>,
     target_str: String,
     replacement_str: String,
-    window: Window, // Add the window parameter to emit events
-) -> Result<(), String> {
-    if target_str.is_empty() {
-        return Err("Target string cannot be empty.".to_string());
-    }
+    options: Option<SearchOptions>, // Regex mode, case and match options
+    subfolder_depth: Option<usize>, // Also renames files in subfolders
+    selection: Option<SelectionOptions>, // Only these files, every file without it
+    window: Window,                 // Add the window parameter to emit events
+) -> Result<String, String> {
+    let selection = FileSelection::from_options(selection)?;
+    let search = FileNameSearch::new(&target_str, &replacement_str, options.unwrap_or_default())?;
 
     let explorer = state.lock().unwrap();
     let current_path = PathBuf::from(explorer.get_current_path());
-
-    search_and_replace_rename_media_files_in_directory(
-        &current_path,
-        &target_str,
-        &replacement_str,
+    let folders = folders_to_process(&current_path, subfolder_depth)?;
+
+    // Remember the directory contents the preview is based on
+    let snapshot = DirectorySnapshot::capture_all(&folders)?;
+
+    let plan = plan_for_folders(&folders, |folder| {
+        search_and_replace_plan(folder, &search, &selection)
+    })
+    .map_err(|e| format!("Failed to build rename plan: {}", e))?;
+
+    store_and_emit_preview(
+        &plan_store,
+        &window,
+        "Search and replace",
+        current_path,
+        snapshot,
+        plan,
     )
-    .map_err(|e| format!("Failed to rename files: {:?}", e))?;
-
-    // Emit an event when renaming is successful
-    window
-        .emit("trigger-reload", "Files renamed successfully")
-        .unwrap();
-
-    Ok(())
 }
 
-pub fn search_and_replace_rename_media_files_in_directory(
-    directory: &Path,
-    target_str: &str,
-    replacement_str: &str,
-) -> Result<(), RenameError> {
-    let entries = fs::read_dir(directory)?;
-
-    for entry in entries {
-        let entry = entry?;
-        let path = entry.path();
-
-        if path.is_file() && is_video_file(&path) {
-            if let Some(file_name) = path.file_name().and_then(OsStr::to_str) {
-                let new_file_name = file_name.replace(target_str, replacement_str);
-                if new_file_name != file_name {
-                    let new_path = path.with_file_name(new_file_name);
-                    fs::rename(&path, new_path)?;
-                }
-            } else {
-                return Err(RenameError::InvalidFilename);
-            }
-        }
-    }
+// END SEARCH AND REPLACE FILE TITLES
 
-    Ok(())
-}
+// START TRANSFORM FILE NAMES
 
+// Case, Unicode and separator fixes, applied in the given order
 #[command]
-pub fn search_and_replace_preview(
+pub fn transform_file_names_preview(
     state: State<'_, Arc<Mutex<FileExplorer>>>,
-    target_str: String,
-    replacement_str: String,
-    window: Window, // Add the window parameter to emit events
-) -> Result<(), String> {
-    if target_str.is_empty() {
-        return Err("Target string cannot be empty.".to_string());
+    plan_store: State<'_, Arc<Mutex<PlanStore>>>,
+    transforms: Vec<NameTransform>,
+    subfolder_depth: Option<usize>, // Also renames files in subfolders
+    selection: Option<SelectionOptions>, // Only these files, every file without it
+    window: Window,                 // To emit events
+) -> Result<String, String> {
+    let selection = FileSelection::from_options(selection)?;
+    if transforms.is_empty() {
+        return Err("Select at least one transform.".to_string());
     }
 
     let explorer = state.lock().unwrap();
     let current_path = PathBuf::from(explorer.get_current_path());
+    let folders = folders_to_process(&current_path, subfolder_depth)?;
+
+    // Remember the directory contents the preview is based on
+    let snapshot = DirectorySnapshot::capture_all(&folders)?;
+
+    let plan = plan_for_folders(&folders, |folder| {
+        transform_file_names_plan(folder, &transforms, &selection)
+    })
+    .map_err(|e| format!("Failed to build rename plan: {}", e))?;
+
+    store_and_emit_preview(
+        &plan_store,
+        &window,
+        "Transform file names",
+        current_path,
+        snapshot,
+        plan,
+    )
+}
 
-    let entries =
-        fs::read_dir(&current_path).map_err(|e| format!("Failed to read directory: {:?}", e))?;
-    let mut new_file_names = Vec::new();
-    let mut file_extensions = Vec::new();
-
-    for entry in entries {
-        let entry = entry.map_err(|e| format!("Failed to read entry: {:?}", e))?;
-        let path = entry.path();
-
-        if path.is_file() && is_video_file(&path) {
-            if let Some(file_name) = path.file_name().and_then(OsStr::to_str) {
-                let new_file_name = file_name.replace(&target_str, &replacement_str);
-                new_file_names.push(new_file_name);
-                if let Some(extension) = path.extension().and_then(OsStr::to_str) {
-                    file_extensions.push(extension.to_string());
-                }
-            } else {
-                return Err("Invalid filename.".to_string());
-            }
-        }
-    }
+// END TRANSFORM FILE NAMES
 
-    // Emit an event with the preview file names
-    window
-        .emit(
-            "trigger-preview",
-            PreviewPayload {
-                new_file_names: new_file_names.clone(),
-            },
-        )
-        .map_err(|e| format!("Failed to emit event: {:?}", e))?;
+// START CLEAN RELEASE NAMES
 
-    Ok(())
+#[command]
+pub fn get_cleanup_rules() -> Result<CleanupRules, String> {
+    load_cleanup_rules()
 }
 
-// END SEARCH AND REPLACE FILE TITLES
+#[command]
+pub fn update_cleanup_rules(rules: CleanupRules) -> Result<(), String> {
+    save_cleanup_rules(&rules)
+}
+
+// Rebuilds release names like `[SubGroup] Show.Name.S01E03.1080p.WEB-DL.x264-GROUP [ABCD1234]`
+// from the parts the rules keep. Without rules the saved ones are used.
+#[command]
+pub fn clean_release_names_preview(
+    state: State<'_, Arc<Mutex<FileExplorer>>>,
+    plan_store: State<'_, Arc<Mutex<PlanStore>>>,
+    rules: Option<CleanupRules>,
+    subfolder_depth: Option<usize>, // Also cleans files in subfolders
+    selection: Option<SelectionOptions>, // Only these files, every file without it
+    window: Window,                 // To emit events
+) -> Result<String, String> {
+    let selection = FileSelection::from_options(selection)?;
+    let explorer = state.lock().unwrap();
+    let current_path = PathBuf::from(explorer.get_current_path());
+    let folders = folders_to_process(&current_path, subfolder_depth)?;
+
+    // Remember the directory contents the preview is based on
+    let snapshot = DirectorySnapshot::capture_all(&folders)?;
+
+    let rules = match rules {
+        Some(rules) => rules,
+        None => load_cleanup_rules()?,
+    };
+    let parser = ReleaseNameParser::new();
+    let plan = plan_for_folders(&folders, |folder| {
+        clean_release_names_plan(folder, &parser, &rules, &selection)
+    })
+    .map_err(|e| format!("Failed to build rename plan: {}", e))?;
+
+    store_and_emit_preview(
+        &plan_store,
+        &window,
+        "Clean release names",
+        current_path,
+        snapshot,
+        plan,
+    )
+}
+
+// END CLEAN RELEASE NAMES
 
 // START ADJUST EPISODE NUMBERS
 
 #[command]
-pub fn adjust_episode_numbers(
+pub fn adjust_episode_numbers_preview(
     state: State<'_, Arc<Mutex<FileExplorer>>>,
+    plan_store: State<'_, Arc<Mutex<PlanStore>>>,
     adjustment_value: i32,
-    window: Window, // To emit events
-) -> Result<(), String> {
+    subfolder_depth: Option<usize>, // Also adjusts files in season subfolders
+    selection: Option<SelectionOptions>, // Only these files, every file without it
+    window: Window,                 // To emit events
+) -> Result<String, String> {
+    let selection = FileSelection::from_options(selection)?;
     let explorer = state.lock().unwrap();
     let current_path = PathBuf::from(explorer.get_current_path());
+    let folders = folders_to_process(&current_path, subfolder_depth)?;
 
-    // Check if the adjustment would result in negative episode numbers
-    let min_episode_number = find_min_episode_number(&current_path)
-        .map_err(|e| format!("Failed to find minimum episode number: {:?}", e))?;
+    // Remember the directory contents the preview is based on
+    let snapshot = DirectorySnapshot::capture_all(&folders)?;
 
-    if adjustment_value < 0 && min_episode_number + adjustment_value < 0 {
-        return Err(format!(
-            "Cannot adjust episode numbers by {}. Minimum episode number is E{:02}, which would result in negative episode numbers.",
-            adjustment_value, min_episode_number
-        ));
-    }
+    check_episode_adjustment(&folders, adjustment_value, &selection)?;
 
-    adjust_episode_numbers_renaming(&current_path, adjustment_value)
-        .map_err(|e| format!("Failed to adjust episode numbers: {:?}", e))?;
-
-    // Emit an event when adjustment is successful
-    window
-        .emit("trigger-reload", "Episode numbers adjusted successfully")
-        .unwrap();
+    let plan = plan_for_folders(&folders, |folder| {
+        adjust_episode_numbers_plan(folder, adjustment_value, &selection)
+    })
+    .map_err(|e| format!("Failed to build rename plan: {:?}", e))?;
 
-    Ok(())
+    store_and_emit_preview(
+        &plan_store,
+        &window,
+        "Adjust episode numbers",
+        current_path,
+        snapshot,
+        plan,
+    )
 }
 
-fn find_min_episode_number(directory: &Path) -> Result<i32, io::Error> {
-    let entries = fs::read_dir(directory)?;
-    let pattern = Regex::new(r"(S\d{2,3})E(\d{2,3})").unwrap();
-    let mut min_episode_number = i32::MAX;
-
-    for entry in entries {
-        let entry = entry?;
-        let path = entry.path();
-
-        if path.is_file() && is_video_file(&path) {
-            if let Some(file_name) = path.file_name().and_then(OsStr::to_str) {
-                if let Some(caps) = pattern.captures(file_name) {
-                    if let Ok(episode_number) = caps[2].parse::<i32>() {
-                        if episode_number < min_episode_number {
-                            min_episode_number = episode_number;
-                        }
-                    }
-                }
-            }
-        }
-    }
-
-    if min_episode_number == i32::MAX {
-        return Err(io::Error::new(io::ErrorKind::NotFound, "No episodes found"));
-    }
+// END ADJUST EPISODE NUMBERS
 
-    Ok(min_episode_number)
-}
+// START ADJUST SEASON NUMBERS
 
-fn adjust_episode_numbers_renaming(
-    directory: &Path,
+#[command]
+pub fn adjust_season_numbers_preview(
+    state: State<'_, Arc<Mutex<FileExplorer>>>,
+    plan_store: State<'_, Arc<Mutex<PlanStore>>>,
     adjustment_value: i32,
-) -> Result<(), io::Error> {
-    let mut entries: Vec<_> = fs::read_dir(directory)?
-        .filter_map(|entry| entry.ok())
-        .filter(|entry| entry.path().is_file() && is_video_file(&entry.path()))
-        .collect();
+    subfolder_depth: Option<usize>, // Also adjusts files in season subfolders
+    selection: Option<SelectionOptions>, // Only these files, every file without it
+    window: Window,                 // To emit events
+) -> Result<String, String> {
+    let selection = FileSelection::from_options(selection)?;
+    let explorer = state.lock().unwrap();
+    let current_path = PathBuf::from(explorer.get_current_path());
+    let folders = folders_to_process(&current_path, subfolder_depth)?;
 
-    let pattern = Regex::new(r"(S\d{2,3})E(\d{2,3})").unwrap();
+    // Remember the directory contents the preview is based on
+    let snapshot = DirectorySnapshot::capture_all(&folders)?;
 
-    // Sort entries based on the episode number
-    entries.sort_by_key(|entry| {
-        if let Some(file_name) = entry.path().file_name().and_then(OsStr::to_str) {
-            if let Some(caps) = pattern.captures(file_name) {
-                if let Ok(episode_number) = caps[2].parse::<i32>() {
-                    return episode_number;
-                }
-            }
-        }
-        0
-    });
+    check_season_adjustment(&folders, adjustment_value, &selection)?;
 
-    // If adjustment is positive, rename from the highest episode number first
-    if adjustment_value > 0 {
-        entries.reverse();
-    }
-
-    // Perform the renaming process
-    for entry in entries {
-        let path = entry.path();
-        if let Some(file_name) = path.file_name().and_then(OsStr::to_str) {
-            if let Some(new_file_name) =
-                adjust_episode_counter_in_filename(file_name, &pattern, adjustment_value)
-            {
-                let new_path = path.with_file_name(new_file_name);
-
-                // Rename the file
-                fs::rename(&path, new_path)?;
-            }
-        }
-    }
+    let plan = plan_for_folders(&folders, |folder| {
+        adjust_season_numbers_plan(folder, adjustment_value, &selection)
+    })
+    .map_err(|e| format!("Failed to build rename plan: {:?}", e))?;
 
-    Ok(())
+    store_and_emit_preview(
+        &plan_store,
+        &window,
+        "Adjust season numbers",
+        current_path,
+        snapshot,
+        plan,
+    )
 }
 
-fn adjust_episode_counter_in_filename(
-    file_name: &str,
-    pattern: &Regex,
-    adjustment_value: i32,
-) -> Option<String> {
-    if let Some(caps) = pattern.captures(file_name) {
-        let season_part = &caps[1]; // e.g., "S01" or "S010"
-        let episode_number_str = &caps[2]; // e.g., "01" or "001"
-
-        if let Ok(episode_number) = episode_number_str.parse::<i32>() {
-            let new_episode_number = episode_number + adjustment_value;
+#[command]
+pub fn remap_episode_range_preview(
+    state: State<'_, Arc<Mutex<FileExplorer>>>,
+    plan_store: State<'_, Arc<Mutex<PlanStore>>>,
+    remap: EpisodeRangeRemap,
+    subfolder_depth: Option<usize>, // Also moves episodes in season subfolders
+    selection: Option<SelectionOptions>, // Only these files, every file without it
+    window: Window,                 // To emit events
+) -> Result<String, String> {
+    let selection = FileSelection::from_options(selection)?;
+    let explorer = state.lock().unwrap();
+    let current_path = PathBuf::from(explorer.get_current_path());
+    let folders = folders_to_process(&current_path, subfolder_depth)?;
+
+    // Remember the directory contents the preview is based on
+    let snapshot = DirectorySnapshot::capture_all(&folders)?;
+
+    check_episode_range_remap(&remap)?;
+    let plan = plan_for_folders(&folders, |folder| {
+        remap_episode_range_plan(folder, &remap, &selection)
+    })
+    .map_err(|e| format!("Failed to build rename plan: {:?}", e))?;
+
+    store_and_emit_preview(
+        &plan_store,
+        &window,
+        "Move episodes to another season",
+        current_path,
+        snapshot,
+        plan,
+    )
+}
 
-            // Block renaming to negative episode numbers
-            if new_episode_number < 0 {
-                return None; // Skip renaming if the new episode number would be negative
-            }
+// END ADJUST SEASON NUMBERS
 
-            let new_episode_str = format!(
-                "{:0width$}",
-                new_episode_number,
-                width = episode_number_str.len()
-            );
-            let new_file_name = pattern
-                .replace(file_name, format!("{}E{}", season_part, new_episode_str))
-                .to_string();
-            return Some(new_file_name);
-        }
-    }
-    None
-}
+// START CONVERT ABSOLUTE NUMBERING
 
 #[command]
-pub fn adjust_episode_numbers_preview(
+pub fn convert_absolute_numbering_preview(
     state: State<'_, Arc<Mutex<FileExplorer>>>,
-    adjustment_value: i32,
-    window: Window, // To emit events
-) -> Result<(), String> {
+    plan_store: State<'_, Arc<Mutex<PlanStore>>>,
+    episodes_by_season: Vec<SeasonedEpisodes>, // Season boundaries from the provider
+    conversion: NumberingConversion,
+    subfolder_depth: Option<usize>, // Also converts files in season subfolders
+    selection: Option<SelectionOptions>, // Only these files, every file without it
+    window: Window,                 // To emit events
+) -> Result<String, String> {
+    let selection = FileSelection::from_options(selection)?;
     let explorer = state.lock().unwrap();
     let current_path = PathBuf::from(explorer.get_current_path());
+    let folders = folders_to_process(&current_path, subfolder_depth)?;
+
+    // Remember the directory contents the preview is based on
+    let snapshot = DirectorySnapshot::capture_all(&folders)?;
+
+    let boundaries = SeasonBoundaries::from_seasons(&episodes_by_season)?;
+    let plan = plan_for_folders(&folders, |folder| {
+        convert_absolute_numbering_plan(folder, &boundaries, conversion, &selection)
+    })
+    .map_err(|e| format!("Failed to build rename plan: {:?}", e))?;
+
+    store_and_emit_preview(
+        &plan_store,
+        &window,
+        "Convert episode numbering",
+        current_path,
+        snapshot,
+        plan,
+    )
+}
 
-    // Check if the adjustment would result in negative episode numbers
-    let min_episode_number = find_min_episode_number(&current_path)
-        .map_err(|e| format!("Failed to find minimum episode number: {:?}", e))?;
-
-    if adjustment_value < 0 && min_episode_number + adjustment_value < 0 {
-        return Err(format!(
-            "Cannot adjust episode numbers by {}. Minimum episode number is E{:02}, which would result in negative episode numbers.",
-            adjustment_value, min_episode_number
-        ));
-    }
+// END CONVERT ABSOLUTE NUMBERING
 
-    let entries =
-        fs::read_dir(&current_path).map_err(|e| format!("Failed to read directory: {:?}", e))?;
-    let mut new_file_names = Vec::new();
-    let mut file_extensions = Vec::new();
-
-    let pattern = Regex::new(r"(S\d{2,3})E(\d{2,3})").unwrap();
-
-    for entry in entries {
-        let entry = entry.map_err(|e| format!("Failed to read entry: {:?}", e))?;
-        let path = entry.path();
-
-        if path.is_file() && is_video_file(&path) {
-            if let Some(file_name) = path.file_name().and_then(OsStr::to_str) {
-                if let Some(new_file_name) =
-                    adjust_episode_counter_in_filename(file_name, &pattern, adjustment_value)
-                {
-                    new_file_names.push(new_file_name);
-                    if let Some(extension) = path.extension().and_then(OsStr::to_str) {
-                        file_extensions.push(extension.to_string());
-                    }
-                } else {
-                    // If no adjustment, keep the original file name
-                    new_file_names.push(file_name.to_string());
-                    if let Some(extension) = path.extension().and_then(OsStr::to_str) {
-                        file_extensions.push(extension.to_string());
-                    }
-                }
-            } else {
-                return Err("Invalid filename.".to_string());
-            }
-        }
-    }
+// START NUMBER UNTAGGED FILES
 
-    // Emit an event with the preview file names
-    window
-        .emit(
-            "trigger-preview",
-            PreviewPayload {
-                new_file_names: new_file_names.clone(),
-            },
-        )
-        .map_err(|e| format!("Failed to emit event: {:?}", e))?;
+// Gives files without an episode tag, e.g. "Track 01.mkv" or "VTS_01_1.mkv", a running SxxEyy
+#[command]
+pub fn number_files_preview(
+    state: State<'_, Arc<Mutex<FileExplorer>>>,
+    plan_store: State<'_, Arc<Mutex<PlanStore>>>,
+    numbering: SequentialNumbering,
+    subfolder_depth: Option<usize>, // Numbers every season subfolder on its own
+    selection: Option<SelectionOptions>, // Only these files, every file without it
+    window: Window,                 // To emit events
+) -> Result<String, String> {
+    let selection = FileSelection::from_options(selection)?;
+    numbering.check()?;
 
-    Ok(())
+    let explorer = state.lock().unwrap();
+    let current_path = PathBuf::from(explorer.get_current_path());
+    let folders = folders_to_process(&current_path, subfolder_depth)?;
+
+    // Remember the directory contents the preview is based on
+    let snapshot = DirectorySnapshot::capture_all(&folders)?;
+
+    let plan = plan_for_folders(&folders, |folder| {
+        number_files_plan(folder, &numbering, &selection)
+    })
+    .map_err(|e| format!("Failed to build rename plan: {}", e))?;
+
+    store_and_emit_preview(
+        &plan_store,
+        &window,
+        "Number files",
+        current_path,
+        snapshot,
+        plan,
+    )
 }
 
-// END ADJUST EPISODE NUMBERS
+// END NUMBER UNTAGGED FILES
 
 // START ORGANIZE VIDEOS INTO DIRECTORIES
 
 #[command]
 pub fn organize_videos_into_directories(
     state: State<'_, Arc<Mutex<FileExplorer>>>,
-    window: Window, // To emit events
-) -> Result<(), String> {
+    selection: Option<SelectionOptions>, // Only these files, every file without it
+    window: Window,                      // To emit events
+) -> Result<RenameReport, String> {
+    let selection = FileSelection::from_options(selection)?;
     let explorer = state.lock().map_err(|e| e.to_string())?;
     let current_dir = PathBuf::from(explorer.get_current_path());
 
-    for entry in fs::read_dir(&current_dir).map_err(|e| e.to_string())? {
-        let entry = entry.map_err(|e| e.to_string())?;
-        let path = entry.path();
-        if path.is_file() && is_video_file(&path) {
-            let file_name = path.file_name().unwrap().to_str().unwrap();
-            let file_stem = path.file_stem().unwrap().to_str().unwrap(); // Get the file name without extension
-            let new_dir = current_dir.join(file_stem.replace(".", "_"));
-            fs::create_dir_all(&new_dir).map_err(|e| e.to_string())?;
-            fs::rename(&path, new_dir.join(file_name)).map_err(|e| e.to_string())?;
-        }
-    }
+    let plan = organize_videos_plan(&current_dir, &selection)?;
+    let report = apply_plan(plan, "Organize videos into directories")?;
 
     // Emit an event
     window
@@ -620,47 +689,20 @@ pub fn organize_videos_into_directories(
         )
         .unwrap();
 
-    Ok(())
+    Ok(report)
 }
 
 #[command]
 pub fn flatten_single_file_directories(
     state: State<'_, Arc<Mutex<FileExplorer>>>,
-    window: Window, // To emit events
-) -> Result<(), String> {
+    selection: Option<SelectionOptions>, // Only these files, every file without it
+    window: Window,                      // To emit events
+) -> Result<RenameReport, String> {
+    let selection = FileSelection::from_options(selection)?;
     let explorer = state.lock().map_err(|e| e.to_string())?;
     let current_dir = PathBuf::from(explorer.get_current_path());
-    let current_dir_clone = current_dir.clone();
-
-    for entry in fs::read_dir(current_dir).map_err(|e| e.to_string())? {
-        let entry = entry.map_err(|e| e.to_string())?;
-        let path = entry.path();
-        if path.is_dir() {
-            let mut video_files = vec![];
-            let mut file_count = 0;
-            for sub_entry in fs::read_dir(&path).map_err(|e| e.to_string())? {
-                let sub_entry = sub_entry.map_err(|e| e.to_string())?;
-                let sub_path = sub_entry.path();
-                if sub_path.is_file() {
-                    file_count += 1;
-                    if is_video_file(&sub_path) {
-                        video_files.push(sub_path);
-                    }
-                }
-                // Skip the directory if it contains more than one file
-                if file_count > 1 {
-                    break;
-                }
-            }
-            if file_count == 1 && video_files.len() == 1 {
-                let video_file = video_files.pop().unwrap();
-                let file_name = video_file.file_name().unwrap();
-                fs::rename(&video_file, current_dir_clone.join(file_name))
-                    .map_err(|e| e.to_string())?;
-                fs::remove_dir(&path).map_err(|e| e.to_string())?;
-            }
-        }
-    }
+    let plan = flatten_single_file_directories_plan(&current_dir, &selection)?;
+    let report = apply_plan(plan, "Flatten single file directories")?;
 
     // Emit an event
     window
@@ -670,7 +712,141 @@ pub fn flatten_single_file_directories(
         )
         .unwrap();
 
-    Ok(())
+    Ok(report)
 }
 
 // END ORGANIZE VIDEOS INTO DIRECTORIES
+
+// START ORGANIZE INTO SHOW FOLDERS
+
+// Moves episodes into `Show Name (Year)/Season 01/Show Name - S01E01 - Title.ext` below
+// the current folder, the layout Plex and Jellyfin expect. Without show details the show
+// name comes from the file names.
+#[command]
+pub fn organize_into_show_folders_preview(
+    state: State<'_, Arc<Mutex<FileExplorer>>>,
+    plan_store: State<'_, Arc<Mutex<PlanStore>>>,
+    show_details: Option<ShowDetailsWithEpisodes>, // Fetched name, year, titles and seasons
+    subfolder_depth: Option<usize>,                // Also moves files from subfolders
+    selection: Option<SelectionOptions>,           // Only these files, every file without it
+    window: Window,                                // To emit events
+) -> Result<String, String> {
+    let selection = FileSelection::from_options(selection)?;
+    let explorer = state.lock().unwrap();
+    let current_path = PathBuf::from(explorer.get_current_path());
+    let folders = folders_to_process(&current_path, subfolder_depth)?;
+
+    // Remember the directory contents the preview is based on
+    let snapshot = DirectorySnapshot::capture_all(&folders)?;
+
+    // Season boundaries turn absolute numbers like "Show - 37" into seasons
+    let boundaries = show_details
+        .as_ref()
+        .and_then(|details| SeasonBoundaries::from_seasons(&details.episodes_by_season).ok());
+
+    let plan = plan_for_folders(&folders, |folder| {
+        organize_into_show_folders_plan(
+            folder,
+            &current_path,
+            show_details.as_ref(),
+            boundaries.as_ref(),
+            &selection,
+        )
+    })
+    .map_err(|e| format!("Failed to build rename plan: {}", e))?;
+
+    store_and_emit_preview(
+        &plan_store,
+        &window,
+        "Organize into show folders",
+        current_path,
+        snapshot,
+        plan,
+    )
+}
+
+// END ORGANIZE INTO SHOW FOLDERS
+
+// START ORGANIZE MOVIES
+
+// Moves movies into `Title (Year)/Title (Year) {edition-Final Cut}.ext` below the current
+// folder. With a TMDB API key, the parsed title and year are confirmed or corrected by TMDB.
+#[command]
+pub async fn organize_movies_preview(
+    state: State<'_, Arc<Mutex<FileExplorer>>>,
+    plan_store: State<'_, Arc<Mutex<PlanStore>>>,
+    tmdb_api_key: Option<String>,        // Confirms titles and years
+    subfolder_depth: Option<usize>,      // Also moves movies from subfolders
+    selection: Option<SelectionOptions>, // Only these files, every file without it
+    window: Window,                      // To emit events
+) -> Result<String, String> {
+    let selection = FileSelection::from_options(selection)?;
+    let current_path = {
+        let explorer = state.lock().unwrap();
+        PathBuf::from(explorer.get_current_path())
+    };
+    let folders = folders_to_process(&current_path, subfolder_depth)?;
+
+    // Remember the directory contents the preview is based on
+    let snapshot = DirectorySnapshot::capture_all(&folders)?;
+
+    let parser = ReleaseNameParser::new();
+    let files =
+        movie_files(&folders, &selection).map_err(|e| format!("Failed to read movies: {}", e))?;
+
+    let confirmed = confirm_movies(&files, &parser, tmdb_api_key.as_deref()).await?;
+
+    let plan = organize_movies_plan(&files, &current_path, &parser, confirmed.as_ref())
+        .map_err(|e| format!("Failed to build rename plan: {}", e))?;
+
+    store_and_emit_preview(
+        &plan_store,
+        &window,
+        "Organize movies",
+        current_path,
+        snapshot,
+        plan,
+    )
+}
+
+// END ORGANIZE MOVIES
+
+// START FLATTEN DIRECTORIES
+
+// Moves videos and the files chosen by the options out of the subfolders of the current
+// folder and removes the folders that end up empty
+#[command]
+pub fn flatten_directories_preview(
+    state: State<'_, Arc<Mutex<FileExplorer>>>,
+    plan_store: State<'_, Arc<Mutex<PlanStore>>>,
+    options: FlattenOptions, // Depth, what happens to other files and to name conflicts
+    selection: Option<SelectionOptions>, // Only these files, every file without it
+    window: Window,          // To emit events
+) -> Result<String, String> {
+    let selection = FileSelection::from_options(selection)?;
+    if options.depth == 0 {
+        return Err("The depth has to be at least 1.".to_string());
+    }
+
+    let explorer = state.lock().unwrap();
+    let current_path = PathBuf::from(explorer.get_current_path());
+    let folders = collect_folders(&current_path, options.depth)
+        .map_err(|e| format!("Failed to list subfolders: {}", e))?;
+
+    // Remember the directory contents the preview is based on
+    let snapshot = DirectorySnapshot::capture_all(&folders)?;
+
+    let plan = flatten_plan(&current_path, &options, &selection)
+        .map_err(|e| format!("Failed to build rename plan: {}", e))?;
+
+    store_and_emit_preview(
+        &plan_store,
+        &window,
+        "Flatten directories",
+        current_path,
+        snapshot,
+        plan,
+    )
+}
+
+// END FLATTEN DIRECTORIES
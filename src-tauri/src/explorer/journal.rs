@@ -0,0 +1,27 @@
+use script_kiddie_core::explorer::journal;
+use script_kiddie_core::explorer::rename_plan::RenameReport;
+use tauri::{command, Window};
+
+// UNDO / REDO
+
+#[command]
+pub fn undo_last_operation(window: Window) -> Result<RenameReport, String> {
+    let (label, report) = journal::undo_last_operation()?;
+
+    window
+        .emit("trigger-reload", format!("Undone: {}", label))
+        .map_err(|e| format!("Failed to emit event: {:?}", e))?;
+
+    Ok(report)
+}
+
+#[command]
+pub fn redo_operation(window: Window) -> Result<RenameReport, String> {
+    let (label, report) = journal::redo_operation()?;
+
+    window
+        .emit("trigger-reload", format!("Redone: {}", label))
+        .map_err(|e| format!("Failed to emit event: {:?}", e))?;
+
+    Ok(report)
+}
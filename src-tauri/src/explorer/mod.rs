@@ -1,4 +1,6 @@
 pub mod file_explorer;
 pub mod file_operations;
+pub mod journal;
+pub mod plan_store;
+pub mod printer;
 pub mod utils;
-pub mod printer;
\ No newline at end of file
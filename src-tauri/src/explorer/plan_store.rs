@@ -0,0 +1,103 @@
+use script_kiddie_core::explorer::rename_plan::RenamePlan;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+// Only the most recent previews are kept, older plan IDs expire
+const MAX_STORED_PLANS: usize = 20;
+
+// STRUCTS
+
+// Path, size and modification time of every entry in one or more directories
+#[derive(Debug, Clone, PartialEq)]
+pub struct DirectorySnapshot {
+    directories: Vec<PathBuf>,
+    entries: Vec<(PathBuf, u64, u128)>,
+}
+
+impl DirectorySnapshot {
+    // Snapshot of several folders, e.g. a show folder and its season folders
+    pub fn capture_all(directories: &[PathBuf]) -> Result<Self, String> {
+        let mut entries = Vec::new();
+        for directory in directories {
+            capture_entries(directory, &mut entries)?;
+        }
+        entries.sort();
+
+        Ok(Self {
+            directories: directories.to_vec(),
+            entries,
+        })
+    }
+}
+
+fn capture_entries(
+    directory: &Path,
+    entries: &mut Vec<(PathBuf, u64, u128)>,
+) -> Result<(), String> {
+    for entry in fs::read_dir(directory).map_err(|e| format!("Failed to read directory: {}", e))? {
+        let entry = entry.map_err(|e| format!("Failed to read entry: {}", e))?;
+        let metadata = entry
+            .metadata()
+            .map_err(|e| format!("Failed to read metadata: {}", e))?;
+        let modified = metadata
+            .modified()
+            .ok()
+            .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+            .map(|duration| duration.as_nanos())
+            .unwrap_or(0);
+
+        entries.push((entry.path(), metadata.len(), modified));
+    }
+
+    Ok(())
+}
+
+// An immutable plan created by a preview command, waiting to be applied
+#[derive(Debug, Clone)]
+pub struct StoredPlan {
+    pub label: String,
+    pub directory: PathBuf,
+    pub snapshot: DirectorySnapshot,
+    pub plan: RenamePlan,
+}
+
+impl StoredPlan {
+    // Rejects the plan if files were added, removed or modified since the preview
+    pub fn ensure_directory_unchanged(&self) -> Result<(), String> {
+        let current = DirectorySnapshot::capture_all(&self.snapshot.directories)?;
+        if current != self.snapshot {
+            return Err(format!(
+                "The contents of {} changed since the preview. Please preview again.",
+                self.directory.display()
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct PlanStore {
+    plans: Vec<(String, StoredPlan)>,
+}
+
+impl PlanStore {
+    // Stores the plan and returns its ID
+    pub fn insert(&mut self, plan: StoredPlan) -> String {
+        let plan_id = uuid::Uuid::new_v4().to_string();
+        self.plans.push((plan_id.clone(), plan));
+
+        if self.plans.len() > MAX_STORED_PLANS {
+            let overflow = self.plans.len() - MAX_STORED_PLANS;
+            self.plans.drain(..overflow);
+        }
+
+        plan_id
+    }
+
+    // Removes the plan, every plan can only be applied once
+    pub fn take(&mut self, plan_id: &str) -> Option<StoredPlan> {
+        let index = self.plans.iter().position(|(id, _)| id == plan_id)?;
+        Some(self.plans.remove(index).1)
+    }
+}
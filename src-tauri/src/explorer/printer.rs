@@ -1,13 +1,15 @@
-use crate::explorer::file_explorer::{is_video_file, FileExplorer, FileInfo};
+use crate::explorer::file_explorer::{FileExplorer, FileInfo};
 use crate::{AppState, WindowState};
-use sanitize_filename::sanitize;
-use std::fs::{self, File};
-use std::future::Future;
+use script_kiddie_core::explorer::duplicates::{
+    delete_duplicates, find_duplicates, DuplicatePhase, DuplicateReport,
+};
+use script_kiddie_core::explorer::media_files::{format_size, list_file_sizes, list_media_files};
+use std::fs::File;
 use std::io::Write; // Bring the Write trait into scope
-use std::path::{Path, PathBuf};
-use std::pin::Pin;
+use std::path::PathBuf;
 use std::sync::{Arc, Mutex};
-use tauri::{command, AppHandle, Manager, State, WindowBuilder, WindowUrl};
+use std::time::{Duration, Instant};
+use tauri::{command, AppHandle, Manager, State, Window, WindowBuilder, WindowUrl};
 use tokio::sync::Mutex as AsyncMutex;
 use tokio_util::sync::CancellationToken;
 
@@ -114,13 +116,13 @@ pub async fn print_media_files_in_directories(
         current_dir = PathBuf::from(explorer.get_current_path());
     } // MutexGuard is dropped here
 
-    let mut media_files = vec![];
     let token = {
         let app_state = app_state.lock().await;
         app_state.cancellation_token.clone().unwrap()
     };
 
-    get_media_files_recursive(&current_dir, &mut media_files, token.clone()).await?;
+    // The window only lists the files, unreadable folders are left out of it
+    let media_files = list_media_files(&current_dir, &token)?.files;
 
     // Get the current directory name with a fallback and add the prefix "media_files_"
     let current_dir_name = current_dir
@@ -140,75 +142,8 @@ pub async fn print_media_files_in_directories(
     Ok(media_files)
 }
 
-fn get_media_files_recursive<'a>(
-    dir: &'a Path,
-    media_files: &'a mut Vec<FileInfo>,
-    token: CancellationToken,
-) -> Pin<Box<dyn Future<Output = Result<(), String>> + Send + 'a>> {
-    Box::pin(async move {
-        if token.is_cancelled() {
-            return Err("Operation cancelled".into());
-        }
-
-        match fs::read_dir(dir) {
-            Ok(entries) => {
-                for entry in entries {
-                    if token.is_cancelled() {
-                        return Err("Operation cancelled".into());
-                    }
-
-                    match entry {
-                        Ok(entry) => {
-                            let path = entry.path();
-                            if path.is_dir() {
-                                if let Err(e) =
-                                    get_media_files_recursive(&path, media_files, token.clone())
-                                        .await
-                                {
-                                    eprintln!("Failed to read directory {}: {}", path.display(), e);
-                                }
-                            } else if path.is_file() {
-                                let is_video = is_video_file(&path);
-                                if is_video {
-                                    let file_name =
-                                        path.file_name().unwrap().to_string_lossy().to_string();
-                                    let sanitized_name = sanitize(&file_name);
-
-                                    media_files.push(FileInfo {
-                                        path: path.to_string_lossy().to_string(),
-                                        is_dir: false,
-                                        is_video,
-                                        name: sanitized_name,
-                                    });
-                                }
-                            }
-                        }
-                        Err(e) => {
-                            eprintln!("Failed to read entry in directory {}: {}", dir.display(), e);
-                        }
-                    }
-                }
-            }
-            Err(e) => {
-                eprintln!("Failed to read directory {}: {}", dir.display(), e);
-            }
-        }
-        Ok(())
-    })
-}
-
 // Print file sizes
 
-fn format_size(size: u64) -> String {
-    if size >= 1_073_741_824 {
-        format!("{:.2} GB", size as f64 / 1_073_741_824.0)
-    } else if size >= 1_048_576 {
-        format!("{:.2} MB", size as f64 / 1_048_576.0)
-    } else {
-        format!("{:.2} KB", size as f64 / 1_024.0)
-    }
-}
-
 #[command]
 pub async fn print_file_sizes(
     state: State<'_, Arc<Mutex<FileExplorer>>>,
@@ -236,29 +171,7 @@ pub async fn print_file_sizes(
         app_state.cancellation_token.clone().unwrap()
     };
 
-    let mut file_info_list = vec![];
-    let entries = fs::read_dir(&current_dir).map_err(|e| e.to_string())?;
-    for entry in entries {
-        let entry = entry.map_err(|e| e.to_string())?;
-        let path = entry.path();
-        let metadata = fs::metadata(&path).map_err(|e| e.to_string())?;
-        let size = if metadata.is_dir() {
-            get_directory_size(&path, token.clone())?
-        } else {
-            metadata.len()
-        };
-        let name = path.file_name().unwrap().to_string_lossy().to_string();
-        let file_info = FileInfo {
-            path: path.to_string_lossy().to_string(),
-            is_dir: path.is_dir(),
-            is_video: is_video_file(&path),
-            name,
-        };
-        file_info_list.push((file_info, size));
-    }
-
-    // Sort the file_info_list by size
-    file_info_list.sort_by(|a, b| b.1.cmp(&a.1));
+    let file_info_list = list_file_sizes(&current_dir, &token)?;
 
     // Create a new list of FileInfo with size appended to the name
     let sorted_file_info_list: Vec<FileInfo> = file_info_list
@@ -293,23 +206,91 @@ pub async fn print_file_sizes(
     Ok(sorted_file_info_list)
 }
 
-fn get_directory_size(path: &PathBuf, token: CancellationToken) -> Result<u64, String> {
-    if token.is_cancelled() {
-        return Err("Operation cancelled".into());
-    }
+// Find duplicate files
+
+// Emitting every file would flood the frontend
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(100);
+
+#[command]
+pub async fn find_duplicate_files(
+    state: State<'_, Arc<Mutex<FileExplorer>>>,
+    app_state: State<'_, Arc<AsyncMutex<AppState>>>,
+    window: Window, // To emit progress events
+) -> Result<DuplicateReport, String> {
+    // Cancel the previous task if it exists
+    let token = {
+        let mut app_state = app_state.lock().await;
+        if let Some(token) = &app_state.cancellation_token {
+            token.cancel();
+        }
+        let token = CancellationToken::new();
+        app_state.cancellation_token = Some(token.clone());
+        app_state.duplicate_report = None;
+        token
+    };
 
-    let mut total_size = 0;
-    if let Ok(entries) = fs::read_dir(path) {
-        for entry in entries {
-            if let Ok(entry) = entry {
-                let metadata = fs::metadata(entry.path()).unwrap();
-                if metadata.is_dir() {
-                    total_size += get_directory_size(&entry.path(), token.clone())?;
-                } else {
-                    total_size += metadata.len();
-                }
+    let current_dir;
+    {
+        let explorer = state.lock().map_err(|e| e.to_string())?;
+        current_dir = PathBuf::from(explorer.get_current_path());
+    } // MutexGuard is dropped here
+
+    // Hashing blocks, so it runs outside of the async runtime
+    let report = tokio::task::spawn_blocking(move || {
+        let mut last_emit: Option<(DuplicatePhase, Instant)> = None;
+        find_duplicates(&current_dir, &token, |progress| {
+            let is_due = last_emit.map_or(true, |(phase, at)| {
+                phase != progress.phase || at.elapsed() >= PROGRESS_INTERVAL
+            });
+            if is_due {
+                let _ = window.emit("duplicate_progress", &progress);
+                last_emit = Some((progress.phase, Instant::now()));
             }
+        })
+    })
+    .await
+    .map_err(|e| e.to_string())??;
+
+    app_state.lock().await.duplicate_report = Some(report.clone());
+    Ok(report)
+}
+
+// Deletes the chosen files of the last search, the only way the duplicate finder deletes anything
+#[command]
+pub async fn delete_duplicate_files(
+    app_state: State<'_, Arc<AsyncMutex<AppState>>>,
+    paths: Vec<String>,
+    window: Window, // To emit events
+) -> Result<DuplicateReport, String> {
+    // Every file is hashed again before it is deleted, which can be cancelled like the search
+    let (report, token) = {
+        let mut app_state = app_state.lock().await;
+        let report = app_state
+            .duplicate_report
+            .clone()
+            .ok_or("Search for duplicates first")?;
+        if let Some(token) = &app_state.cancellation_token {
+            token.cancel();
         }
-    }
-    Ok(total_size)
+        let token = CancellationToken::new();
+        app_state.cancellation_token = Some(token.clone());
+        (report, token)
+    };
+    let paths: Vec<PathBuf> = paths.into_iter().map(PathBuf::from).collect();
+
+    let checked_report = report.clone();
+    let deleted =
+        tokio::task::spawn_blocking(move || delete_duplicates(&checked_report, &paths, &token))
+            .await
+            .map_err(|e| e.to_string())??;
+
+    let mut report = report;
+    report.remove_paths(&deleted);
+    app_state.lock().await.duplicate_report = Some(report.clone());
+
+    window
+        .emit("trigger-reload", "Duplicate files deleted successfully")
+        .unwrap();
+
+    Ok(report)
 }
@@ -16,12 +16,20 @@ use explorer::file_explorer::{
     select_and_set_current_path, FileExplorer,
 };
 use explorer::file_operations::{
-    add_titles_to_episodes, add_titles_to_episodes_preview, adjust_episode_numbers,
-    adjust_episode_numbers_preview, flatten_single_file_directories, get_current_episode_names,
-    organize_videos_into_directories, search_and_replace, search_and_replace_preview,
+    add_titles_to_episodes_preview, adjust_episode_numbers_preview, adjust_season_numbers_preview,
+    apply_rename_plan, clean_release_names_preview, convert_absolute_numbering_preview,
+    fetch_and_apply_titles_preview, flatten_directories_preview, flatten_single_file_directories,
+    get_cleanup_rules, get_current_episode_names, get_sanitize_profile, number_files_preview,
+    organize_into_show_folders_preview, organize_movies_preview, organize_videos_into_directories,
+    remap_episode_range_preview, rename_with_template_preview, search_and_replace_preview,
+    set_sanitize_profile, transform_file_names_preview, update_cleanup_rules,
+    validate_filename_template,
 };
-use explorer::printer::{print_file_sizes, print_media_files_in_directories, cancel_file_printer, save_file_to_folder};
+use explorer::journal::{redo_operation, undo_last_operation};
+use explorer::plan_store::PlanStore;
+use explorer::printer::{print_file_sizes, print_media_files_in_directories, cancel_file_printer, save_file_to_folder, find_duplicate_files, delete_duplicate_files};
 use explorer::utils::{list_drives, list_files_in_home_directory};
+use script_kiddie_core::explorer::duplicates::DuplicateReport;
 use tokio::sync::Mutex as AsyncMutex;
 use tokio_util::sync::CancellationToken;
 use utils::qr_code_generator::{generate_qr_code, save_qr_code_as_png, save_qr_code_as_svg};
@@ -34,6 +42,7 @@ use tauri::{generate_handler, Builder, Manager};
 #[derive(Default)]
 pub struct AppState {
     cancellation_token: Option<CancellationToken>,
+    duplicate_report: Option<DuplicateReport>, // The last search, files can only be deleted from it
 }
 
 #[derive(Default)]
@@ -48,10 +57,12 @@ fn main() {
             let explorer = Arc::new(Mutex::new(FileExplorer::new(app_handle)));
             let app_state = Arc::new(AsyncMutex::new(AppState::default()));
             let window_state = Arc::new(Mutex::new(WindowState::default()));
+            let plan_store = Arc::new(Mutex::new(PlanStore::default()));
 
             app.manage(explorer);
             app.manage(app_state);
             app.manage(window_state);
+            app.manage(plan_store);
 
             Ok(())
         })
@@ -66,15 +77,31 @@ fn main() {
             open_in_file_explorer,
             open_in_terminal,
             select_and_set_current_path,
-            add_titles_to_episodes,
             add_titles_to_episodes_preview,
-            adjust_episode_numbers,
+            fetch_and_apply_titles_preview,
             adjust_episode_numbers_preview,
+            adjust_season_numbers_preview,
+            remap_episode_range_preview,
+            convert_absolute_numbering_preview,
+            get_cleanup_rules,
+            update_cleanup_rules,
+            clean_release_names_preview,
+            rename_with_template_preview,
+            validate_filename_template,
+            apply_rename_plan,
+            flatten_directories_preview,
             flatten_single_file_directories,
             get_current_episode_names,
             organize_videos_into_directories,
-            search_and_replace,
+            organize_into_show_folders_preview,
+            organize_movies_preview,
             search_and_replace_preview,
+            transform_file_names_preview,
+            get_sanitize_profile,
+            set_sanitize_profile,
+            number_files_preview,
+            undo_last_operation,
+            redo_operation,
             generate_qr_code,
             save_qr_code_as_png,
             save_qr_code_as_svg,
@@ -88,6 +115,8 @@ fn main() {
             print_file_sizes,
             cancel_file_printer,
             save_file_to_folder,
+            find_duplicate_files,
+            delete_duplicate_files,
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
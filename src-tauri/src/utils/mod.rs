@@ -1,2 +1,2 @@
+pub mod qr_code_generator;
 pub mod utils;
-pub mod qr_code_generator;
\ No newline at end of file